@@ -1,4 +1,4 @@
-use ra2a::agent::{A2AAgentError, AgentBuilder, AgentHandler};
+use ra2a::agent::{A2AAgentError, AgentBuilder, AgentHandler, RequestContext};
 use ra2a::core::message::{Message, SendMessageResponsePayload};
 use ra2a::core::task::Task;
 use ra2a::core::util::Object;
@@ -18,6 +18,7 @@ impl MyAgentHandler {
 impl AgentHandler for MyAgentHandler {
     async fn handle_message(
         &self,
+        _context: &RequestContext,
         message: Message,
         _metadata: Option<Object>,
         _task: Task,
@@ -1,22 +1,77 @@
-use crate::agent::AgentHandler;
+use crate::agent::{
+    AgentHandler, Middleware, Next, RemoteAddr, RequestContext, SkillAuthFailure, SkillRouter,
+};
+use crate::auth::{AuthError, Authentication, Authorizer, OwnerOnlyAuthorizer, TaskAction};
+use crate::core::agent::AgentCard;
+use crate::core::delegation::DelegationChain;
 use crate::core::message::{
-    SendMessageConfiguration, SendMessageRequest, SendMessageResponse, SendMessageResponsePayload,
+    Message, SendMessageConfiguration, SendMessageRequest, SendMessageResponse,
+    SendMessageResponsePayload,
 };
+use crate::core::part::{Part, PartBase};
+use crate::core::push_notification::PushNotificationConfig;
+use crate::core::role::Role;
 use crate::core::task::{GetTaskRequest, Task, TaskState, TaskStatus};
-use crate::core::{A2AError, A2AProtocolError, A2ATransportError, A2A};
-use crate::queue::bounded::BoundedTaskQueue;
+use crate::core::util::Object;
+use crate::core::{
+    A2A, A2AError, A2AProtocolError, A2ARequest, A2AResponse, A2ATransportError, SKILL_METADATA_KEY,
+};
+use crate::limit::RateLimiter;
+use crate::push::PushNotifier;
 use crate::queue::TaskQueue;
-use crate::store::memory::InMemoryTaskStore;
+use crate::queue::bounded::BoundedTaskQueue;
 use crate::store::TaskStore;
+use crate::store::memory::InMemoryTaskStore;
+use std::collections::{HashMap, VecDeque};
 use std::fmt::Debug;
 use std::sync::Arc;
+use tokio::sync::Mutex;
 use uuid::Uuid;
 
+/// Default number of times a request may be delegated between agents before it is rejected.
+pub const DEFAULT_MAX_DELEGATION_DEPTH: usize = 8;
+
+/// Responses kept to answer retried messages, see [`SentMessages`].
+const MAX_SENT_MESSAGES: usize = 10_000;
+
+type ResponseSlot = Arc<Mutex<Option<SendMessageResponse>>>;
+
 #[derive(Clone)]
 pub struct A2ADelegate {
-    agent: Arc<dyn AgentHandler>,
+    name: Option<String>,
+    max_delegation_depth: usize,
+    authentication: Option<Authentication>,
+    authorizer: Arc<dyn Authorizer>,
+    skill_router: Option<Arc<dyn SkillRouter>>,
+    skill_auth_failure: SkillAuthFailure,
+    card: Option<Arc<AgentCard>>,
+    extended_card: Option<Arc<AgentCard>>,
+    rate_limiter: Option<RateLimiter>,
+    push_notifier: Option<PushNotifier>,
+    middlewares: Vec<Arc<dyn Middleware>>,
+    upstream: Upstream,
     store: Arc<dyn TaskStore>,
     queue: Arc<dyn TaskQueue>,
+    sent_messages: Arc<Mutex<SentMessages>>,
+}
+
+/// Responses to the latest messages carrying a client message id, oldest first. A message
+/// sent again by the same caller with the same id, such as a retry after a timeout, is
+/// answered with the response of the first one instead of being handled again.
+#[derive(Debug, Default)]
+struct SentMessages {
+    // (caller, message id) -> response, locked on its own while the message is handled
+    responses: HashMap<(String, String), ResponseSlot>,
+    order: VecDeque<(String, String)>,
+}
+
+/// Where the delegate sends the work it receives.
+#[derive(Clone)]
+enum Upstream {
+    /// A local handler, with tasks persisted in the delegate's store.
+    Handler(Arc<dyn AgentHandler>),
+    /// Another A2A implementation that owns its own tasks, e.g. a gateway in front of remote agents.
+    Forward(Arc<dyn A2A + Send + Sync>),
 }
 
 impl Debug for A2ADelegate {
@@ -25,19 +80,195 @@ impl Debug for A2ADelegate {
     }
 }
 
+/// Calls through the trait run with [`RequestContext::default()`], as a trusted anonymous
+/// caller: they are neither authenticated nor rate limited, tasks they create get no
+/// owner, and the skill a message targets is checked against no credentials. Serve
+/// untrusted callers through a transport, or pass the context
+/// [`A2ADelegate::request_context`] resolves to the `*_with_context` methods.
 #[async_trait::async_trait]
 impl A2A for A2ADelegate {
     async fn send_message(
         &self,
         request: SendMessageRequest,
+    ) -> Result<SendMessageResponse, A2AError> {
+        self.send_message_with_context(&RequestContext::default(), request)
+            .await
+    }
+
+    async fn get_task(&self, request: GetTaskRequest) -> Result<Task, A2AError> {
+        self.get_task_with_context(&RequestContext::default(), request)
+            .await
+    }
+
+    async fn get_authenticated_extended_card(&self) -> Result<AgentCard, A2AError> {
+        self.get_authenticated_extended_card_with_context(&RequestContext::default())
+            .await
+    }
+}
+
+impl A2ADelegate {
+    pub fn new<T: AgentHandler + 'static>(agent: Arc<T>) -> Self {
+        A2ADelegate {
+            name: None,
+            max_delegation_depth: DEFAULT_MAX_DELEGATION_DEPTH,
+            authentication: None,
+            authorizer: Arc::new(OwnerOnlyAuthorizer),
+            skill_router: None,
+            skill_auth_failure: SkillAuthFailure::default(),
+            card: None,
+            extended_card: None,
+            rate_limiter: None,
+            push_notifier: None,
+            middlewares: vec![],
+            upstream: Upstream::Handler(agent),
+            store: Arc::new(InMemoryTaskStore::default()),
+            queue: Arc::new(BoundedTaskQueue::new(10)),
+            sent_messages: Arc::new(Mutex::new(SentMessages::default())),
+        }
+    }
+
+    /// Creates a delegate that forwards every call to `a2a`, which is responsible for its own tasks.
+    /// The delegate still records who created each task and authorizes access to it.
+    pub fn forwarding<T: A2A + Send + Sync + 'static>(a2a: Arc<T>) -> Self {
+        A2ADelegate {
+            name: None,
+            max_delegation_depth: DEFAULT_MAX_DELEGATION_DEPTH,
+            authentication: None,
+            authorizer: Arc::new(OwnerOnlyAuthorizer),
+            skill_router: None,
+            skill_auth_failure: SkillAuthFailure::default(),
+            card: None,
+            extended_card: None,
+            rate_limiter: None,
+            push_notifier: None,
+            middlewares: vec![],
+            upstream: Upstream::Forward(a2a),
+            store: Arc::new(InMemoryTaskStore::default()),
+            queue: Arc::new(BoundedTaskQueue::new(10)),
+            sent_messages: Arc::new(Mutex::new(SentMessages::default())),
+        }
+    }
+
+    /// Handles a message received by a server, with the context of the transport request
+    /// it arrived with. In-process callers go through [`A2A::send_message`] instead.
+    pub async fn send_message_with_context(
+        &self,
+        context: &RequestContext,
+        request: SendMessageRequest,
+    ) -> Result<SendMessageResponse, A2AError> {
+        self.call(context, A2ARequest::SendMessage(Box::new(request)))
+            .await?
+            .into_send_message()
+    }
+
+    /// Fetches a task for a server request, see [`A2ADelegate::send_message_with_context`].
+    pub async fn get_task_with_context(
+        &self,
+        context: &RequestContext,
+        request: GetTaskRequest,
+    ) -> Result<Task, A2AError> {
+        self.call(context, A2ARequest::GetTask(request))
+            .await?
+            .into_task()
+    }
+
+    /// Returns the extended card to authenticated callers, see
+    /// [`A2ADelegate::send_message_with_context`].
+    pub async fn get_authenticated_extended_card_with_context(
+        &self,
+        context: &RequestContext,
+    ) -> Result<AgentCard, A2AError> {
+        self.call(context, A2ARequest::GetAuthenticatedExtendedCard)
+            .await?
+            .into_agent_card()
+    }
+
+    /// Runs a call through the middleware stack.
+    async fn call(
+        &self,
+        context: &RequestContext,
+        request: A2ARequest,
+    ) -> Result<A2AResponse, A2AError> {
+        Next {
+            delegate: self,
+            middlewares: &self.middlewares,
+        }
+        .run(context, request)
+        .await
+    }
+
+    /// Handles a call that made it through the middleware stack.
+    async fn dispatch(
+        &self,
+        context: &RequestContext,
+        request: A2ARequest,
+    ) -> Result<A2AResponse, A2AError> {
+        match request {
+            A2ARequest::SendMessage(request) => self
+                .send_message_once(context, *request)
+                .await
+                .map(A2AResponse::SendMessage),
+            A2ARequest::GetTask(request) => self
+                .get_task_inner(context, request)
+                .await
+                .map(A2AResponse::GetTask),
+            A2ARequest::GetAuthenticatedExtendedCard => self
+                .get_authenticated_extended_card_inner(context)
+                .map(A2AResponse::GetAuthenticatedExtendedCard),
+        }
+    }
+
+    /// Handles a message unless the caller already sent one with the same client message
+    /// id, answering with the response to that one. Messages that failed are handled again.
+    async fn send_message_once(
+        &self,
+        context: &RequestContext,
+        request: SendMessageRequest,
+    ) -> Result<SendMessageResponse, A2AError> {
+        let message_id = match &request.message {
+            Some(message) if !message.message_id.is_empty() => message.message_id.clone(),
+            _ => return self.send_message_inner(context, request).await,
+        };
+        let caller = context
+            .principal
+            .as_ref()
+            .map(|p| p.subject.clone())
+            .unwrap_or_default();
+        let slot = self.sent_messages.lock().await.slot(caller, message_id);
+        // held while the message is handled, so a retry waits for the first attempt
+        let mut slot = slot.lock().await;
+        if let Some(response) = slot.as_ref() {
+            tracing::debug!("answering a message sent again");
+            return Ok(response.clone());
+        }
+        let response = self.send_message_inner(context, request).await?;
+        *slot = Some(response.clone());
+        Ok(response)
+    }
+
+    async fn send_message_inner(
+        &self,
+        context: &RequestContext,
+        request: SendMessageRequest,
     ) -> Result<SendMessageResponse, A2AError> {
         tracing::debug!(request = ?request, "send_message");
+        let chain = self.enter_delegation(&request)?;
+        let agent = match &self.upstream {
+            Upstream::Handler(agent) => agent,
+            Upstream::Forward(a2a) => {
+                return chain
+                    .scope(self.forward_message(context, a2a.as_ref(), request))
+                    .await;
+            }
+        };
         let mut message = match request.message {
             Some(message) => message,
             None => return Err(A2AError::Transport(A2ATransportError::MissingPayload)),
         };
-        // ensures server owns the message_id
-        message.message_id = Uuid::new_v4().to_string();
+        // messages the client did not name get an id of the server
+        if message.message_id.is_empty() {
+            message.message_id = Uuid::new_v4().to_string();
+        }
         // todo context ids should be validated to ensure user doesn't put wierd stuff in them
         // todo actually all things should be validated
 
@@ -50,30 +281,62 @@ impl A2A for A2ADelegate {
                 history_length: 0,
                 blocking: true,
             });
+        let push = configuration.push_notification.as_ref();
+        if let Some(config) = push {
+            match &self.push_notifier {
+                Some(notifier) => notifier.check(config)?,
+                None => return Err(A2AProtocolError::push_notification_not_supported().into()),
+            }
+        }
 
+        let is_new = message.task_id.is_none();
         let mut task = match &message.task_id {
-            Some(task_id) => match self.store.fetch(task_id).await {
-                Ok(Some(task)) => task,
-                Ok(None) => {
-                    return Err(A2AError::Protocol(A2AProtocolError::task_not_found(
-                        task_id.clone(),
-                    )));
-                }
-                Err(e) => return Err(e.into()),
-            },
+            Some(task_id) => {
+                self.authorized_task(context, task_id, TaskAction::SendMessage)
+                    .await?
+            }
             None => Task::new(),
         };
 
+        let skill = match self.authentication.is_some() || self.rate_limiter.is_some() {
+            true => {
+                self.target_skill(&message, request.metadata.as_ref())
+                    .await?
+            }
+            false => None,
+        };
+        if let Err(e) = self.authorize_skill(context, skill.as_deref()) {
+            tracing::debug!(error = %e, "skill authorization failed");
+            let task = match self.skill_auth_failure {
+                SkillAuthFailure::Error => return Err(e.into()),
+                SkillAuthFailure::AuthRequired => {
+                    self.auth_required(context, task, message, is_new, e)
+                        .await?
+                }
+            };
+            return Ok(SendMessageResponse {
+                payload: Some(SendMessageResponsePayload::Task(task)),
+            });
+        }
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter
+                .check_message(context, skill.as_deref(), is_new)
+                .await?;
+        }
+
         let payload = match configuration.blocking {
             true => {
-                let payload = self
-                    .agent
-                    .handle_message(message, request.metadata, task)
+                let payload = chain
+                    .scope(agent.handle_message(context, message, request.metadata, task))
                     .await?;
                 match &payload {
                     SendMessageResponsePayload::Task(task) => {
                         // persist the task, audit, etc
                         self.store.upsert(task.clone()).await?;
+                        if is_new {
+                            self.claim(context, &task.id).await?;
+                        }
+                        self.notify(push, task);
                     }
                     SendMessageResponsePayload::Message(_message) => {
                         // no task, audit the message
@@ -88,7 +351,11 @@ impl A2A for A2ADelegate {
                     timestamp: None,
                 });
                 let task = self.store.upsert(task).await?;
+                if is_new {
+                    self.claim(context, &task.id).await?;
+                }
                 self.queue.push(task.clone()).await?;
+                self.notify(push, &task);
                 SendMessageResponsePayload::Task(task)
             }
         };
@@ -97,23 +364,379 @@ impl A2A for A2ADelegate {
         })
     }
 
-    async fn get_task(&self, request: GetTaskRequest) -> Result<Task, A2AError> {
-        let task = self.store.fetch(&request.id).await?;
-        match task {
-            Some(task) => Ok(task),
-            None => Err(A2AError::Protocol(A2AProtocolError::task_not_found(
-                request.id,
-            ))),
+    async fn get_task_inner(
+        &self,
+        context: &RequestContext,
+        request: GetTaskRequest,
+    ) -> Result<Task, A2AError> {
+        if let Upstream::Forward(a2a) = &self.upstream {
+            let task = a2a.get_task(request).await?;
+            return self.authorize_task(context, task, TaskAction::Get).await;
+        }
+        self.authorized_task(context, &request.id, TaskAction::Get)
+            .await
+    }
+
+    /// Forwards a message, checking follow-ups against the owner of their task and
+    /// recording the caller as the owner of new tasks.
+    async fn forward_message(
+        &self,
+        context: &RequestContext,
+        a2a: &(dyn A2A + Send + Sync),
+        request: SendMessageRequest,
+    ) -> Result<SendMessageResponse, A2AError> {
+        let task_id = request.message.as_ref().and_then(|m| m.task_id.clone());
+        if let Some(task_id) = &task_id {
+            let task = a2a
+                .get_task(GetTaskRequest {
+                    id: task_id.clone(),
+                    history_length: None,
+                    metadata: None,
+                })
+                .await?;
+            self.authorize_task(context, task, TaskAction::SendMessage)
+                .await?;
+        }
+        let response = a2a.send_message(request).await?;
+        if let (None, Some(SendMessageResponsePayload::Task(task))) = (&task_id, &response.payload)
+        {
+            self.claim(context, &task.id).await?;
+        }
+        Ok(response)
+    }
+
+    fn get_authenticated_extended_card_inner(
+        &self,
+        context: &RequestContext,
+    ) -> Result<AgentCard, A2AError> {
+        let Some(card) = &self.extended_card else {
+            return Err(A2AProtocolError::authenticated_extended_card_not_configured().into());
+        };
+        if context.principal.is_none() {
+            return Err(AuthError::MissingCredentials.into());
+        }
+        Ok(card.as_ref().clone())
+    }
+
+    /// Fetches a task the caller is allowed to act on. Tasks the caller may not access
+    /// are reported as not found so that their ids do not leak.
+    async fn authorized_task(
+        &self,
+        context: &RequestContext,
+        task_id: &str,
+        action: TaskAction,
+    ) -> Result<Task, A2AError> {
+        let task = self
+            .store
+            .fetch(task_id)
+            .await?
+            .ok_or_else(|| A2AProtocolError::task_not_found(task_id.to_string()))?;
+        self.authorize_task(context, task, action).await
+    }
+
+    /// Checks the caller may act on `task`, reporting it as not found otherwise.
+    async fn authorize_task(
+        &self,
+        context: &RequestContext,
+        task: Task,
+        action: TaskAction,
+    ) -> Result<Task, A2AError> {
+        let owner = self.store.owner(&task.id).await?;
+        let principal = context.principal.as_ref();
+        if !self
+            .authorizer
+            .authorize(principal, action, &task, owner.as_deref())
+            .await
+        {
+            tracing::debug!(task_id = task.id, ?action, "task access denied");
+            return Err(A2AProtocolError::task_not_found(task.id).into());
+        }
+        Ok(task)
+    }
+
+    /// The card skill a message targets, chosen by the skill router or otherwise named in
+    /// the request or message metadata. A named skill must be declared by the card and
+    /// agree with the router, so that callers cannot pick a skill with weaker security.
+    async fn target_skill(
+        &self,
+        message: &Message,
+        metadata: Option<&Object>,
+    ) -> Result<Option<String>, A2AProtocolError> {
+        let named = [metadata, message.metadata.as_ref()]
+            .into_iter()
+            .flatten()
+            .find_map(|metadata| metadata.get_str(SKILL_METADATA_KEY));
+        if let (Some(skill), Some(card)) = (named, &self.card)
+            && !card.skills.iter().any(|s| s.id == skill)
+        {
+            return Err(A2AProtocolError::invalid_skill(skill.to_string()));
+        }
+        let routed = match &self.skill_router {
+            Some(router) => router.route(message, metadata).await,
+            None => None,
+        };
+        match (named, routed) {
+            (Some(named), Some(routed)) if named != routed => {
+                tracing::debug!(named, routed, "message names another skill than routed");
+                Err(A2AProtocolError::invalid_skill(named.to_string()))
+            }
+            (_, Some(routed)) => Ok(Some(routed)),
+            (named, None) => Ok(named.map(str::to_string)),
+        }
+    }
+
+    /// Checks the caller against the security requirements of the skill the message targets.
+    fn authorize_skill(
+        &self,
+        context: &RequestContext,
+        skill: Option<&str>,
+    ) -> Result<(), AuthError> {
+        match (&self.authentication, skill) {
+            (Some(authentication), Some(skill)) => {
+                authentication.authorize_skill(skill, &context.schemes)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Parks the task in the `auth-required` state instead of handing the message to the handler.
+    async fn auth_required(
+        &self,
+        context: &RequestContext,
+        mut task: Task,
+        mut message: Message,
+        is_new: bool,
+        error: AuthError,
+    ) -> Result<Task, A2AError> {
+        if let (true, Some(context_id)) = (is_new, &message.context_id) {
+            task.context_id = context_id.clone();
+        }
+        message.task_id = Some(task.id.clone());
+        message.context_id = Some(task.context_id.clone());
+        task.history.push(message);
+        task.status = Some(TaskStatus {
+            state: TaskState::AuthRequired.into(),
+            message: Some(Message {
+                message_id: Uuid::new_v4().to_string(),
+                context_id: Some(task.context_id.clone()),
+                task_id: Some(task.id.clone()),
+                role: Role::Agent.into(),
+                parts: vec![Part {
+                    part: Some(PartBase::Text(error.to_string())),
+                }],
+                metadata: None,
+                extensions: vec![],
+            }),
+            timestamp: None,
+        });
+        let task = self.store.upsert(task).await?;
+        if is_new {
+            self.claim(context, &task.id).await?;
+        }
+        Ok(task)
+    }
+
+    /// Sends the task to the push notification url of the request in the background.
+    fn notify(&self, config: Option<&PushNotificationConfig>, task: &Task) {
+        let (Some(notifier), Some(config)) = (&self.push_notifier, config) else {
+            return;
+        };
+        let (notifier, config, task) = (notifier.clone(), config.clone(), task.clone());
+        tokio::spawn(async move {
+            if let Err(e) = notifier.notify(&config, &task).await {
+                tracing::warn!(error = %e, task_id = task.id, "failed to send push notification");
+            }
+        });
+    }
+
+    /// Makes the caller the owner of a task it created.
+    async fn claim(&self, context: &RequestContext, task_id: &str) -> Result<(), A2AError> {
+        if let Some(principal) = &context.principal {
+            self.store.set_owner(task_id, &principal.subject).await?;
+        }
+        Ok(())
+    }
+
+    /// Policy deciding who may act on existing tasks, [`OwnerOnlyAuthorizer`] by default.
+    pub fn with_authorizer(mut self, authorizer: Arc<dyn Authorizer>) -> Self {
+        self.authorizer = authorizer;
+        self
+    }
+
+    /// Works out the skill of messages. A skill named in their metadata must match it.
+    pub fn with_skill_router(mut self, router: Arc<dyn SkillRouter>) -> Self {
+        self.skill_router = Some(router);
+        self
+    }
+
+    pub fn with_skill_auth_failure(mut self, failure: SkillAuthFailure) -> Self {
+        self.skill_auth_failure = failure;
+        self
+    }
+
+    /// Public card of the agent, published at `/.well-known/agent-card.json` next to the
+    /// json-rpc endpoint.
+    pub fn with_card(mut self, card: AgentCard) -> Self {
+        self.card = Some(Arc::new(card));
+        self
+    }
+
+    pub fn card(&self) -> Option<&AgentCard> {
+        self.card.as_deref()
+    }
+
+    /// Card served to authenticated callers in place of the public one, usually listing
+    /// more skills and details.
+    pub fn with_extended_card(mut self, card: AgentCard) -> Self {
+        self.extended_card = Some(Arc::new(card));
+        self
+    }
+
+    /// Rate limits and quotas enforced on the requests the delegate serves.
+    pub fn with_rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Appends `middleware` to the stack wrapping the calls the delegate serves, see
+    /// [`Middleware`].
+    pub fn with_middleware(mut self, middleware: Arc<dyn Middleware>) -> Self {
+        self.middlewares.push(middleware);
+        self
+    }
+
+    /// Sends signed push notifications to the urls requests configure. Without a
+    /// notifier, requests configuring push notifications are rejected.
+    ///
+    /// The task is notified as the call that configured push returns it, so a
+    /// non-blocking call only notifies the `submitted` state. Later state changes, e.g.
+    /// by whatever works the task queue, are not notified by the delegate and have to be
+    /// sent with [`PushNotifier::notify`].
+    pub fn with_push_notifier(mut self, notifier: PushNotifier) -> Self {
+        self.push_notifier = Some(notifier);
+        self
+    }
+
+    pub fn push_notifier(&self) -> Option<&PushNotifier> {
+        self.push_notifier.as_ref()
+    }
+
+    /// Takes the request of an authenticated caller out of its rate limits. Transports
+    /// call it right after [`A2ADelegate::request_context`].
+    pub async fn check_rate_limits(&self, context: &RequestContext) -> Result<(), A2AError> {
+        match &self.rate_limiter {
+            Some(rate_limiter) => rate_limiter.check_request(context).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Authenticates requests against the security requirements of the agent card.
+    pub fn with_authentication(mut self, authentication: Authentication) -> Self {
+        self.authentication = Some(authentication);
+        self
+    }
+
+    pub fn authentication(&self) -> Option<&Authentication> {
+        self.authentication.as_ref()
+    }
+
+    /// Authenticates a transport request and captures the context handlers receive with it.
+    pub async fn request_context<B>(
+        &self,
+        request: &http::Request<B>,
+    ) -> Result<RequestContext, AuthError> {
+        let authenticated = match &self.authentication {
+            Some(authentication) => authentication.authenticate(request).await?,
+            None => Default::default(),
+        };
+        Ok(RequestContext {
+            principal: authenticated.principal,
+            schemes: authenticated.schemes,
+            headers: request.headers().clone(),
+            remote_addr: request.extensions().get::<RemoteAddr>().map(|addr| addr.0),
+        })
+    }
+
+    /// Name of the agent, recorded in the delegation chain of requests it delegates.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn with_max_delegation_depth(mut self, max_delegation_depth: usize) -> Self {
+        self.max_delegation_depth = max_delegation_depth;
+        self
+    }
+
+    /// Validates the delegation chain of an incoming request and returns the chain that
+    /// outgoing requests made while handling it should carry.
+    fn enter_delegation(
+        &self,
+        request: &SendMessageRequest,
+    ) -> Result<DelegationChain, A2AProtocolError> {
+        let chain = DelegationChain::from_metadata(request.metadata.as_ref()).unwrap_or_default();
+        let depth = chain.depth.max(chain.agents.len());
+        if depth > self.max_delegation_depth {
+            return Err(A2AProtocolError::delegation_depth_exceeded(
+                depth,
+                self.max_delegation_depth,
+            ));
+        }
+        let Some(name) = &self.name else {
+            return Ok(chain.with_hop());
+        };
+        if chain.contains(name) {
+            return Err(A2AProtocolError::delegation_loop(
+                name.clone(),
+                chain.agents,
+            ));
         }
+        Ok(chain.with_agent(name))
     }
 }
 
-impl A2ADelegate {
-    pub fn new<T: AgentHandler + 'static>(agent: Arc<T>) -> Self {
-        A2ADelegate {
-            agent,
-            store: Arc::new(InMemoryTaskStore::default()),
-            queue: Arc::new(BoundedTaskQueue::new(10)),
+impl Next<'_> {
+    /// Passes the call on to the rest of the stack.
+    pub async fn run(
+        self,
+        context: &RequestContext,
+        request: A2ARequest,
+    ) -> Result<A2AResponse, A2AError> {
+        match self.middlewares.split_first() {
+            Some((middleware, middlewares)) => {
+                let next = Next {
+                    delegate: self.delegate,
+                    middlewares,
+                };
+                middleware.handle(context, request, next).await
+            }
+            None => self.delegate.dispatch(context, request).await,
+        }
+    }
+}
+
+impl Debug for Next<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Next")
+            .field("middlewares", &self.middlewares.len())
+            .finish()
+    }
+}
+
+impl SentMessages {
+    fn slot(&mut self, caller: String, message_id: String) -> ResponseSlot {
+        let key = (caller, message_id);
+        if let Some(slot) = self.responses.get(&key) {
+            return slot.clone();
+        }
+        let slot = ResponseSlot::default();
+        self.responses.insert(key.clone(), slot.clone());
+        self.order.push_back(key);
+        while self.order.len() > MAX_SENT_MESSAGES {
+            if let Some(oldest) = self.order.pop_front() {
+                self.responses.remove(&oldest);
+            }
         }
+        slot
     }
 }
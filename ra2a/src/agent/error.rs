@@ -7,4 +7,13 @@ pub enum A2AAgentError {}
 pub enum AgentBuilderError {
     #[error("Name is required")]
     MissingName,
+
+    #[error("An agent card declaring the security schemes is required for authentication")]
+    MissingCard,
+
+    #[error("An authenticator is required to serve an authenticated extended card")]
+    MissingAuthenticator,
+
+    #[error("A multiplexed server is required to host other agents")]
+    MissingMultiplexedServer,
 }
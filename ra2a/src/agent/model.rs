@@ -1,7 +1,12 @@
-use crate::agent::{A2ADelegate, AgentBuilderError, AgentHandler};
+use crate::agent::{A2ADelegate, AgentBuilderError, AgentHandler, Middleware, SkillRouter};
+use crate::auth::{Authentication, Authenticator, Authorizer, Principal};
+use crate::core::agent::AgentCard;
 use crate::core::{A2AError, Transport};
+use crate::limit::RateLimiter;
+use crate::push::PushNotifier;
 use crate::server::{A2AServer, A2AServerError};
-use std::collections::HashMap;
+use crate::tls::ServerTlsConfig;
+use std::collections::{BTreeMap, HashMap};
 use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::task::JoinHandle;
@@ -16,47 +21,7 @@ pub struct Agent<A: AgentHandler + 'static> {
 impl<A: AgentHandler + 'static> Agent<A> {
     /// Starts the agent server with the configured transports that responds to requests in the A2A protocol.
     pub async fn start_server(&self) -> Result<AgentServerHandle, A2AError> {
-        let (tx, rx) = tokio::sync::oneshot::channel::<()>();
-        let server = self.server.clone();
-
-        let mut local_addrs = HashMap::new();
-        let (grpc_listener, jrpc_listener) = server.bind_all().await?;
-        let mut transports = vec![];
-        if let Some(listener) = grpc_listener {
-            local_addrs.insert(
-                Transport::Grpc,
-                listener.local_addr().map_err(A2AServerError::from)?,
-            );
-            transports.push((Transport::Grpc, listener));
-        }
-        if let Some(listener) = jrpc_listener {
-            local_addrs.insert(
-                Transport::JsonRpc,
-                listener.local_addr().map_err(A2AServerError::from)?,
-            );
-            transports.push((Transport::JsonRpc, listener));
-        }
-
-        let handle: JoinHandle<Result<(), A2AError>> = tokio::spawn(async move {
-            let shutdown = async move {
-                tokio::select! {
-                        _ = tokio::signal::ctrl_c() => {}
-                // Treat either "sent ()" or "sender dropped" as a shutdown signal
-                        _ = rx => {}
-                    }
-            };
-
-            server
-                .serve_with_shutdown(transports, shutdown)
-                .await
-                .map_err(A2AError::from)
-        });
-
-        Ok(AgentServerHandle {
-            tx: Some(tx),
-            handle: Some(handle),
-            local_addrs,
-        })
+        self.server.start().await
     }
 
     /// Returns the supported transports for the agent.
@@ -65,6 +30,43 @@ impl<A: AgentHandler + 'static> Agent<A> {
     }
 }
 
+/// The transport request a message arrived with, as seen by an [`AgentHandler`].
+#[derive(Debug, Clone, Default)]
+pub struct RequestContext {
+    /// The authenticated caller, `None` when the agent allows anonymous access.
+    pub principal: Option<Principal>,
+    /// Principal for each card scheme the caller presented valid credentials for, used to
+    /// check the requirements of the skill a message targets.
+    pub schemes: BTreeMap<String, Principal>,
+    pub headers: http::HeaderMap,
+    /// Address of the peer the request came from, when the transport knows it.
+    pub remote_addr: Option<SocketAddr>,
+}
+
+/// Address of the peer a request came from, inserted into the request extensions by the
+/// transports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RemoteAddr(pub SocketAddr);
+
+/// The rest of the middleware stack, ending with the delegate handling the call.
+#[derive(Clone, Copy)]
+pub struct Next<'a> {
+    pub(crate) delegate: &'a A2ADelegate,
+    pub(crate) middlewares: &'a [Arc<dyn Middleware>],
+}
+
+/// How a message is answered when the caller does not satisfy the security requirements
+/// of the skill it targets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SkillAuthFailure {
+    /// The request fails with an authorization error.
+    #[default]
+    Error,
+    /// The message is answered with a task in the `auth-required` state, without reaching
+    /// the handler, so the caller can retry with stronger credentials.
+    AuthRequired,
+}
+
 #[derive(Debug)]
 pub struct AgentServerHandle {
     tx: Option<tokio::sync::oneshot::Sender<()>>,
@@ -73,6 +75,18 @@ pub struct AgentServerHandle {
 }
 
 impl AgentServerHandle {
+    pub(crate) fn new(
+        tx: tokio::sync::oneshot::Sender<()>,
+        handle: JoinHandle<Result<(), A2AError>>,
+        local_addrs: HashMap<Transport, SocketAddr>,
+    ) -> Self {
+        Self {
+            tx: Some(tx),
+            handle: Some(handle),
+            local_addrs,
+        }
+    }
+
     /// Ask the server to stop (graceful).
     pub async fn shutdown(mut self) -> Result<(), A2AError> {
         if let Some(tx) = self.tx.take() {
@@ -118,6 +132,27 @@ pub struct AgentBuilder<A: AgentHandler + 'static> {
     pub json_rpc_socket: Option<SocketAddr>,
     #[cfg(feature = "grpc")]
     pub grpc_socket: Option<SocketAddr>,
+    #[cfg(feature = "grpc")]
+    pub grpc_config: Option<crate::grpc::GrpcConfig>,
+    pub multiplexed_socket: Option<SocketAddr>,
+    #[cfg(unix)]
+    pub json_rpc_unix_socket: Option<std::path::PathBuf>,
+    #[cfg(all(unix, feature = "grpc"))]
+    pub grpc_unix_socket: Option<std::path::PathBuf>,
+    pub stdio: bool,
+    pub hosted_agents: Vec<(String, A2ADelegate)>,
+    pub agent_hosts: Vec<(String, String)>,
+    pub max_delegation_depth: Option<usize>,
+    pub card: Option<AgentCard>,
+    pub extended_card: Option<AgentCard>,
+    pub authenticator: Option<Arc<dyn Authenticator>>,
+    pub authorizer: Option<Arc<dyn Authorizer>>,
+    pub skill_router: Option<Arc<dyn SkillRouter>>,
+    pub skill_auth_failure: SkillAuthFailure,
+    pub rate_limiter: Option<RateLimiter>,
+    pub middlewares: Vec<Arc<dyn Middleware>>,
+    pub push_notifier: Option<PushNotifier>,
+    pub tls: Option<ServerTlsConfig>,
 }
 
 impl<A: AgentHandler + 'static> AgentBuilder<A> {
@@ -128,6 +163,27 @@ impl<A: AgentHandler + 'static> AgentBuilder<A> {
             json_rpc_socket: None,
             #[cfg(feature = "grpc")]
             grpc_socket: None,
+            #[cfg(feature = "grpc")]
+            grpc_config: None,
+            multiplexed_socket: None,
+            #[cfg(unix)]
+            json_rpc_unix_socket: None,
+            #[cfg(all(unix, feature = "grpc"))]
+            grpc_unix_socket: None,
+            stdio: false,
+            hosted_agents: vec![],
+            agent_hosts: vec![],
+            max_delegation_depth: None,
+            card: None,
+            extended_card: None,
+            authenticator: None,
+            authorizer: None,
+            skill_router: None,
+            skill_auth_failure: SkillAuthFailure::default(),
+            rate_limiter: None,
+            middlewares: vec![],
+            push_notifier: None,
+            tls: None,
         }
     }
 
@@ -147,21 +203,218 @@ impl<A: AgentHandler + 'static> AgentBuilder<A> {
         self
     }
 
-    pub fn build(self) -> Result<Agent<A>, AgentBuilderError> {
-        let name = match self.name {
-            Some(name) => name,
-            None => return Err(AgentBuilderError::MissingName),
+    /// Serves json-rpc and, with the `grpc` feature, grpc together on `addr`, for platforms
+    /// giving the agent a single port. Takes precedence over the servers of each transport.
+    pub fn with_multiplexed_server(mut self, addr: SocketAddr) -> Self {
+        self.multiplexed_socket = Some(addr);
+        self
+    }
+
+    /// Serves json-rpc on the Unix domain socket at `path`, e.g. for a sidecar agent.
+    #[cfg(unix)]
+    pub fn with_json_rpc_unix_socket(mut self, path: impl Into<std::path::PathBuf>) -> Self {
+        self.json_rpc_unix_socket = Some(path.into());
+        self
+    }
+
+    #[cfg(all(unix, feature = "grpc"))]
+    pub fn with_grpc_unix_socket(mut self, path: impl Into<std::path::PathBuf>) -> Self {
+        self.grpc_unix_socket = Some(path.into());
+        self
+    }
+
+    /// Serves newline-delimited json-rpc over stdin and stdout, for agents spawned by their
+    /// client, see [`A2AClient::spawn`](crate::client::A2AClient::spawn). The server stops
+    /// once stdin is closed.
+    pub fn with_stdio(mut self) -> Self {
+        self.stdio = true;
+        self
+    }
+
+    /// Hosts another agent as `name` on the multiplexed server, next to this one. Build
+    /// its delegate with [`AgentBuilder::build_delegate`]. Requires
+    /// [`AgentBuilder::with_multiplexed_server`].
+    pub fn with_hosted_agent(mut self, name: impl Into<String>, delegate: A2ADelegate) -> Self {
+        self.hosted_agents.push((name.into(), delegate));
+        self
+    }
+
+    /// Routes requests whose Host header is `host` to the hosted agent `name`.
+    pub fn with_agent_host(mut self, host: impl Into<String>, name: impl Into<String>) -> Self {
+        self.agent_hosts.push((host.into(), name.into()));
+        self
+    }
+
+    /// Codec limits, compression and HTTP/2 settings of the gRPC server.
+    #[cfg(feature = "grpc")]
+    pub fn with_grpc_config(mut self, config: crate::grpc::GrpcConfig) -> Self {
+        self.grpc_config = Some(config);
+        self
+    }
+
+    /// Maximum number of agents a request may have been delegated through before reaching this one.
+    pub fn with_max_delegation_depth(mut self, depth: usize) -> Self {
+        self.max_delegation_depth = Some(depth);
+        self
+    }
+
+    /// Serves every transport over TLS, with mutual TLS when the config has a client CA.
+    pub fn with_tls(mut self, tls: ServerTlsConfig) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+
+    pub fn with_card(mut self, card: AgentCard) -> Self {
+        self.card = Some(card);
+        self
+    }
+
+    /// Card served to authenticated callers by `agent/getAuthenticatedExtendedCard`,
+    /// `GET /v1/card` and the gRPC `GetAgentCard` method. Requires an authenticator.
+    pub fn with_extended_card(mut self, card: AgentCard) -> Self {
+        self.extended_card = Some(card);
+        self
+    }
+
+    /// Validates credentials for the security schemes of the agent card. Requests that do
+    /// not satisfy the card's security requirements are rejected by every transport.
+    pub fn with_authenticator(mut self, authenticator: impl Authenticator + 'static) -> Self {
+        self.authenticator = Some(Arc::new(authenticator));
+        self
+    }
+
+    /// Policy deciding who may act on existing tasks, by default only their creator.
+    pub fn with_authorizer(mut self, authorizer: impl Authorizer + 'static) -> Self {
+        self.authorizer = Some(Arc::new(authorizer));
+        self
+    }
+
+    /// Works out the skill of messages. A skill named in their metadata must match it.
+    pub fn with_skill_router(mut self, router: impl SkillRouter + 'static) -> Self {
+        self.skill_router = Some(Arc::new(router));
+        self
+    }
+
+    /// How messages from callers that do not satisfy the security requirements of the
+    /// targeted skill are answered, an authorization error by default.
+    pub fn with_skill_auth_failure(mut self, failure: SkillAuthFailure) -> Self {
+        self.skill_auth_failure = failure;
+        self
+    }
+
+    /// Rate limits and quotas enforced on the requests the agent serves.
+    pub fn with_rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Appends `middleware` to the stack wrapping every call, whichever transport it
+    /// arrives on. Middlewares run in the order they are added, the first one outermost.
+    pub fn with_middleware(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.middlewares.push(Arc::new(middleware));
+        self
+    }
+
+    /// Sends signed push notifications of task updates, publishing the public keys at
+    /// `/.well-known/jwks.json` on the json-rpc server. Only the state a call returns is
+    /// notified, see [`A2ADelegate::with_push_notifier`].
+    pub fn with_push_notifier(mut self, notifier: PushNotifier) -> Self {
+        self.push_notifier = Some(notifier);
+        self
+    }
+
+    /// Builds the delegate serving the agent, without a server, e.g. to host it on the
+    /// server of another agent with [`AgentBuilder::with_hosted_agent`].
+    pub fn build_delegate(self) -> Result<A2ADelegate, AgentBuilderError> {
+        self.delegate()
+    }
+
+    fn delegate(&self) -> Result<A2ADelegate, AgentBuilderError> {
+        let Some(name) = &self.name else {
+            return Err(AgentBuilderError::MissingName);
         };
 
-        let delegate = A2ADelegate::new(self.handler.clone());
+        let mut delegate = A2ADelegate::new(self.handler.clone()).with_name(name.clone());
+        if let Some(depth) = self.max_delegation_depth {
+            delegate = delegate.with_max_delegation_depth(depth);
+        }
+        if let Some(card) = &self.card {
+            delegate = delegate.with_card(card.clone());
+        }
+        if let Some(authenticator) = &self.authenticator {
+            let Some(card) = &self.card else {
+                return Err(AgentBuilderError::MissingCard);
+            };
+            delegate =
+                delegate.with_authentication(Authentication::new(card, authenticator.clone()));
+        }
+        if let Some(card) = &self.extended_card {
+            if delegate.authentication().is_none() {
+                return Err(AgentBuilderError::MissingAuthenticator);
+            }
+            delegate = delegate.with_extended_card(card.clone());
+        }
+        if let Some(authorizer) = &self.authorizer {
+            delegate = delegate.with_authorizer(authorizer.clone());
+        }
+        if let Some(router) = &self.skill_router {
+            delegate = delegate.with_skill_router(router.clone());
+        }
+        delegate = delegate.with_skill_auth_failure(self.skill_auth_failure);
+        if let Some(rate_limiter) = &self.rate_limiter {
+            delegate = delegate.with_rate_limiter(rate_limiter.clone());
+        }
+        for middleware in &self.middlewares {
+            delegate = delegate.with_middleware(middleware.clone());
+        }
+        if let Some(notifier) = &self.push_notifier {
+            delegate = delegate.with_push_notifier(notifier.clone());
+        }
+        Ok(delegate)
+    }
+
+    pub fn build(self) -> Result<Agent<A>, AgentBuilderError> {
+        let delegate = self.delegate()?;
+        let hosting = !self.hosted_agents.is_empty() || !self.agent_hosts.is_empty();
+        if hosting && self.multiplexed_socket.is_none() {
+            return Err(AgentBuilderError::MissingMultiplexedServer);
+        }
+        let name = self.name.unwrap_or_default();
         let mut server = A2AServer::new(delegate);
+        if let Some(tls) = self.tls {
+            server = server.with_tls(tls);
+        }
         if let Some(addr) = self.json_rpc_socket {
             server = server.with_jsonrpc(addr);
         }
         #[cfg(feature = "grpc")]
+        if let Some(config) = self.grpc_config {
+            server = server.with_grpc_config(config);
+        }
+        #[cfg(feature = "grpc")]
         if let Some(addr) = self.grpc_socket {
             server = server.with_grpc(addr);
         }
+        if let Some(addr) = self.multiplexed_socket {
+            server = server.with_multiplexed(addr);
+        }
+        #[cfg(unix)]
+        if let Some(path) = self.json_rpc_unix_socket {
+            server = server.with_jsonrpc_unix(path);
+        }
+        #[cfg(all(unix, feature = "grpc"))]
+        if let Some(path) = self.grpc_unix_socket {
+            server = server.with_grpc_unix(path);
+        }
+        if self.stdio {
+            server = server.with_stdio();
+        }
+        for (name, delegate) in self.hosted_agents {
+            server = server.with_agent(name, delegate);
+        }
+        for (host, name) in self.agent_hosts {
+            server = server.with_agent_host(host, name);
+        }
 
         Ok(Agent {
             name,
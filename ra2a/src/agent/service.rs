@@ -1,7 +1,8 @@
-use crate::agent::A2AAgentError;
+use crate::agent::{A2AAgentError, Next, RequestContext};
 use crate::core::message::{Message, SendMessageResponsePayload};
 use crate::core::task::Task;
 use crate::core::util::Object;
+use crate::core::{A2AError, A2ARequest, A2AResponse};
 use async_trait::async_trait;
 use std::fmt::Debug;
 
@@ -9,6 +10,7 @@ use std::fmt::Debug;
 pub trait AgentHandler: Debug + Send + Sync {
     async fn handle_message(
         &self,
+        context: &RequestContext,
         message: Message,
         metadata: Option<Object>,
         task: Task,
@@ -22,6 +24,7 @@ pub struct NoopAgentHandler;
 impl AgentHandler for NoopAgentHandler {
     async fn handle_message(
         &self,
+        _context: &RequestContext,
         message: Message,
         _metadata: Option<Object>,
         _task: Task,
@@ -29,3 +32,26 @@ impl AgentHandler for NoopAgentHandler {
         Ok(SendMessageResponsePayload::Message(message))
     }
 }
+
+/// Works out which card skill a message targets. Takes precedence over the skill named
+/// in its metadata, which must then match.
+#[async_trait]
+pub trait SkillRouter: Debug + Send + Sync {
+    /// Returns the id of the targeted skill, `None` if the message targets no particular skill.
+    async fn route(&self, message: &Message, metadata: Option<&Object>) -> Option<String>;
+}
+
+/// Wraps the calls an agent serves, the same way for every transport, e.g. for logging,
+/// metrics or request validation. Calls reach the middleware once the transport has
+/// authenticated them and checked the rate limits of the caller.
+#[async_trait]
+pub trait Middleware: Debug + Send + Sync {
+    /// Handles `request`, usually by passing it on with [`Next::run`] and looking at or
+    /// changing what comes back. Returning without calling `next` answers the call.
+    async fn handle(
+        &self,
+        context: &RequestContext,
+        request: A2ARequest,
+        next: Next<'_>,
+    ) -> Result<A2AResponse, A2AError>;
+}
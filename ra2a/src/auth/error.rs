@@ -0,0 +1,19 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum AuthError {
+    #[error("Missing credentials")]
+    MissingCredentials,
+
+    #[error("Invalid credentials")]
+    InvalidCredentials,
+
+    #[error("Credentials for {scheme} are missing required scopes: {missing:?}")]
+    InsufficientScope {
+        scheme: String,
+        missing: Vec<String>,
+    },
+
+    #[error("Unknown skill {0}")]
+    UnknownSkill(String),
+}
@@ -0,0 +1,81 @@
+use crate::core::util::Object;
+use std::collections::BTreeMap;
+
+/// The authenticated caller of a request.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Principal {
+    /// Stable identifier of the caller, such as a user id or client id.
+    pub subject: String,
+    /// Name of the card security scheme the caller authenticated with.
+    pub scheme: String,
+    pub scopes: Vec<String>,
+    /// Any additional claims the authenticator knows about the caller.
+    pub claims: Object,
+}
+
+/// Outcome of authenticating a request against an agent card.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Authenticated {
+    /// The caller, `None` for anonymous requests.
+    pub principal: Option<Principal>,
+    /// Principal for each card scheme the caller presented valid credentials for.
+    pub schemes: BTreeMap<String, Principal>,
+}
+
+/// A credential presented by a caller for one of the card's security schemes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Credential {
+    ApiKey(String),
+    /// Bearer token, used by http bearer, OAuth2 and OpenID Connect schemes.
+    Bearer(String),
+    Basic {
+        username: String,
+        password: String,
+    },
+    /// Any other http authorization scheme, with its raw credentials.
+    Http {
+        scheme: String,
+        credentials: String,
+    },
+    /// DER encoded certificate presented by the client during a mutual TLS handshake.
+    ClientCertificate(Vec<u8>),
+}
+
+/// An operation on an existing task, checked by an [`crate::auth::Authorizer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TaskAction {
+    Get,
+    /// Sending a follow-up message for the task.
+    SendMessage,
+    Cancel,
+    Resubscribe,
+    PushNotificationConfig,
+}
+
+/// DER encoded certificate chain presented by the client, leaf first. Transports that
+/// terminate TLS insert it into the request extensions for mutual TLS schemes.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PeerCertificates(pub Vec<Vec<u8>>);
+
+impl Principal {
+    pub fn new(subject: impl Into<String>) -> Self {
+        Self {
+            subject: subject.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_scopes(mut self, scopes: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.scopes = scopes.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn with_claims(mut self, claims: Object) -> Self {
+        self.claims = claims;
+        self
+    }
+
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|s| s == scope)
+    }
+}
@@ -0,0 +1,426 @@
+use crate::auth::{AuthError, Authenticated, Credential, PeerCertificates, Principal, TaskAction};
+use crate::core::agent::{AgentCard, Security};
+use crate::core::task::Task;
+use crate::core::util::{Scheme, SecurityScheme};
+use async_trait::async_trait;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use http::header::{AUTHORIZATION, COOKIE};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/// Validates credentials presented for the security schemes declared on an agent card.
+#[async_trait]
+pub trait Authenticator: Debug + Send + Sync {
+    /// Validates `credential`, presented for the card scheme named `scheme_name`,
+    /// and returns the principal it belongs to. The principal's scopes are checked
+    /// against the scopes the card requires for the scheme.
+    async fn authenticate(
+        &self,
+        scheme_name: &str,
+        scheme: &SecurityScheme,
+        credential: &Credential,
+    ) -> Result<Principal, AuthError>;
+}
+
+/// Decides whether a caller may act on an existing task.
+#[async_trait]
+pub trait Authorizer: Debug + Send + Sync {
+    /// `owner` is the subject of the principal that created the task, `None` if it was
+    /// created anonymously. `principal` is `None` for anonymous callers.
+    async fn authorize(
+        &self,
+        principal: Option<&Principal>,
+        action: TaskAction,
+        task: &Task,
+        owner: Option<&str>,
+    ) -> bool;
+}
+
+/// Only the principal that created a task may act on it. Tasks created anonymously
+/// are open to every caller.
+#[derive(Debug, Clone, Default)]
+pub struct OwnerOnlyAuthorizer;
+
+/// Accepts a fixed set of api keys and bearer tokens, useful for development and tests.
+#[derive(Debug, Clone, Default)]
+pub struct StaticAuthenticator {
+    tokens: HashMap<String, Principal>,
+}
+
+/// Identifies mutual TLS callers by the common name of their certificate's subject.
+/// The certificate chain has already been verified against the server's client CA by
+/// the TLS layer.
+#[derive(Debug, Clone, Default)]
+pub struct ClientCertificateAuthenticator;
+
+/// Enforces the `security` requirements of an agent card on incoming requests.
+///
+/// Requirements are an OR of ANDs: a request is authenticated when every scheme of at
+/// least one requirement is satisfied. A card without requirements accepts anonymous
+/// requests. Skills may declare stricter requirements of their own, checked once the
+/// skill a message targets is known.
+#[derive(Debug, Clone)]
+pub struct Authentication {
+    schemes: HashMap<String, SecurityScheme>,
+    requirements: Vec<Security>,
+    // skill id -> requirements, empty for skills that only need the card level ones
+    skills: HashMap<String, Vec<Security>>,
+    authenticator: Arc<dyn Authenticator>,
+}
+
+impl StaticAuthenticator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accepts `token` as an api key or bearer token for `principal`.
+    pub fn with_token(mut self, token: impl Into<String>, principal: Principal) -> Self {
+        self.tokens.insert(token.into(), principal);
+        self
+    }
+}
+
+#[async_trait]
+impl Authenticator for StaticAuthenticator {
+    async fn authenticate(
+        &self,
+        _scheme_name: &str,
+        _scheme: &SecurityScheme,
+        credential: &Credential,
+    ) -> Result<Principal, AuthError> {
+        let token = match credential {
+            Credential::ApiKey(token) | Credential::Bearer(token) => token,
+            _ => return Err(AuthError::InvalidCredentials),
+        };
+        self.tokens
+            .get(token)
+            .cloned()
+            .ok_or(AuthError::InvalidCredentials)
+    }
+}
+
+#[async_trait]
+impl Authenticator for ClientCertificateAuthenticator {
+    async fn authenticate(
+        &self,
+        _scheme_name: &str,
+        _scheme: &SecurityScheme,
+        credential: &Credential,
+    ) -> Result<Principal, AuthError> {
+        let Credential::ClientCertificate(certificate) = credential else {
+            return Err(AuthError::InvalidCredentials);
+        };
+        subject_common_name(certificate)
+            .map(Principal::new)
+            .ok_or(AuthError::InvalidCredentials)
+    }
+}
+
+#[async_trait]
+impl Authorizer for OwnerOnlyAuthorizer {
+    async fn authorize(
+        &self,
+        principal: Option<&Principal>,
+        _action: TaskAction,
+        _task: &Task,
+        owner: Option<&str>,
+    ) -> bool {
+        match owner {
+            Some(owner) => principal.is_some_and(|p| p.subject == owner),
+            None => true,
+        }
+    }
+}
+
+impl Authentication {
+    pub fn new(card: &AgentCard, authenticator: Arc<dyn Authenticator>) -> Self {
+        Self {
+            schemes: card.security_schemes.clone(),
+            requirements: card.security.clone(),
+            skills: card
+                .skills
+                .iter()
+                .map(|skill| (skill.id.clone(), skill.security.clone()))
+                .collect(),
+            authenticator,
+        }
+    }
+
+    /// Authenticates every credential the request presents for a declared scheme and checks
+    /// the card level requirements. The caller is `None` when the card allows anonymous
+    /// access and no valid credentials were presented.
+    pub async fn authenticate<B>(
+        &self,
+        request: &http::Request<B>,
+    ) -> Result<Authenticated, AuthError> {
+        let mut schemes = BTreeMap::new();
+        let mut rejected = HashSet::new();
+        for (name, scheme) in &self.schemes {
+            let Some(credential) = extract(scheme, request) else {
+                continue;
+            };
+            match self
+                .authenticator
+                .authenticate(name, scheme, &credential)
+                .await
+            {
+                Ok(mut principal) => {
+                    principal.scheme = name.clone();
+                    schemes.insert(name.clone(), principal);
+                }
+                Err(e) => {
+                    tracing::debug!(scheme = name, error = %e, "credentials rejected");
+                    rejected.insert(name.clone());
+                }
+            }
+        }
+        let principal = match self.requirements.is_empty() {
+            true => schemes.values().next().cloned(),
+            false => self.evaluate(&self.requirements, &schemes, &rejected)?,
+        };
+        Ok(Authenticated { principal, schemes })
+    }
+
+    /// Checks the security requirements of the skill `skill_id` against the schemes the
+    /// caller authenticated with. Skills without requirements only need the card level
+    /// requirements, skills the card does not declare are refused.
+    pub fn authorize_skill(
+        &self,
+        skill_id: &str,
+        schemes: &BTreeMap<String, Principal>,
+    ) -> Result<(), AuthError> {
+        match self.skills.get(skill_id) {
+            Some(requirements) if requirements.is_empty() => Ok(()),
+            Some(requirements) => self
+                .evaluate(requirements, schemes, &HashSet::new())
+                .map(|_| ()),
+            None => Err(AuthError::UnknownSkill(skill_id.to_string())),
+        }
+    }
+
+    /// Value for the `WWW-Authenticate` header of a rejected http request.
+    pub fn challenge(&self) -> Option<String> {
+        let mut challenges: Vec<String> = self
+            .schemes
+            .values()
+            .filter_map(|scheme| match scheme.scheme.as_ref()? {
+                Scheme::HTTPAuth(http) => Some(capitalize(&http.scheme)),
+                Scheme::OAuth2(_) | Scheme::OpenIDConnect(_) => Some("Bearer".to_string()),
+                _ => None,
+            })
+            .collect();
+        challenges.sort();
+        challenges.dedup();
+        match challenges.is_empty() {
+            true => None,
+            false => Some(challenges.join(", ")),
+        }
+    }
+
+    /// Returns the caller identified by the first satisfied requirement.
+    fn evaluate(
+        &self,
+        requirements: &[Security],
+        schemes: &BTreeMap<String, Principal>,
+        rejected: &HashSet<String>,
+    ) -> Result<Option<Principal>, AuthError> {
+        let mut error = AuthError::MissingCredentials;
+        for requirement in requirements {
+            match self.satisfy(requirement, schemes, rejected) {
+                Ok(principal) => return Ok(principal),
+                // report the most specific failure
+                Err(AuthError::MissingCredentials) => {}
+                Err(e) => error = e,
+            }
+        }
+        Err(error)
+    }
+
+    fn satisfy(
+        &self,
+        requirement: &Security,
+        schemes: &BTreeMap<String, Principal>,
+        rejected: &HashSet<String>,
+    ) -> Result<Option<Principal>, AuthError> {
+        let mut names: Vec<&String> = requirement.schemes.keys().collect();
+        names.sort();
+        let mut authenticated: Option<Principal> = None;
+        for name in names {
+            if !self.schemes.contains_key(name) {
+                tracing::warn!(
+                    scheme = name,
+                    "security requirement names an undeclared scheme"
+                );
+                return Err(AuthError::MissingCredentials);
+            }
+            let Some(principal) = schemes.get(name) else {
+                return match rejected.contains(name) {
+                    true => Err(AuthError::InvalidCredentials),
+                    false => Err(AuthError::MissingCredentials),
+                };
+            };
+            let missing: Vec<String> = requirement.schemes[name]
+                .list
+                .iter()
+                .filter(|scope| !principal.has_scope(scope))
+                .cloned()
+                .collect();
+            if !missing.is_empty() {
+                return Err(AuthError::InsufficientScope {
+                    scheme: name.clone(),
+                    missing,
+                });
+            }
+            match authenticated.as_mut() {
+                // the first scheme identifies the caller, later ones only add scopes
+                Some(first) => first.scopes.extend(principal.scopes.iter().cloned()),
+                None => authenticated = Some(principal.clone()),
+            }
+        }
+        Ok(authenticated)
+    }
+}
+
+/// Reads the credential for `scheme` from the request, if one was presented.
+fn extract<B>(scheme: &SecurityScheme, request: &http::Request<B>) -> Option<Credential> {
+    match scheme.scheme.as_ref()? {
+        Scheme::APIKey(api_key) => {
+            let value = match api_key.location.as_str() {
+                "header" => header(request, &api_key.name)?.to_string(),
+                "query" => query_param(request, &api_key.name)?,
+                "cookie" => cookie(request, &api_key.name)?,
+                _ => return None,
+            };
+            Some(Credential::ApiKey(value))
+        }
+        Scheme::HTTPAuth(http) => {
+            let (scheme, credentials) = authorization(request)?;
+            if !scheme.eq_ignore_ascii_case(&http.scheme) {
+                return None;
+            }
+            if scheme.eq_ignore_ascii_case("bearer") {
+                return Some(Credential::Bearer(credentials.to_string()));
+            }
+            if scheme.eq_ignore_ascii_case("basic") {
+                let decoded = String::from_utf8(STANDARD.decode(credentials).ok()?).ok()?;
+                let (username, password) = decoded.split_once(':')?;
+                return Some(Credential::Basic {
+                    username: username.to_string(),
+                    password: password.to_string(),
+                });
+            }
+            Some(Credential::Http {
+                scheme: scheme.to_string(),
+                credentials: credentials.to_string(),
+            })
+        }
+        Scheme::OAuth2(_) | Scheme::OpenIDConnect(_) => {
+            let (scheme, token) = authorization(request)?;
+            match scheme.eq_ignore_ascii_case("bearer") {
+                true => Some(Credential::Bearer(token.to_string())),
+                false => None,
+            }
+        }
+        Scheme::MutualTLS(_) => {
+            let certificates = request.extensions().get::<PeerCertificates>()?;
+            let leaf = certificates.0.first()?;
+            Some(Credential::ClientCertificate(leaf.clone()))
+        }
+    }
+}
+
+fn header<'a, B>(request: &'a http::Request<B>, name: &str) -> Option<&'a str> {
+    request.headers().get(name)?.to_str().ok()
+}
+
+fn authorization<B>(request: &http::Request<B>) -> Option<(&str, &str)> {
+    let value = request.headers().get(AUTHORIZATION)?.to_str().ok()?;
+    let (scheme, credentials) = value.split_once(' ')?;
+    Some((scheme, credentials.trim()))
+}
+
+fn query_param<B>(request: &http::Request<B>, name: &str) -> Option<String> {
+    request
+        .uri()
+        .query()?
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value.to_string())
+}
+
+fn cookie<B>(request: &http::Request<B>, name: &str) -> Option<String> {
+    request
+        .headers()
+        .get_all(COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value.to_string())
+}
+
+fn capitalize(value: &str) -> String {
+    let mut chars = value.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Common name of the subject of a DER encoded X.509 certificate.
+fn subject_common_name(certificate: &[u8]) -> Option<String> {
+    const SEQUENCE: u8 = 0x30;
+    const VERSION: u8 = 0xa0;
+    const COMMON_NAME: [u8; 3] = [0x55, 0x04, 0x03];
+
+    let (tag, certificate, _) = der_element(certificate)?;
+    if tag != SEQUENCE {
+        return None;
+    }
+    let (_, tbs_certificate, _) = der_element(certificate)?;
+    // the version is optional, then serial number, signature, issuer and validity
+    let (tag, _, mut fields) = der_element(tbs_certificate)?;
+    if tag == VERSION {
+        fields = der_element(fields)?.2;
+    }
+    for _ in 0..3 {
+        fields = der_element(fields)?.2;
+    }
+    // subject: a sequence of sets of (oid, value) attributes
+    let (_, mut names, _) = der_element(fields)?;
+    while !names.is_empty() {
+        let (_, mut attributes, rest) = der_element(names)?;
+        names = rest;
+        while !attributes.is_empty() {
+            let (_, attribute, rest) = der_element(attributes)?;
+            attributes = rest;
+            let (_, oid, value) = der_element(attribute)?;
+            if oid == COMMON_NAME {
+                let (_, value, _) = der_element(value)?;
+                return String::from_utf8(value.to_vec()).ok();
+            }
+        }
+    }
+    None
+}
+
+/// Splits the DER element at the start of `input` into its tag, contents and the bytes after it.
+fn der_element(input: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let (&tag, rest) = input.split_first()?;
+    let (&length, mut rest) = rest.split_first()?;
+    let length = match length {
+        0x00..=0x7f => length as usize,
+        0x81..=0x84 => {
+            let (bytes, remaining) = rest.split_at_checked((length & 0x7f) as usize)?;
+            rest = remaining;
+            bytes.iter().fold(0, |acc, b| (acc << 8) | *b as usize)
+        }
+        _ => return None,
+    };
+    let (contents, rest) = rest.split_at_checked(length)?;
+    Some((tag, contents, rest))
+}
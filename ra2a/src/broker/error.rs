@@ -0,0 +1,42 @@
+use crate::client::A2AClientError;
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum AgentBrokerError {
+    #[error("Unknown agent: {0}")]
+    UnknownAgent(String),
+
+    #[error("No healthy replica available for agent: {0}")]
+    NoHealthyReplica(String),
+
+    #[error("Unable to determine which agent should receive the message")]
+    UnroutableMessage,
+
+    #[error("Failed to read agent registry {path}")]
+    RegistryIo {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Invalid agent registry: {0}")]
+    InvalidRegistry(String),
+
+    #[error("Failed to fetch agent card from {url}")]
+    CardFetch {
+        url: String,
+        #[source]
+        source: reqwest::Error,
+    },
+
+    #[error("Agent {0} has no interface with a supported transport")]
+    NoSupportedInterface(String),
+
+    #[error("Failed to connect to agent at {url}")]
+    Connect {
+        url: String,
+        #[source]
+        source: A2AClientError,
+    },
+}
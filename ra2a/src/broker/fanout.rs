@@ -0,0 +1,270 @@
+use crate::broker::AgentBroker;
+use crate::core::artifact::Artifact;
+use crate::core::message::{SendMessageRequest, SendMessageResponsePayload};
+use crate::core::task::{Task, TaskState, TaskStatus};
+use crate::core::util::Object;
+use crate::core::{A2AError, A2AProtocolError};
+use futures::StreamExt;
+use futures::stream::FuturesUnordered;
+use serde_json::json;
+use std::collections::HashMap;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Decides when a fan out is finished and whether the composite task succeeded. A fan
+/// out to no agents, or with a quorum of zero, is satisfied without sending anything, one
+/// with a quorum larger than its agents fails without sending anything.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MergeStrategy {
+    /// Finish as soon as one agent completes.
+    FirstComplete,
+    /// Wait for every agent, succeeding only if all of them complete.
+    #[default]
+    All,
+    /// Finish as soon as the given number of agents complete.
+    Quorum(usize),
+}
+
+/// The same message sent to several agents concurrently.
+#[derive(Debug, Clone)]
+pub struct FanOut {
+    request: SendMessageRequest,
+    agents: Vec<String>,
+    timeout: Duration,
+    agent_timeouts: HashMap<String, Duration>,
+    strategy: MergeStrategy,
+}
+
+/// What a single agent produced during a fan out.
+#[derive(Debug)]
+pub enum FanOutOutcome {
+    Response(Box<SendMessageResponsePayload>),
+    Error(A2AError),
+    TimedOut,
+}
+
+#[derive(Debug)]
+pub struct AgentResponse {
+    pub agent: String,
+    pub outcome: FanOutOutcome,
+}
+
+/// The merged result of a fan out. `task` is a composite task holding the artifacts of
+/// every agent, annotated with the agent and task that produced them.
+#[derive(Debug)]
+pub struct FanOutResult {
+    pub task: Task,
+    pub responses: Vec<AgentResponse>,
+}
+
+impl FanOut {
+    pub fn new(request: SendMessageRequest) -> Self {
+        Self {
+            request,
+            agents: vec![],
+            timeout: Duration::from_secs(30),
+            agent_timeouts: HashMap::new(),
+            strategy: MergeStrategy::default(),
+        }
+    }
+
+    pub fn with_agent(mut self, agent: impl Into<String>) -> Self {
+        self.agents.push(agent.into());
+        self
+    }
+
+    pub fn with_agents(mut self, agents: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.agents.extend(agents.into_iter().map(Into::into));
+        self
+    }
+
+    /// Default time each agent is given to respond.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Overrides the timeout for a single agent.
+    pub fn with_agent_timeout(mut self, agent: impl Into<String>, timeout: Duration) -> Self {
+        self.agent_timeouts.insert(agent.into(), timeout);
+        self
+    }
+
+    pub fn with_strategy(mut self, strategy: MergeStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    fn required(&self) -> usize {
+        match self.strategy {
+            MergeStrategy::FirstComplete => 1.min(self.agents.len()),
+            MergeStrategy::All => self.agents.len(),
+            MergeStrategy::Quorum(n) => n,
+        }
+    }
+}
+
+impl FanOutOutcome {
+    /// Whether the agent finished its work, either by completing a task or answering directly.
+    pub fn is_complete(&self) -> bool {
+        let FanOutOutcome::Response(payload) = self else {
+            return false;
+        };
+        match payload.as_ref() {
+            SendMessageResponsePayload::Message(_) => true,
+            SendMessageResponsePayload::Task(task) => task
+                .status
+                .as_ref()
+                .is_some_and(|s| s.state == TaskState::Completed.into_i32()),
+        }
+    }
+
+    fn as_str(&self) -> &str {
+        match self {
+            FanOutOutcome::Response(_) if self.is_complete() => "completed",
+            FanOutOutcome::Response(_) => "incomplete",
+            FanOutOutcome::Error(_) => "failed",
+            FanOutOutcome::TimedOut => "timedOut",
+        }
+    }
+}
+
+impl AgentBroker {
+    /// Sends the fan out message to every agent concurrently and merges the results
+    /// according to its [`MergeStrategy`]. Agents still running once the strategy is
+    /// satisfied, or can no longer be satisfied, are abandoned.
+    pub async fn fan_out(&self, fan_out: FanOut) -> FanOutResult {
+        let required = fan_out.required();
+        let mut pending: FuturesUnordered<_> = fan_out
+            .agents
+            .iter()
+            .map(|agent| {
+                let timeout = fan_out
+                    .agent_timeouts
+                    .get(agent)
+                    .copied()
+                    .unwrap_or(fan_out.timeout);
+                self.fan_out_one(agent, fan_out.request.clone(), timeout)
+            })
+            .collect();
+
+        let mut responses = vec![];
+        let mut completed = 0;
+        // the sends only start once polled, none is made when nothing is required or the
+        // quorum is out of reach
+        while completed < required && completed + pending.len() >= required {
+            let Some(response) = pending.next().await else {
+                break;
+            };
+            if response.outcome.is_complete() {
+                completed += 1;
+            }
+            responses.push(response);
+        }
+        drop(pending);
+
+        let state = match completed >= required {
+            true => TaskState::Completed,
+            false => TaskState::Failed,
+        };
+        FanOutResult {
+            task: composite_task(&fan_out, &responses, state),
+            responses,
+        }
+    }
+
+    async fn fan_out_one(
+        &self,
+        agent: &str,
+        request: SendMessageRequest,
+        timeout: Duration,
+    ) -> AgentResponse {
+        let res = tokio::time::timeout(timeout, self.send_message(agent, request)).await;
+        let outcome = match res {
+            Ok(Ok(res)) => match res.payload {
+                Some(payload) => FanOutOutcome::Response(Box::new(payload)),
+                None => FanOutOutcome::Error(A2AProtocolError::invalid_agent_response().into()),
+            },
+            Ok(Err(e)) => FanOutOutcome::Error(e),
+            Err(_) => FanOutOutcome::TimedOut,
+        };
+        AgentResponse {
+            agent: agent.to_string(),
+            outcome,
+        }
+    }
+}
+
+fn composite_task(fan_out: &FanOut, responses: &[AgentResponse], state: TaskState) -> Task {
+    let mut task = Task::new();
+    if let Some(context_id) = fan_out
+        .request
+        .message
+        .as_ref()
+        .and_then(|m| m.context_id.clone())
+    {
+        task.context_id = context_id;
+    }
+    if let Some(message) = &fan_out.request.message {
+        task.history.push(message.clone());
+    }
+
+    let mut agents = vec![];
+    for response in responses {
+        let mut summary = json!({
+            "agent": response.agent,
+            "outcome": response.outcome.as_str(),
+        });
+        match &response.outcome {
+            FanOutOutcome::Response(payload) => match payload.as_ref() {
+                SendMessageResponsePayload::Task(agent_task) => {
+                    summary["taskId"] = json!(agent_task.id);
+                    for artifact in &agent_task.artifacts {
+                        let mut artifact = artifact.clone();
+                        let metadata = artifact.metadata.get_or_insert_with(Object::empty);
+                        metadata.insert("agent", json!(response.agent));
+                        metadata.insert("taskId", json!(agent_task.id));
+                        task.artifacts.push(artifact);
+                    }
+                }
+                SendMessageResponsePayload::Message(message) => {
+                    let mut metadata = Object::empty();
+                    metadata.insert("agent", json!(response.agent));
+                    metadata.insert("messageId", json!(message.message_id));
+                    task.artifacts.push(Artifact {
+                        artifact_id: Uuid::new_v4().to_string(),
+                        name: Some(response.agent.clone()),
+                        description: None,
+                        parts: message.parts.clone(),
+                        metadata: Some(metadata),
+                        extensions: message.extensions.clone(),
+                    });
+                }
+            },
+            FanOutOutcome::Error(e) => summary["error"] = json!(e.to_string()),
+            FanOutOutcome::TimedOut => {}
+        }
+        agents.push(summary);
+    }
+    // agents that were abandoned before answering
+    for agent in &fan_out.agents {
+        if !responses.iter().any(|r| &r.agent == agent) {
+            agents.push(json!({ "agent": agent, "outcome": "abandoned" }));
+        }
+    }
+
+    let strategy = match fan_out.strategy {
+        MergeStrategy::FirstComplete => json!("firstComplete"),
+        MergeStrategy::All => json!("all"),
+        MergeStrategy::Quorum(n) => json!({ "quorum": n }),
+    };
+    let mut metadata = Object::empty();
+    metadata.insert("fanOut", json!({ "strategy": strategy, "agents": agents }));
+    task.metadata = Some(metadata);
+    task.status = Some(TaskStatus {
+        state: state.into(),
+        message: None,
+        timestamp: None,
+    });
+    task
+}
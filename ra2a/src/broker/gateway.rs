@@ -0,0 +1,296 @@
+use crate::broker::{AgentBroker, AgentBrokerError};
+use crate::core::agent::AgentCard;
+use crate::core::message::{
+    Message, SendMessageRequest, SendMessageResponse, SendMessageResponsePayload,
+};
+use crate::core::task::{GetTaskRequest, Task, TaskState};
+use crate::core::{A2A, A2AError, A2AProtocolError, SKILL_METADATA_KEY};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::Arc;
+use tokio::sync::{Mutex, RwLock};
+use uuid::Uuid;
+
+/// Metadata key (on the request or the message) naming the backend agent a new message is for.
+pub const GATEWAY_AGENT_METADATA_KEY: &str = "agent";
+
+/// Task and context ids kept by default, see [`A2AGateway::with_max_routes`].
+const DEFAULT_MAX_ROUTES: usize = 10_000;
+
+/// An A2A implementation that fronts many backend agents behind a single endpoint.
+///
+/// Backend task and context ids are replaced with gateway issued ids so that they are
+/// unique across all backends, and every call for a task or context is routed to the
+/// backend that owns it. Context ids the gateway did not issue are dropped, the backend
+/// starts a new context. Task ids are forgotten once the task is seen in a terminal state.
+/// Serve it with [`crate::agent::A2ADelegate::forwarding`].
+#[derive(Debug, Clone)]
+pub struct A2AGateway {
+    broker: AgentBroker,
+    card: AgentCard,
+    backends: Arc<RwLock<BTreeMap<String, AgentCard>>>,
+    default_agent: Option<String>,
+    max_routes: usize,
+    tasks: Arc<Mutex<GatewayIds>>,
+    contexts: Arc<Mutex<GatewayIds>>,
+}
+
+/// The backend agent and backend task id behind a gateway task id.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TaskRoute {
+    pub agent: String,
+    pub task_id: String,
+}
+
+/// Gateway issued ids, of tasks or contexts, and the backend ids behind them, oldest
+/// first.
+#[derive(Debug, Default)]
+struct GatewayIds {
+    // the route of contexts holds the backend context id as `task_id`
+    routes: HashMap<String, TaskRoute>,
+    reverse: HashMap<TaskRoute, String>,
+    order: VecDeque<String>,
+}
+
+impl A2AGateway {
+    /// Creates a gateway that routes through `broker`. The `card` describes the gateway
+    /// itself; its skills are extended with the skills of every backend.
+    pub fn new(broker: AgentBroker, card: AgentCard) -> Self {
+        Self {
+            broker,
+            card,
+            backends: Arc::new(RwLock::new(BTreeMap::new())),
+            default_agent: None,
+            max_routes: DEFAULT_MAX_ROUTES,
+            tasks: Arc::new(Mutex::new(GatewayIds::default())),
+            contexts: Arc::new(Mutex::new(GatewayIds::default())),
+        }
+    }
+
+    /// Agent that receives new messages which do not name an agent or skill.
+    pub fn with_default_agent(mut self, agent: impl Into<String>) -> Self {
+        self.default_agent = Some(agent.into());
+        self
+    }
+
+    /// Maximum number of task ids, and of context ids, kept, the oldest being dropped
+    /// first. Calls for a dropped task fail with task not found, messages in a dropped
+    /// context start a new one.
+    pub fn with_max_routes(mut self, max_routes: usize) -> Self {
+        self.max_routes = max_routes.max(1);
+        self
+    }
+
+    /// Exposes the broker agent `agent` through the gateway, described by `card`.
+    pub async fn register_backend(&self, agent: impl Into<String>, card: AgentCard) {
+        self.backends.write().await.insert(agent.into(), card);
+    }
+
+    pub async fn deregister_backend(&self, agent: &str) -> bool {
+        self.backends.write().await.remove(agent).is_some()
+    }
+
+    pub fn broker(&self) -> &AgentBroker {
+        &self.broker
+    }
+
+    /// The gateway card, with skills being the union of the skills of all backends.
+    pub async fn agent_card(&self) -> AgentCard {
+        let mut card = self.card.clone();
+        for (agent, backend) in self.backends.read().await.iter() {
+            for skill in &backend.skills {
+                if card.skills.iter().any(|s| s.id == skill.id) {
+                    tracing::warn!(agent, skill = skill.id, "duplicate skill id, skipping");
+                    continue;
+                }
+                card.skills.push(skill.clone());
+            }
+        }
+        card
+    }
+
+    /// Resolves a gateway task id into the backend that owns it.
+    pub async fn resolve(&self, task_id: &str) -> Option<TaskRoute> {
+        self.tasks.lock().await.routes.get(task_id).cloned()
+    }
+
+    async fn route_new_message(&self, request: &SendMessageRequest) -> Result<String, A2AError> {
+        let backends = self.backends.read().await;
+        let metadata = [
+            request.metadata.as_ref(),
+            request.message.as_ref().and_then(|m| m.metadata.as_ref()),
+        ];
+        for metadata in metadata.into_iter().flatten() {
+            if let Some(agent) = metadata.get_str(GATEWAY_AGENT_METADATA_KEY) {
+                return match backends.contains_key(agent) {
+                    true => Ok(agent.to_string()),
+                    false => Err(AgentBrokerError::UnknownAgent(agent.to_string()).into()),
+                };
+            }
+            if let Some(skill) = metadata.get_str(SKILL_METADATA_KEY) {
+                let owner = backends
+                    .iter()
+                    .find(|(_, card)| card.skills.iter().any(|s| s.id == skill));
+                if let Some((agent, _)) = owner {
+                    return Ok(agent.clone());
+                }
+            }
+        }
+        if let Some(agent) = &self.default_agent {
+            return Ok(agent.clone());
+        }
+        match backends.len() {
+            1 => Ok(backends.keys().next().cloned().unwrap_or_default()),
+            _ => Err(AgentBrokerError::UnroutableMessage.into()),
+        }
+    }
+
+    /// Resolves a gateway context id into the backend that owns it, the route holding the
+    /// backend context id.
+    async fn resolve_context(&self, context_id: &str) -> Option<TaskRoute> {
+        self.contexts.lock().await.routes.get(context_id).cloned()
+    }
+
+    /// Returns the gateway id for a backend task, issuing a new one the first time it is seen.
+    async fn gateway_id(&self, agent: &str, task_id: &str) -> String {
+        self.tasks
+            .lock()
+            .await
+            .issue(agent, task_id, self.max_routes)
+    }
+
+    /// Returns the gateway id for a backend context, issuing a new one the first time it
+    /// is seen.
+    async fn gateway_context_id(&self, agent: &str, context_id: &str) -> String {
+        self.contexts
+            .lock()
+            .await
+            .issue(agent, context_id, self.max_routes)
+    }
+
+    async fn localize_message(&self, agent: &str, message: &mut Message) {
+        if let Some(task_id) = &message.task_id {
+            message.task_id = Some(self.gateway_id(agent, task_id).await);
+        }
+        if let Some(context_id) = &message.context_id {
+            message.context_id = Some(self.gateway_context_id(agent, context_id).await);
+        }
+    }
+
+    async fn localize_task(&self, agent: &str, mut task: Task) -> Task {
+        task.id = self.gateway_id(agent, &task.id).await;
+        task.context_id = self.gateway_context_id(agent, &task.context_id).await;
+        let status_message = task.status.as_mut().and_then(|s| s.message.as_mut());
+        for message in task.history.iter_mut().chain(status_message) {
+            self.localize_message(agent, message).await;
+        }
+        let terminal = task
+            .status
+            .as_ref()
+            .and_then(|status| TaskState::try_from(status.state).ok())
+            .is_some_and(TaskState::is_terminal);
+        if terminal {
+            self.tasks.lock().await.forget(&task.id);
+        }
+        task
+    }
+}
+
+impl GatewayIds {
+    fn issue(&mut self, agent: &str, backend_id: &str, max_routes: usize) -> String {
+        let route = TaskRoute {
+            agent: agent.to_string(),
+            task_id: backend_id.to_string(),
+        };
+        if let Some(id) = self.reverse.get(&route) {
+            return id.clone();
+        }
+        let id = Uuid::new_v4().to_string();
+        self.routes.insert(id.clone(), route.clone());
+        self.reverse.insert(route, id.clone());
+        self.order.push_back(id.clone());
+        while self.order.len() > max_routes {
+            if let Some(oldest) = self.order.pop_front()
+                && let Some(route) = self.routes.remove(&oldest)
+            {
+                self.reverse.remove(&route);
+            }
+        }
+        id
+    }
+
+    fn forget(&mut self, id: &str) {
+        if let Some(route) = self.routes.remove(id) {
+            self.reverse.remove(&route);
+            self.order.retain(|issued| issued != id);
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl A2A for A2AGateway {
+    async fn send_message(
+        &self,
+        mut request: SendMessageRequest,
+    ) -> Result<SendMessageResponse, A2AError> {
+        let task_id = request.message.as_ref().and_then(|m| m.task_id.clone());
+        let context_id = request.message.as_ref().and_then(|m| m.context_id.clone());
+        let context = match context_id {
+            Some(context_id) => self.resolve_context(&context_id).await,
+            None => None,
+        };
+        let agent = match (task_id, &context) {
+            (Some(task_id), _) => {
+                let route = self
+                    .resolve(&task_id)
+                    .await
+                    .ok_or_else(|| A2AProtocolError::task_not_found(task_id))?;
+                if let Some(message) = request.message.as_mut() {
+                    message.task_id = Some(route.task_id);
+                }
+                route.agent
+            }
+            // follow-ups in a context stay with the backend holding it
+            (None, Some(context)) => context.agent.clone(),
+            (None, None) => self.route_new_message(&request).await?,
+        };
+        if let Some(message) = request.message.as_mut() {
+            // never hand a backend a context id of another backend, or one it did not issue
+            message.context_id = context
+                .filter(|context| context.agent == agent)
+                .map(|context| context.task_id);
+        }
+
+        let mut res = self.broker.send_message(&agent, request).await?;
+        res.payload = match res.payload {
+            Some(SendMessageResponsePayload::Task(task)) => Some(SendMessageResponsePayload::Task(
+                self.localize_task(&agent, task).await,
+            )),
+            Some(SendMessageResponsePayload::Message(mut message)) => {
+                self.localize_message(&agent, &mut message).await;
+                Some(SendMessageResponsePayload::Message(message))
+            }
+            None => None,
+        };
+        Ok(res)
+    }
+
+    async fn get_task(&self, mut request: GetTaskRequest) -> Result<Task, A2AError> {
+        let route = self
+            .resolve(&request.id)
+            .await
+            .ok_or_else(|| A2AProtocolError::task_not_found(request.id.clone()))?;
+        let id = std::mem::replace(&mut request.id, route.task_id);
+        let task = self
+            .broker
+            .get_task(&route.agent, request)
+            .await
+            .map_err(|e| match e {
+                // never leak the backend task id
+                A2AError::Protocol(A2AProtocolError::TaskNotFound { .. }) => {
+                    A2AProtocolError::task_not_found(id).into()
+                }
+                e => e,
+            })?;
+        Ok(self.localize_task(&route.agent, task).await)
+    }
+}
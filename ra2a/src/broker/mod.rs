@@ -1,3 +1,13 @@
+mod error;
+mod fanout;
+mod gateway;
+mod model;
+mod registry;
 mod service;
 
+pub use error::*;
+pub use fanout::*;
+pub use gateway::*;
+pub use model::*;
+pub use registry::*;
 pub use service::*;
@@ -0,0 +1,264 @@
+use crate::broker::AgentBrokerError;
+use crate::client::{A2AClient, A2AClientError};
+use crate::core::agent::AgentCard;
+use crate::core::task::GetTaskRequest;
+use crate::core::{A2A, A2AError, A2AProtocolError, Transport, WELL_KNOWN_AGENT_CARD_PATH};
+use async_trait::async_trait;
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// A single instance of an agent that the broker can route requests to.
+#[derive(Debug, Clone)]
+pub struct AgentReplica {
+    id: String,
+    client: A2AClient,
+    card_url: Option<String>,
+    weight: u32,
+    tags: Vec<String>,
+    state: Arc<ReplicaState>,
+}
+
+#[derive(Debug)]
+struct ReplicaState {
+    healthy: AtomicBool,
+    consecutive_failures: AtomicU32,
+    consecutive_successes: AtomicU32,
+    in_flight: AtomicUsize,
+}
+
+/// Strategy used by the broker to spread new work across healthy replicas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LoadBalancing {
+    #[default]
+    RoundRobin,
+    LeastInFlight,
+    /// Round robin where each replica receives a share of new work proportional to its weight.
+    WeightedRoundRobin,
+}
+
+/// Controls how often replicas are probed and how many consecutive results
+/// are needed before a replica changes health state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HealthCheckConfig {
+    pub interval: Duration,
+    pub timeout: Duration,
+    pub unhealthy_threshold: u32,
+    pub healthy_threshold: u32,
+}
+
+/// Determines whether a replica is able to serve requests.
+#[async_trait]
+pub trait HealthProbe: Debug + Send + Sync {
+    async fn probe(&self, replica: &AgentReplica) -> Result<(), A2AError>;
+}
+
+/// Probes a replica by fetching its agent card, the default probe. Replicas without a
+/// card url are probed with [`TaskLookupProbe`].
+#[derive(Debug, Clone, Default)]
+pub struct AgentCardProbe;
+
+/// Probes a replica by looking up a task that does not exist. Any protocol level
+/// answer (including task not found) proves the replica is up and speaking A2A.
+#[derive(Debug, Clone, Default)]
+pub struct TaskLookupProbe;
+
+/// Decrements the in-flight counter of a replica when dropped.
+#[derive(Debug)]
+pub(crate) struct InFlightGuard {
+    state: Arc<ReplicaState>,
+}
+
+impl AgentReplica {
+    pub fn new(id: impl Into<String>, client: A2AClient) -> Self {
+        Self {
+            id: id.into(),
+            client,
+            card_url: None,
+            weight: 1,
+            tags: vec![],
+            state: Arc::new(ReplicaState {
+                healthy: AtomicBool::new(true),
+                consecutive_failures: AtomicU32::new(0),
+                consecutive_successes: AtomicU32::new(0),
+                in_flight: AtomicUsize::new(0),
+            }),
+        }
+    }
+
+    /// Connects to the replica at `url` and uses the url as the replica id. JSON-RPC
+    /// replicas are probed at the agent card they publish next to their endpoint.
+    pub async fn connect(
+        transport: Transport,
+        url: impl AsRef<str>,
+    ) -> Result<Self, A2AClientError> {
+        let url = url.as_ref();
+        let client = A2AClient::new(transport, url).await?;
+        let replica = Self::new(url, client);
+        Ok(match transport {
+            Transport::JsonRpc => replica.with_card_url(format!(
+                "{}{WELL_KNOWN_AGENT_CARD_PATH}",
+                url.trim_end_matches('/')
+            )),
+            _ => replica,
+        })
+    }
+
+    /// Url of the agent card of the replica, fetched by [`AgentCardProbe`].
+    pub fn with_card_url(mut self, card_url: impl Into<String>) -> Self {
+        self.card_url = Some(card_url.into());
+        self
+    }
+
+    /// Relative share of new work under [`LoadBalancing::WeightedRoundRobin`], at least 1.
+    pub fn with_weight(mut self, weight: u32) -> Self {
+        self.weight = weight.max(1);
+        self
+    }
+
+    pub fn with_tags(mut self, tags: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.tags = tags.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn card_url(&self) -> Option<&str> {
+        self.card_url.as_deref()
+    }
+
+    pub fn weight(&self) -> u32 {
+        self.weight
+    }
+
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    pub fn client(&self) -> &A2AClient {
+        &self.client
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.state.healthy.load(Ordering::Acquire)
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.state.in_flight.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn begin(&self) -> InFlightGuard {
+        self.state.in_flight.fetch_add(1, Ordering::AcqRel);
+        InFlightGuard {
+            state: self.state.clone(),
+        }
+    }
+
+    pub(crate) fn record_success(&self, config: &HealthCheckConfig) {
+        self.state.consecutive_failures.store(0, Ordering::Release);
+        let successes = self
+            .state
+            .consecutive_successes
+            .fetch_add(1, Ordering::AcqRel)
+            + 1;
+        if successes >= config.healthy_threshold && !self.is_healthy() {
+            tracing::info!(replica = %self.id, "replica marked healthy");
+            self.state.healthy.store(true, Ordering::Release);
+        }
+    }
+
+    pub(crate) fn record_failure(&self, config: &HealthCheckConfig) {
+        self.state.consecutive_successes.store(0, Ordering::Release);
+        let failures = self
+            .state
+            .consecutive_failures
+            .fetch_add(1, Ordering::AcqRel)
+            + 1;
+        if failures >= config.unhealthy_threshold && self.is_healthy() {
+            tracing::warn!(replica = %self.id, failures, "replica marked unhealthy");
+            self.state.healthy.store(false, Ordering::Release);
+        }
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.state.in_flight.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+impl Default for HealthCheckConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(10),
+            timeout: Duration::from_secs(2),
+            unhealthy_threshold: 3,
+            healthy_threshold: 1,
+        }
+    }
+}
+
+impl HealthCheckConfig {
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn with_unhealthy_threshold(mut self, threshold: u32) -> Self {
+        self.unhealthy_threshold = threshold.max(1);
+        self
+    }
+
+    pub fn with_healthy_threshold(mut self, threshold: u32) -> Self {
+        self.healthy_threshold = threshold.max(1);
+        self
+    }
+}
+
+#[async_trait]
+impl HealthProbe for AgentCardProbe {
+    async fn probe(&self, replica: &AgentReplica) -> Result<(), A2AError> {
+        let Some(url) = replica.card_url() else {
+            return TaskLookupProbe.probe(replica).await;
+        };
+        let fetch = async {
+            reqwest::get(url)
+                .await?
+                .error_for_status()?
+                .json::<AgentCard>()
+                .await
+        };
+        fetch.await.map_err(|source| AgentBrokerError::CardFetch {
+            url: url.to_string(),
+            source,
+        })?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl HealthProbe for TaskLookupProbe {
+    async fn probe(&self, replica: &AgentReplica) -> Result<(), A2AError> {
+        let res = replica
+            .client()
+            .get_task(GetTaskRequest {
+                id: "ra2a-health-probe".to_string(),
+                history_length: Some(0),
+                metadata: None,
+            })
+            .await;
+        match res {
+            Ok(_) => Ok(()),
+            Err(A2AError::Protocol(A2AProtocolError::TaskNotFound { .. })) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+}
@@ -0,0 +1,285 @@
+use crate::broker::{AgentBroker, AgentBrokerError, AgentReplica};
+use crate::core::Transport;
+use crate::core::agent::{AgentCard, AgentInterface, TransportProtocol};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+/// A declarative description of the agents a broker routes to, usually loaded from a
+/// TOML, YAML or JSON file with [`AgentBroker::load_registry_file`].
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistryConfig {
+    #[serde(default)]
+    pub agents: Vec<RegistryEntry>,
+}
+
+/// A single replica of an agent. Entries that share a name are replicas of the same agent.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistryEntry {
+    pub name: String,
+
+    /// Where the card describing the replica's endpoints comes from.
+    #[serde(flatten)]
+    pub card: CardSource,
+
+    /// Transport used to reach the replica, picked from the interfaces of the card.
+    /// Defaults to the first interface with a transport this crate supports.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preferred_transport: Option<TransportProtocol>,
+
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CardSource {
+    /// URL the card is fetched from every time the registry is loaded.
+    CardUrl(String),
+    Card(Box<AgentCard>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegistryFormat {
+    Toml,
+    Yaml,
+    Json,
+}
+
+/// Handle to a registry file watch. Watching stops when the handle is dropped.
+#[derive(Debug)]
+pub struct RegistryWatchHandle {
+    handle: Option<JoinHandle<()>>,
+}
+
+fn default_weight() -> u32 {
+    1
+}
+
+impl RegistryFormat {
+    /// Picks the format from the file extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "toml" => Some(RegistryFormat::Toml),
+            "yaml" | "yml" => Some(RegistryFormat::Yaml),
+            "json" => Some(RegistryFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+impl RegistryConfig {
+    pub fn parse(contents: &str, format: RegistryFormat) -> Result<Self, AgentBrokerError> {
+        let res = match format {
+            RegistryFormat::Toml => toml::from_str(contents).map_err(|e| e.to_string()),
+            RegistryFormat::Yaml => serde_yaml::from_str(contents).map_err(|e| e.to_string()),
+            RegistryFormat::Json => serde_json::from_str(contents).map_err(|e| e.to_string()),
+        };
+        res.map_err(AgentBrokerError::InvalidRegistry)
+    }
+
+    pub async fn from_file(path: impl AsRef<Path>) -> Result<Self, AgentBrokerError> {
+        let path = path.as_ref();
+        Self::parse(&read(path).await?, format(path)?)
+    }
+}
+
+impl AgentBroker {
+    /// Registers the agents described by `config`.
+    ///
+    /// Agents registered by a previous config that are missing from this one are
+    /// deregistered. Replicas that are still listed keep their health state and task
+    /// routes. Every entry is resolved before the broker is changed, so an invalid
+    /// config leaves the broker untouched.
+    pub async fn load_registry(&self, config: &RegistryConfig) -> Result<(), AgentBrokerError> {
+        let mut registry_agents = self.registry_agents.lock().await;
+
+        let mut agents: BTreeMap<String, Vec<AgentReplica>> = BTreeMap::new();
+        for entry in &config.agents {
+            let (transport, url) = resolve_interface(entry).await?;
+            let card_url = match &entry.card {
+                CardSource::CardUrl(card_url) => Some(card_url.clone()),
+                CardSource::Card(_) => None,
+            };
+            let existing = self
+                .replicas(&entry.name)
+                .await
+                .ok()
+                .and_then(|replicas| replicas.into_iter().find(|r| r.id() == url));
+            let replica = match existing {
+                Some(replica) => replica,
+                None => {
+                    let replica = AgentReplica::connect(transport, &url)
+                        .await
+                        .map_err(|source| AgentBrokerError::Connect { url, source })?;
+                    match card_url {
+                        Some(card_url) => replica.with_card_url(card_url),
+                        None => replica,
+                    }
+                }
+            };
+            agents
+                .entry(entry.name.clone())
+                .or_default()
+                .push(replica.with_weight(entry.weight).with_tags(&entry.tags));
+        }
+
+        for agent in registry_agents.iter() {
+            if !agents.contains_key(agent) {
+                tracing::info!(agent, "agent removed from registry");
+                self.deregister(agent).await;
+            }
+        }
+        *registry_agents = agents.keys().cloned().collect::<HashSet<_>>();
+        for (agent, replicas) in agents {
+            self.replace_replicas(&agent, replicas).await;
+        }
+        Ok(())
+    }
+
+    pub async fn load_registry_file(&self, path: impl AsRef<Path>) -> Result<(), AgentBrokerError> {
+        self.load_registry(&RegistryConfig::from_file(path).await?)
+            .await
+    }
+
+    /// Loads the registry file, then reloads it every time its contents change, checking
+    /// on the given interval. A reload that fails is logged and retried on the next check,
+    /// the broker keeps the agents from the last successful load in the meantime.
+    pub async fn watch_registry(
+        &self,
+        path: impl Into<PathBuf>,
+        interval: Duration,
+    ) -> Result<RegistryWatchHandle, AgentBrokerError> {
+        let path = path.into();
+        let format = format(&path)?;
+        let mut loaded = read(&path).await?;
+        self.load_registry(&RegistryConfig::parse(&loaded, format)?)
+            .await?;
+
+        let broker = self.clone();
+        let handle = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            // the first tick completes immediately
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                let contents = match read(&path).await {
+                    Ok(contents) => contents,
+                    Err(e) => {
+                        tracing::warn!(path = %path.display(), error = ?e, "failed to read registry");
+                        continue;
+                    }
+                };
+                if contents == loaded {
+                    continue;
+                }
+                let res = match RegistryConfig::parse(&contents, format) {
+                    Ok(config) => broker.load_registry(&config).await,
+                    Err(e) => Err(e),
+                };
+                match res {
+                    Ok(()) => {
+                        tracing::info!(path = %path.display(), "registry reloaded");
+                        loaded = contents;
+                    }
+                    Err(e) => {
+                        tracing::warn!(path = %path.display(), error = ?e, "failed to reload registry")
+                    }
+                }
+            }
+        });
+        Ok(RegistryWatchHandle {
+            handle: Some(handle),
+        })
+    }
+}
+
+impl RegistryWatchHandle {
+    pub fn stop(mut self) {
+        if let Some(handle) = self.handle.take() {
+            handle.abort();
+        }
+    }
+}
+
+impl Drop for RegistryWatchHandle {
+    fn drop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            handle.abort();
+        }
+    }
+}
+
+async fn read(path: &Path) -> Result<String, AgentBrokerError> {
+    tokio::fs::read_to_string(path)
+        .await
+        .map_err(|source| AgentBrokerError::RegistryIo {
+            path: path.to_path_buf(),
+            source,
+        })
+}
+
+fn format(path: &Path) -> Result<RegistryFormat, AgentBrokerError> {
+    RegistryFormat::from_path(path).ok_or_else(|| {
+        AgentBrokerError::InvalidRegistry(format!(
+            "unknown registry format for {}, expected .toml, .yaml, .yml or .json",
+            path.display()
+        ))
+    })
+}
+
+async fn fetch_agent_card(url: &str) -> Result<AgentCard, AgentBrokerError> {
+    let fetch = async {
+        reqwest::get(url)
+            .await?
+            .error_for_status()?
+            .json::<AgentCard>()
+            .await
+    };
+    fetch.await.map_err(|source| AgentBrokerError::CardFetch {
+        url: url.to_string(),
+        source,
+    })
+}
+
+/// Picks the transport and url used to reach the replica described by `entry`.
+async fn resolve_interface(entry: &RegistryEntry) -> Result<(Transport, String), AgentBrokerError> {
+    let card = match &entry.card {
+        CardSource::Card(card) => Cow::Borrowed(card.as_ref()),
+        CardSource::CardUrl(url) => Cow::Owned(fetch_agent_card(url).await?),
+    };
+    let main = AgentInterface::new(&card.url, card.preferred_transport.unwrap_or_default());
+    std::iter::once(&main)
+        .chain(&card.additional_interfaces)
+        .filter(|i| !i.url.is_empty())
+        .filter(|i| {
+            entry
+                .preferred_transport
+                .is_none_or(|wanted| i.transport.unwrap_or_default() == wanted)
+        })
+        .find_map(|i| {
+            Some((
+                supported_transport(i.transport.unwrap_or_default())?,
+                i.url.clone(),
+            ))
+        })
+        .ok_or_else(|| AgentBrokerError::NoSupportedInterface(entry.name.clone()))
+}
+
+fn supported_transport(protocol: TransportProtocol) -> Option<Transport> {
+    match protocol {
+        TransportProtocol::JsonRpc => Some(Transport::JsonRpc),
+        #[cfg(feature = "grpc")]
+        TransportProtocol::Grpc => Some(Transport::Grpc),
+        _ => None,
+    }
+}
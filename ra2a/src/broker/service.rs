@@ -1,4 +1,397 @@
+use crate::broker::{
+    AgentBrokerError, AgentCardProbe, AgentReplica, HealthCheckConfig, HealthProbe, LoadBalancing,
+};
+use crate::core::message::{SendMessageRequest, SendMessageResponse, SendMessageResponsePayload};
+use crate::core::task::{GetTaskRequest, Task, TaskState};
+use crate::core::{A2A, A2AError, A2AProtocolError};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::sync::{Mutex, RwLock};
+use tokio::task::JoinHandle;
+
+/// Task routes kept per agent by default, see [`AgentBroker::with_max_task_routes`].
+const DEFAULT_MAX_TASK_ROUTES: usize = 10_000;
+
+/// Routes requests to named agents, each of which may be served by several replicas.
+///
+/// Tasks live in the store of the replica that created them, so once a replica
+/// returns a task every follow-up for that task is routed back to the same replica.
+/// Routes are dropped once the task is seen in a terminal state.
 #[derive(Debug, Clone)]
-pub struct AgentBroker {}
+pub struct AgentBroker {
+    pools: Arc<RwLock<HashMap<String, Arc<AgentPool>>>>,
+    load_balancing: LoadBalancing,
+    health_check: HealthCheckConfig,
+    probe: Arc<dyn HealthProbe>,
+    max_task_routes: usize,
+    // agents declared by the last loaded registry config
+    pub(crate) registry_agents: Arc<Mutex<HashSet<String>>>,
+}
+
+#[derive(Debug, Default)]
+struct AgentPool {
+    replicas: RwLock<Vec<AgentReplica>>,
+    cursor: AtomicUsize,
+    task_routes: Mutex<TaskRoutes>,
+}
+
+/// The replica of each task, oldest first.
+#[derive(Debug, Default)]
+struct TaskRoutes {
+    // task id -> replica id
+    replicas: HashMap<String, String>,
+    order: VecDeque<String>,
+}
+
+/// Handle to the background health checking loop. The loop stops when the handle is dropped.
+#[derive(Debug)]
+pub struct BrokerHealthHandle {
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Default for AgentBroker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AgentBroker {
+    pub fn new() -> Self {
+        Self {
+            pools: Arc::new(RwLock::new(HashMap::new())),
+            load_balancing: LoadBalancing::default(),
+            health_check: HealthCheckConfig::default(),
+            probe: Arc::new(AgentCardProbe),
+            max_task_routes: DEFAULT_MAX_TASK_ROUTES,
+            registry_agents: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    pub fn with_load_balancing(mut self, load_balancing: LoadBalancing) -> Self {
+        self.load_balancing = load_balancing;
+        self
+    }
+
+    pub fn with_health_check(mut self, health_check: HealthCheckConfig) -> Self {
+        self.health_check = health_check;
+        self
+    }
+
+    /// How replicas are probed, [`AgentCardProbe`] by default.
+    pub fn with_health_probe(mut self, probe: impl HealthProbe + 'static) -> Self {
+        self.probe = Arc::new(probe);
+        self
+    }
+
+    /// Maximum number of task routes kept per agent, the oldest being dropped first.
+    /// Follow-ups of a task whose route was dropped go to any replica.
+    pub fn with_max_task_routes(mut self, max_task_routes: usize) -> Self {
+        self.max_task_routes = max_task_routes.max(1);
+        self
+    }
+
+    /// Adds a replica to the named agent, creating the agent if it is not yet known.
+    pub async fn register(&self, agent: impl Into<String>, replica: AgentReplica) {
+        let pool = self
+            .pools
+            .write()
+            .await
+            .entry(agent.into())
+            .or_default()
+            .clone();
+        let mut replicas = pool.replicas.write().await;
+        replicas.retain(|r| r.id() != replica.id());
+        replicas.push(replica);
+    }
+
+    /// Replaces the replicas of the named agent, creating the agent if it is not yet known.
+    pub(crate) async fn replace_replicas(&self, agent: &str, replicas: Vec<AgentReplica>) {
+        let pool = self
+            .pools
+            .write()
+            .await
+            .entry(agent.to_string())
+            .or_default()
+            .clone();
+        *pool.replicas.write().await = replicas;
+    }
+
+    /// Removes the named agent and all of its replicas.
+    pub async fn deregister(&self, agent: &str) -> bool {
+        self.pools.write().await.remove(agent).is_some()
+    }
+
+    pub async fn agents(&self) -> Vec<String> {
+        self.pools.read().await.keys().cloned().collect()
+    }
+
+    /// Agents with at least one replica carrying `tag`.
+    pub async fn agents_with_tag(&self, tag: &str) -> Vec<String> {
+        let mut agents = vec![];
+        for (agent, pool) in self.pools.read().await.iter() {
+            let replicas = pool.replicas.read().await;
+            if replicas.iter().any(|r| r.tags().iter().any(|t| t == tag)) {
+                agents.push(agent.clone());
+            }
+        }
+        agents
+    }
+
+    pub async fn replicas(&self, agent: &str) -> Result<Vec<AgentReplica>, AgentBrokerError> {
+        let pool = self.pool(agent).await?;
+        Ok(pool.replicas.read().await.clone())
+    }
+
+    /// Returns the replica that owns `task_id`, if the broker has seen the task.
+    pub async fn task_owner(&self, agent: &str, task_id: &str) -> Option<AgentReplica> {
+        let pool = self.pool(agent).await.ok()?;
+        pool.route(task_id).await
+    }
+
+    /// Sends a message to the named agent. Follow-up messages for a known task are
+    /// routed to the replica that owns the task; new work goes to a healthy replica
+    /// chosen by the configured load balancing strategy.
+    pub async fn send_message(
+        &self,
+        agent: &str,
+        request: SendMessageRequest,
+    ) -> Result<SendMessageResponse, A2AError> {
+        let pool = self.pool(agent).await?;
+        let task_id = request.message.as_ref().and_then(|m| m.task_id.clone());
+        let sticky = match &task_id {
+            Some(task_id) => pool.route(task_id).await,
+            None => None,
+        };
+        let replica = match sticky {
+            Some(replica) => replica,
+            None => pool
+                .select(self.load_balancing)
+                .await
+                .ok_or_else(|| AgentBrokerError::NoHealthyReplica(agent.to_string()))?,
+        };
+
+        let res = self.call_send_message(&replica, request).await?;
+        if let Some(SendMessageResponsePayload::Task(task)) = &res.payload {
+            pool.bind(task, replica.id(), self.max_task_routes).await;
+        }
+        Ok(res)
+    }
+
+    /// Fetches a task from the named agent. The owning replica is tried first and,
+    /// because the call is idempotent, other healthy replicas are tried if it is unreachable.
+    pub async fn get_task(&self, agent: &str, request: GetTaskRequest) -> Result<Task, A2AError> {
+        let pool = self.pool(agent).await?;
+        let owner = pool.route(&request.id).await;
+        let mut candidates = pool.healthy().await;
+        if let Some(owner) = &owner {
+            candidates.retain(|r| r.id() != owner.id());
+            candidates.insert(0, owner.clone());
+        }
+        if candidates.is_empty() {
+            return Err(AgentBrokerError::NoHealthyReplica(agent.to_string()).into());
+        }
+
+        let mut owner_err = None;
+        let mut last_err = None;
+        for replica in candidates {
+            let is_owner = owner.as_ref().is_some_and(|o| o.id() == replica.id());
+            match self.call_get_task(&replica, request.clone()).await {
+                Ok(task) => {
+                    pool.bind(&task, replica.id(), self.max_task_routes).await;
+                    return Ok(task);
+                }
+                // the owner is authoritative, other replicas will not know about the task either
+                Err(e @ A2AError::Protocol(A2AProtocolError::TaskNotFound { .. })) if is_owner => {
+                    return Err(e);
+                }
+                // other replicas cannot tell better than the unreachable owner
+                Err(e @ A2AError::Transport(_)) if is_owner => owner_err = Some(e),
+                Err(e @ A2AError::Protocol(A2AProtocolError::TaskNotFound { .. }))
+                | Err(e @ A2AError::Transport(_)) => last_err = Some(e),
+                Err(e) => return Err(e),
+            }
+        }
+        Err(owner_err
+            .or(last_err)
+            .unwrap_or_else(|| A2AProtocolError::task_not_found(request.id).into()))
+    }
+
+    /// Probes every registered replica once and updates its health state.
+    pub async fn check_health(&self) {
+        let pools: Vec<Arc<AgentPool>> = self.pools.read().await.values().cloned().collect();
+        for pool in pools {
+            let replicas = pool.replicas.read().await.clone();
+            let probes = replicas.iter().map(|replica| async move {
+                let res =
+                    tokio::time::timeout(self.health_check.timeout, self.probe.probe(replica))
+                        .await;
+                match res {
+                    Ok(Ok(())) => replica.record_success(&self.health_check),
+                    Ok(Err(e)) => {
+                        tracing::debug!(replica = replica.id(), error = ?e, "health probe failed");
+                        replica.record_failure(&self.health_check)
+                    }
+                    Err(_) => {
+                        tracing::debug!(replica = replica.id(), "health probe timed out");
+                        replica.record_failure(&self.health_check)
+                    }
+                }
+            });
+            futures::future::join_all(probes).await;
+        }
+    }
+
+    /// Spawns a background loop that probes all replicas on the configured interval.
+    pub fn start_health_checks(&self) -> BrokerHealthHandle {
+        let broker = self.clone();
+        let handle = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(broker.health_check.interval);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                broker.check_health().await;
+            }
+        });
+        BrokerHealthHandle {
+            handle: Some(handle),
+        }
+    }
+
+    async fn pool(&self, agent: &str) -> Result<Arc<AgentPool>, AgentBrokerError> {
+        self.pools
+            .read()
+            .await
+            .get(agent)
+            .cloned()
+            .ok_or_else(|| AgentBrokerError::UnknownAgent(agent.to_string()))
+    }
+
+    async fn call_send_message(
+        &self,
+        replica: &AgentReplica,
+        request: SendMessageRequest,
+    ) -> Result<SendMessageResponse, A2AError> {
+        let _guard = replica.begin();
+        let res = replica.client().send_message(request).await;
+        self.observe(replica, &res);
+        res
+    }
+
+    async fn call_get_task(
+        &self,
+        replica: &AgentReplica,
+        request: GetTaskRequest,
+    ) -> Result<Task, A2AError> {
+        let _guard = replica.begin();
+        let res = replica.client().get_task(request).await;
+        self.observe(replica, &res);
+        res
+    }
+
+    /// Passive health checking, transport failures count against the replica.
+    fn observe<T>(&self, replica: &AgentReplica, res: &Result<T, A2AError>) {
+        match res {
+            Err(A2AError::Transport(_)) => replica.record_failure(&self.health_check),
+            _ => replica.record_success(&self.health_check),
+        }
+    }
+}
+
+impl AgentPool {
+    async fn route(&self, task_id: &str) -> Option<AgentReplica> {
+        let replica_id = self
+            .task_routes
+            .lock()
+            .await
+            .replicas
+            .get(task_id)
+            .cloned()?;
+        self.replicas
+            .read()
+            .await
+            .iter()
+            .find(|r| r.id() == replica_id)
+            .cloned()
+    }
+
+    /// Routes the follow-ups of `task` to the replica that returned it, until it is over.
+    async fn bind(&self, task: &Task, replica_id: &str, max_routes: usize) {
+        let mut routes = self.task_routes.lock().await;
+        let terminal = task
+            .status
+            .as_ref()
+            .and_then(|status| TaskState::try_from(status.state).ok())
+            .is_some_and(TaskState::is_terminal);
+        if terminal {
+            if routes.replicas.remove(&task.id).is_some() {
+                routes.order.retain(|id| id != &task.id);
+            }
+            return;
+        }
+        let previous = routes
+            .replicas
+            .insert(task.id.clone(), replica_id.to_string());
+        if previous.is_none() {
+            routes.order.push_back(task.id.clone());
+        }
+        while routes.order.len() > max_routes {
+            if let Some(oldest) = routes.order.pop_front() {
+                routes.replicas.remove(&oldest);
+            }
+        }
+    }
+
+    async fn select(&self, load_balancing: LoadBalancing) -> Option<AgentReplica> {
+        let healthy = self.healthy().await;
+        if healthy.is_empty() {
+            return None;
+        }
+        match load_balancing {
+            LoadBalancing::RoundRobin => {
+                let idx = self.cursor.fetch_add(1, Ordering::Relaxed) % healthy.len();
+                healthy.into_iter().nth(idx)
+            }
+            LoadBalancing::LeastInFlight => healthy.into_iter().min_by_key(|r| r.in_flight()),
+            LoadBalancing::WeightedRoundRobin => {
+                let total: usize = healthy.iter().map(|r| r.weight() as usize).sum();
+                let mut idx = self.cursor.fetch_add(1, Ordering::Relaxed) % total;
+                healthy
+                    .into_iter()
+                    .find(|r| match idx < r.weight() as usize {
+                        true => true,
+                        false => {
+                            idx -= r.weight() as usize;
+                            false
+                        }
+                    })
+            }
+        }
+    }
+
+    async fn healthy(&self) -> Vec<AgentReplica> {
+        self.replicas
+            .read()
+            .await
+            .iter()
+            .filter(|r| r.is_healthy())
+            .cloned()
+            .collect()
+    }
+}
+
+impl BrokerHealthHandle {
+    pub fn stop(mut self) {
+        if let Some(handle) = self.handle.take() {
+            handle.abort();
+        }
+    }
+}
 
-impl AgentBroker {}
+impl Drop for BrokerHealthHandle {
+    fn drop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            handle.abort();
+        }
+    }
+}
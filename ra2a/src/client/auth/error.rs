@@ -0,0 +1,26 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum CredentialError {
+    #[error("No available credentials satisfy the security requirements of the agent card")]
+    Unsatisfiable,
+
+    #[error("Invalid value for credential header {0}")]
+    InvalidHeader(String),
+
+    #[error("Failed to build the http client for token requests")]
+    HttpClient(#[source] reqwest::Error),
+
+    #[error("Token url {0} is not trusted with the client secret")]
+    UntrustedTokenUrl(String),
+
+    #[error("Token request to {url} failed")]
+    TokenRequest {
+        url: String,
+        #[source]
+        source: reqwest::Error,
+    },
+
+    #[error("Token endpoint {url} responded with status {status}")]
+    TokenRejected { url: String, status: u16 },
+}
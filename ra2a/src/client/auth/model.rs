@@ -0,0 +1,76 @@
+use crate::client::auth::CredentialError;
+use http::{HeaderMap, HeaderName, HeaderValue};
+use serde::Deserialize;
+use std::time::Instant;
+
+/// A secret the client holds for one of the security schemes of a remote agent card.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClientCredential {
+    ApiKey(String),
+    /// Bearer token, presented for http bearer, OAuth2 and OpenID Connect schemes.
+    Bearer(String),
+    Basic {
+        username: String,
+        password: String,
+    },
+    /// Client id and secret, exchanged for access tokens at the token url of an
+    /// OAuth2 client credentials flow.
+    ClientCredentials {
+        client_id: String,
+        client_secret: String,
+    },
+}
+
+/// Headers and query parameters that carry the credentials of a request.
+#[derive(Debug, Clone, Default)]
+pub struct AppliedCredentials {
+    pub headers: HeaderMap,
+    pub query: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct CachedToken {
+    pub(crate) access_token: String,
+    pub(crate) expires_at: Option<Instant>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct TokenResponse {
+    pub(crate) access_token: String,
+    #[serde(default)]
+    pub(crate) expires_in: Option<u64>,
+}
+
+impl AppliedCredentials {
+    pub fn is_empty(&self) -> bool {
+        self.headers.is_empty() && self.query.is_empty()
+    }
+
+    pub(crate) fn insert_header(&mut self, name: &str, value: &str) -> Result<(), CredentialError> {
+        let invalid = || CredentialError::InvalidHeader(name.to_string());
+        let name = HeaderName::try_from(name).map_err(|_| invalid())?;
+        let mut value = HeaderValue::try_from(value).map_err(|_| invalid())?;
+        value.set_sensitive(true);
+        self.headers.append(name, value);
+        Ok(())
+    }
+
+    /// Adds the credentials to an outgoing http request.
+    pub fn apply<B>(&self, request: &mut http::Request<B>) {
+        for (name, value) in &self.headers {
+            request.headers_mut().append(name, value.clone());
+        }
+        if self.query.is_empty() {
+            return;
+        }
+        let Ok(mut url) = reqwest::Url::parse(&request.uri().to_string()) else {
+            tracing::warn!(uri = %request.uri(), "cannot add credential query parameters");
+            return;
+        };
+        url.query_pairs_mut().extend_pairs(&self.query);
+        match url.as_str().parse() {
+            Ok(uri) => *request.uri_mut() = uri,
+            Err(e) => tracing::warn!(error = %e, "cannot add credential query parameters"),
+        }
+    }
+}
@@ -0,0 +1,302 @@
+use crate::client::auth::{
+    AppliedCredentials, CachedToken, ClientCredential, CredentialError, TokenResponse,
+};
+use crate::core::agent::{AgentCard, Security};
+use crate::core::util::{ClientCredentialsOAuth2Flow, Flow, Scheme, SecurityScheme};
+use async_trait::async_trait;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use http::header::{AUTHORIZATION, COOKIE};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// Default time before expiry at which cached access tokens are refreshed.
+pub const DEFAULT_TOKEN_REFRESH_SKEW: Duration = Duration::from_secs(30);
+
+/// Default time allowed for a token endpoint to answer.
+pub const DEFAULT_TOKEN_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+type TokenSlot = Arc<Mutex<Option<CachedToken>>>;
+
+/// Supplies the credentials a client holds for the security schemes of a remote agent card.
+#[async_trait]
+pub trait CredentialProvider: Debug + Send + Sync {
+    /// Returns the credential for the card scheme named `scheme_name`, `None` if the
+    /// client has none.
+    async fn credential(
+        &self,
+        scheme_name: &str,
+        scheme: &SecurityScheme,
+    ) -> Option<ClientCredential>;
+
+    /// Whether the client secret for the scheme `scheme_name` may be sent to `token_url`,
+    /// as declared by the remote card. Every token url is allowed by default, pin them so
+    /// a spoofed card cannot collect the secret.
+    fn allows_token_url(&self, _scheme_name: &str, _token_url: &str) -> bool {
+        true
+    }
+}
+
+/// Holds a fixed credential per card scheme name.
+#[derive(Debug, Clone, Default)]
+pub struct StaticCredentialProvider {
+    credentials: HashMap<String, ClientCredential>,
+    token_urls: HashMap<String, String>,
+}
+
+/// Attaches credentials satisfying the `security` requirements of a remote agent card
+/// to outgoing requests.
+///
+/// The first requirement the provider has credentials for is used. Access tokens
+/// obtained through OAuth2 client credentials flows are cached and refreshed shortly
+/// before they expire.
+#[derive(Debug, Clone)]
+pub struct ClientAuthentication {
+    schemes: HashMap<String, SecurityScheme>,
+    requirements: Vec<Security>,
+    provider: Arc<dyn CredentialProvider>,
+    http: reqwest::Client,
+    refresh_skew: Duration,
+    // token url, client id and scopes -> token, locked on its own while it is fetched
+    tokens: Arc<Mutex<HashMap<String, TokenSlot>>>,
+}
+
+impl StaticCredentialProvider {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Presents `credential` for the card scheme named `scheme_name`.
+    pub fn with_credential(
+        mut self,
+        scheme_name: impl Into<String>,
+        credential: ClientCredential,
+    ) -> Self {
+        self.credentials.insert(scheme_name.into(), credential);
+        self
+    }
+
+    /// Only sends the client secret for the scheme `scheme_name` to `token_url`.
+    pub fn with_token_url(
+        mut self,
+        scheme_name: impl Into<String>,
+        token_url: impl Into<String>,
+    ) -> Self {
+        self.token_urls.insert(scheme_name.into(), token_url.into());
+        self
+    }
+}
+
+#[async_trait]
+impl CredentialProvider for StaticCredentialProvider {
+    async fn credential(
+        &self,
+        scheme_name: &str,
+        _scheme: &SecurityScheme,
+    ) -> Option<ClientCredential> {
+        self.credentials.get(scheme_name).cloned()
+    }
+
+    fn allows_token_url(&self, scheme_name: &str, token_url: &str) -> bool {
+        self.token_urls
+            .get(scheme_name)
+            .is_none_or(|pinned| pinned == token_url)
+    }
+}
+
+impl ClientAuthentication {
+    pub fn new(
+        card: &AgentCard,
+        provider: Arc<dyn CredentialProvider>,
+    ) -> Result<Self, CredentialError> {
+        Ok(Self {
+            schemes: card.security_schemes.clone(),
+            requirements: card.security.clone(),
+            provider,
+            http: reqwest::Client::builder()
+                .timeout(DEFAULT_TOKEN_REQUEST_TIMEOUT)
+                .build()
+                .map_err(CredentialError::HttpClient)?,
+            refresh_skew: DEFAULT_TOKEN_REFRESH_SKEW,
+            tokens: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+
+    /// How long before expiry cached access tokens are refreshed.
+    pub fn with_refresh_skew(mut self, refresh_skew: Duration) -> Self {
+        self.refresh_skew = refresh_skew;
+        self
+    }
+
+    /// Client used to call token endpoints, with a timeout of
+    /// [`DEFAULT_TOKEN_REQUEST_TIMEOUT`] by default.
+    pub fn with_http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = http;
+        self
+    }
+
+    /// Resolves the headers and query parameters for the next request.
+    pub async fn credentials(&self) -> Result<AppliedCredentials, CredentialError> {
+        if self.requirements.is_empty() {
+            return Ok(AppliedCredentials::default());
+        }
+        for requirement in &self.requirements {
+            match self.satisfy(requirement).await {
+                Ok(credentials) => return Ok(credentials),
+                Err(CredentialError::Unsatisfiable) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Err(CredentialError::Unsatisfiable)
+    }
+
+    async fn satisfy(&self, requirement: &Security) -> Result<AppliedCredentials, CredentialError> {
+        let mut names: Vec<&String> = requirement.schemes.keys().collect();
+        names.sort();
+        let mut applied = AppliedCredentials::default();
+        for name in names {
+            let scheme = self
+                .schemes
+                .get(name)
+                .ok_or(CredentialError::Unsatisfiable)?;
+            let credential = self
+                .provider
+                .credential(name, scheme)
+                .await
+                .ok_or(CredentialError::Unsatisfiable)?;
+            let scopes = &requirement.schemes[name].list;
+            self.attach(&mut applied, name, scheme, credential, scopes)
+                .await?;
+        }
+        Ok(applied)
+    }
+
+    async fn attach(
+        &self,
+        applied: &mut AppliedCredentials,
+        scheme_name: &str,
+        scheme: &SecurityScheme,
+        credential: ClientCredential,
+        scopes: &[String],
+    ) -> Result<(), CredentialError> {
+        let scheme = scheme
+            .scheme
+            .as_ref()
+            .ok_or(CredentialError::Unsatisfiable)?;
+        match (scheme, credential) {
+            (Scheme::APIKey(api_key), ClientCredential::ApiKey(key)) => {
+                match api_key.location.as_str() {
+                    "header" => applied.insert_header(&api_key.name, &key)?,
+                    "query" => applied.query.push((api_key.name.clone(), key)),
+                    "cookie" => applied
+                        .insert_header(COOKIE.as_str(), &format!("{}={key}", api_key.name))?,
+                    _ => return Err(CredentialError::Unsatisfiable),
+                }
+            }
+            (Scheme::HTTPAuth(http), ClientCredential::Bearer(token))
+                if http.scheme.eq_ignore_ascii_case("bearer") =>
+            {
+                applied.insert_header(AUTHORIZATION.as_str(), &format!("Bearer {token}"))?
+            }
+            (Scheme::HTTPAuth(http), ClientCredential::Basic { username, password })
+                if http.scheme.eq_ignore_ascii_case("basic") =>
+            {
+                let encoded = STANDARD.encode(format!("{username}:{password}"));
+                applied.insert_header(AUTHORIZATION.as_str(), &format!("Basic {encoded}"))?
+            }
+            (Scheme::OAuth2(_) | Scheme::OpenIDConnect(_), ClientCredential::Bearer(token)) => {
+                applied.insert_header(AUTHORIZATION.as_str(), &format!("Bearer {token}"))?
+            }
+            (
+                Scheme::OAuth2(oauth2),
+                ClientCredential::ClientCredentials {
+                    client_id,
+                    client_secret,
+                },
+            ) => {
+                let Some(Flow::ClientCredentials(flow)) =
+                    oauth2.flows.as_ref().and_then(|f| f.flow.as_ref())
+                else {
+                    return Err(CredentialError::Unsatisfiable);
+                };
+                if !self.provider.allows_token_url(scheme_name, &flow.token_url) {
+                    return Err(CredentialError::UntrustedTokenUrl(flow.token_url.clone()));
+                }
+                let token = self
+                    .access_token(flow, &client_id, &client_secret, scopes)
+                    .await?;
+                applied.insert_header(AUTHORIZATION.as_str(), &format!("Bearer {token}"))?
+            }
+            _ => return Err(CredentialError::Unsatisfiable),
+        }
+        Ok(())
+    }
+
+    /// Returns a cached access token for the flow, fetching a new one when there is none
+    /// or it is about to expire.
+    async fn access_token(
+        &self,
+        flow: &ClientCredentialsOAuth2Flow,
+        client_id: &str,
+        client_secret: &str,
+        scopes: &[String],
+    ) -> Result<String, CredentialError> {
+        let scope = scopes.join(" ");
+        let key = format!("{} {client_id} {scope}", flow.token_url);
+        let slot = self.tokens.lock().await.entry(key).or_default().clone();
+        // held across the fetch so concurrent requests for the same token share one
+        // token request, without holding up requests for other tokens
+        let mut slot = slot.lock().await;
+        if let Some(token) = slot.as_ref() {
+            let fresh = token.expires_at.is_none_or(|expires_at| {
+                Instant::now()
+                    .checked_add(self.refresh_skew)
+                    .is_some_and(|refresh_at| refresh_at < expires_at)
+            });
+            if fresh {
+                return Ok(token.access_token.clone());
+            }
+        }
+
+        let url = flow.token_url.clone();
+        let mut form = vec![("grant_type", "client_credentials")];
+        if !scope.is_empty() {
+            form.push(("scope", &scope));
+        }
+        let res = self
+            .http
+            .post(&url)
+            .basic_auth(client_id, Some(client_secret))
+            .form(&form)
+            .send()
+            .await
+            .map_err(|source| CredentialError::TokenRequest {
+                url: url.clone(),
+                source,
+            })?;
+        if !res.status().is_success() {
+            return Err(CredentialError::TokenRejected {
+                url,
+                status: res.status().as_u16(),
+            });
+        }
+        let token: TokenResponse = res
+            .json()
+            .await
+            .map_err(|source| CredentialError::TokenRequest { url, source })?;
+        tracing::debug!(token_url = flow.token_url, "fetched access token");
+        let cached = CachedToken {
+            // lifetimes beyond what an instant can hold never expire
+            expires_at: token
+                .expires_in
+                .and_then(|secs| Instant::now().checked_add(Duration::from_secs(secs))),
+            access_token: token.access_token,
+        };
+        let access_token = cached.access_token.clone();
+        *slot = Some(cached);
+        Ok(access_token)
+    }
+}
@@ -1,4 +1,6 @@
 use crate::client::jsonrpc::A2AJsonRpcClientError;
+use crate::jws::JwsError;
+use crate::tls::TlsError;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -9,4 +11,24 @@ pub enum A2AClientError {
 
     #[error("json-rpc")]
     JsonRpc(#[from] A2AJsonRpcClientError),
+
+    #[error("Failed to fetch agent card from {url}")]
+    CardFetch {
+        url: String,
+        #[source]
+        source: reqwest::Error,
+    },
+
+    #[error("Invalid agent card from {url}")]
+    CardDecode {
+        url: String,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    #[error("Agent card failed verification")]
+    CardVerification(#[from] JwsError),
+
+    #[error("tls")]
+    Tls(#[from] TlsError),
 }
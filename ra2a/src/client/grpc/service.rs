@@ -1,77 +1,248 @@
+use crate::client::auth::ClientAuthentication;
 use crate::client::grpc::A2AGrpcClientError;
+use crate::client::{A2AClientConfig, ClientInterceptor, ClientTransport};
+use crate::core::agent::{AgentCard, GetAgentCardRequest};
 use crate::core::message::{SendMessageRequest, SendMessageResponse};
 use crate::core::task::{GetTaskGrpcRequest, GetTaskRequest, Task};
 use crate::core::{
-    A2A, A2AError, A2AErrorCode, A2AProtocolError, GRPC_GET_TASK_PATH, GRPC_SEND_MESSAGE_PATH,
+    A2A, A2AError, A2AProtocolError, A2ARequest, A2AResponse, A2ATransportError,
+    GRPC_GET_AGENT_CARD_PATH, GRPC_GET_TASK_PATH, GRPC_SEND_MESSAGE_PATH, Transport,
 };
+use crate::grpc::GrpcConfig;
 use async_trait::async_trait;
+use http::HeaderMap;
 use http::uri::PathAndQuery;
+use std::sync::Arc;
 use tonic::client::Grpc;
-use tonic::transport::Channel;
-use tonic::{Code, Request};
+use tonic::metadata::MetadataMap;
+use tonic::transport::{Channel, Endpoint};
+use tonic::{Code, Extensions, Request, Status};
 use tonic_prost::ProstCodec;
 
 #[derive(Debug, Clone)]
 pub struct A2AGrpcClient {
     channel: Channel,
+    authentication: Option<ClientAuthentication>,
+    interceptors: Arc<[Arc<dyn ClientInterceptor>]>,
+    grpc: Arc<GrpcConfig>,
 }
 
 impl A2AGrpcClient {
     pub async fn new(url: impl Into<String>) -> Result<Self, A2AGrpcClientError> {
+        Self::new_with_config(url, A2AClientConfig::default()).await
+    }
+
+    pub async fn new_with_config(
+        url: impl Into<String>,
+        config: A2AClientConfig,
+    ) -> Result<Self, A2AGrpcClientError> {
         let url = url.into();
-        let channel = Channel::from_shared(url)?.connect().await?;
-        Ok(Self { channel })
+        let interceptors = config.call_interceptors(&url);
+        let mut endpoint = Self::endpoint(Channel::from_shared(url)?, &config);
+        if let Some(tls) = &config.tls {
+            endpoint = endpoint.tls_config(tls.grpc_config())?;
+        }
+        let channel = endpoint.connect().await?;
+        Ok(Self::from_channel(channel, config, interceptors))
     }
-}
 
-#[async_trait]
-impl A2A for A2AGrpcClient {
-    async fn send_message(
+    /// Creates a client of the agent listening on the Unix domain socket at `path`.
+    #[cfg(unix)]
+    pub async fn new_unix(
+        path: impl AsRef<std::path::Path>,
+        config: A2AClientConfig,
+    ) -> Result<Self, A2AGrpcClientError> {
+        let path = path.as_ref();
+        let interceptors = config.call_interceptors(&format!("unix:{}", path.display()));
+        let endpoint = Self::endpoint(Endpoint::from_static("http://localhost"), &config);
+        let channel = endpoint
+            .connect_with_connector(crate::client::local::UnixConnector::new(path))
+            .await?;
+        Ok(Self::from_channel(channel, config, interceptors))
+    }
+
+    fn endpoint(endpoint: Endpoint, config: &A2AClientConfig) -> Endpoint {
+        let endpoint = config.grpc.endpoint(endpoint);
+        match config.timeout {
+            // Calls are timed out by the interceptors, tonic reports its own deadlines as
+            // cancellations.
+            Some(timeout) => endpoint.connect_timeout(timeout),
+            None => endpoint,
+        }
+    }
+
+    fn from_channel(
+        channel: Channel,
+        config: A2AClientConfig,
+        interceptors: Arc<[Arc<dyn ClientInterceptor>]>,
+    ) -> Self {
+        Self {
+            channel,
+            authentication: config.authentication,
+            interceptors,
+            grpc: Arc::new(config.grpc),
+        }
+    }
+
+    /// Wraps `message` in a request carrying the headers of the interceptors and the
+    /// credentials required by the remote card. Query parameter credentials have no
+    /// equivalent in gRPC and are not sent.
+    async fn request<T>(&self, message: T, mut headers: HeaderMap) -> Result<Request<T>, A2AError> {
+        if let Some(authentication) = &self.authentication {
+            let credentials = authentication
+                .credentials()
+                .await
+                .map_err(A2ATransportError::from)?;
+            if !credentials.query.is_empty() {
+                tracing::warn!("query parameter credentials are not supported over grpc");
+            }
+            for (name, value) in credentials.headers {
+                if let Some(name) = name {
+                    headers.append(name, value);
+                }
+            }
+        }
+        Ok(Request::from_parts(
+            MetadataMap::from_headers(headers),
+            Extensions::default(),
+            message,
+        ))
+    }
+
+    async fn send_message_rpc(
         &self,
         request: SendMessageRequest,
+        headers: HeaderMap,
     ) -> Result<SendMessageResponse, A2AError> {
-        let mut grpc = Grpc::new(self.channel.clone());
+        let mut grpc = self.grpc.client(Grpc::new(self.channel.clone()));
         grpc.ready()
             .await
             .map_err(|e| tonic::Status::unavailable(format!("client not ready: {e}")))?;
+        let request = self.request(request, headers).await?;
         let res = grpc
             .unary(
-                Request::new(request),
+                request,
                 PathAndQuery::from_static(GRPC_SEND_MESSAGE_PATH),
                 ProstCodec::<SendMessageRequest, SendMessageResponse>::default(),
             )
             .await;
-        match res {
-            Ok(res) => Ok(res.into_inner()),
-            // todo unpack various grpc error codes to discover protocol errors underneath, which probably depends on the spec getting updated
-            Err(err) => Err(A2AError::from(err)),
-        }
+        res.map(|res| res.into_inner()).map_err(protocol_error)
     }
 
-    async fn get_task(&self, request: GetTaskRequest) -> Result<Task, A2AError> {
+    async fn get_task_rpc(
+        &self,
+        request: GetTaskRequest,
+        headers: HeaderMap,
+    ) -> Result<Task, A2AError> {
         let task_id = request.id.clone();
         let request: GetTaskGrpcRequest = request.into();
-        let mut grpc = Grpc::new(self.channel.clone());
+        let mut grpc = self.grpc.client(Grpc::new(self.channel.clone()));
         grpc.ready()
             .await
             .map_err(|e| tonic::Status::unavailable(format!("client not ready: {e}")))?;
+        let request = self.request(request, headers).await?;
         let res = grpc
             .unary(
-                Request::new(request),
+                request,
                 PathAndQuery::from_static(GRPC_GET_TASK_PATH),
                 ProstCodec::<GetTaskGrpcRequest, Task>::default(),
             )
             .await;
-        match res {
-            Ok(res) => Ok(res.into_inner()),
-            // todo unpack various grpc error codes to discover protocol errors underneath, which probably depends on the spec getting updated
-            Err(err) => match err.code() {
-                Code::NotFound => Err(A2AError::Protocol(A2AProtocolError::TaskNotFound {
-                    id: task_id,
-                    code: A2AErrorCode::TaskNotFound,
-                })),
-                _ => Err(A2AError::from(err)),
-            },
+        res.map(|res| res.into_inner())
+            .map_err(|err| match protocol_error(err) {
+                // servers that do not send the a2a code still report missing tasks as not found
+                A2AError::Transport(A2ATransportError::Grcp(err))
+                    if err.code() == Code::NotFound =>
+                {
+                    A2AProtocolError::task_not_found(task_id).into()
+                }
+                A2AError::Protocol(A2AProtocolError::TaskNotFound { id, .. }) if id.is_empty() => {
+                    A2AProtocolError::task_not_found(task_id).into()
+                }
+                e => e,
+            })
+    }
+
+    async fn get_authenticated_extended_card_rpc(
+        &self,
+        headers: HeaderMap,
+    ) -> Result<AgentCard, A2AError> {
+        let mut grpc = self.grpc.client(Grpc::new(self.channel.clone()));
+        grpc.ready()
+            .await
+            .map_err(|e| tonic::Status::unavailable(format!("client not ready: {e}")))?;
+        let request = self.request(GetAgentCardRequest {}, headers).await?;
+        let res = grpc
+            .unary(
+                request,
+                PathAndQuery::from_static(GRPC_GET_AGENT_CARD_PATH),
+                ProstCodec::<GetAgentCardRequest, AgentCard>::default(),
+            )
+            .await;
+        res.map(|res| res.into_inner()).map_err(protocol_error)
+    }
+}
+
+#[async_trait]
+impl A2A for A2AGrpcClient {
+    async fn send_message(
+        &self,
+        request: SendMessageRequest,
+    ) -> Result<SendMessageResponse, A2AError> {
+        self.call(A2ARequest::SendMessage(Box::new(request)))
+            .await?
+            .into_send_message()
+    }
+
+    async fn get_task(&self, request: GetTaskRequest) -> Result<Task, A2AError> {
+        self.call(A2ARequest::GetTask(request)).await?.into_task()
+    }
+
+    async fn get_authenticated_extended_card(&self) -> Result<AgentCard, A2AError> {
+        self.call(A2ARequest::GetAuthenticatedExtendedCard)
+            .await?
+            .into_agent_card()
+    }
+}
+
+#[async_trait]
+impl ClientTransport for A2AGrpcClient {
+    fn transport(&self) -> Transport {
+        Transport::Grpc
+    }
+
+    fn interceptors(&self) -> &[Arc<dyn ClientInterceptor>] {
+        &self.interceptors
+    }
+
+    async fn send(&self, request: A2ARequest, headers: HeaderMap) -> Result<A2AResponse, A2AError> {
+        match request {
+            A2ARequest::SendMessage(request) => self
+                .send_message_rpc(*request, headers)
+                .await
+                .map(A2AResponse::SendMessage),
+            A2ARequest::GetTask(request) => self
+                .get_task_rpc(request, headers)
+                .await
+                .map(A2AResponse::GetTask),
+            A2ARequest::GetAuthenticatedExtendedCard => self
+                .get_authenticated_extended_card_rpc(headers)
+                .await
+                .map(A2AResponse::GetAuthenticatedExtendedCard),
         }
     }
 }
+
+/// Rebuilds protocol errors from the A2A `code` metadata and JSON details of a status.
+fn protocol_error(status: Status) -> A2AError {
+    let code = status
+        .metadata()
+        .get("code")
+        .and_then(|code| code.to_str().ok())
+        .and_then(|code| code.parse().ok());
+    let data = serde_json::from_slice(status.details()).ok();
+    match code.and_then(|code| A2AProtocolError::from_code(code, data.as_ref())) {
+        Some(err) => err.into(),
+        None => A2AError::from(status),
+    }
+}
@@ -0,0 +1,157 @@
+use crate::agent::{A2ADelegate, RequestContext};
+use crate::client::auth::ClientAuthentication;
+use crate::client::jsonrpc::protocol_error;
+use crate::client::{A2AClientConfig, ClientInterceptor, ClientTransport};
+use crate::core::agent::AgentCard;
+use crate::core::message::{SendMessageRequest, SendMessageResponse};
+use crate::core::task::{GetTaskRequest, Task};
+use crate::core::{A2A, A2AError, A2ARequest, A2AResponse, A2ATransportError, Transport};
+use crate::server::jsonrpc::error_object;
+use async_trait::async_trait;
+use http::HeaderMap;
+use jsonrpsee::core::ClientError;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::sync::Arc;
+
+/// Calls an [`A2ADelegate`] of the same process, without a network hop, e.g. to embed
+/// agents in a larger binary or to test them.
+///
+/// Calls go through the interceptors of the client, then the authentication, rate limits
+/// and middlewares of the delegate, like over any transport. Interceptors see them as
+/// JSON-RPC calls. In strict mode, requests and responses are serialized to JSON and back,
+/// and the errors of the agent are reduced to what a JSON-RPC client would receive, to
+/// catch values that would not survive the wire.
+#[derive(Debug, Clone)]
+pub struct A2AInProcessClient {
+    delegate: Arc<A2ADelegate>,
+    authentication: Option<ClientAuthentication>,
+    interceptors: Arc<[Arc<dyn ClientInterceptor>]>,
+    strict: bool,
+}
+
+impl A2AInProcessClient {
+    pub fn new(delegate: A2ADelegate) -> Self {
+        Self::new_with_config(delegate, A2AClientConfig::default())
+    }
+
+    /// Applies the credentials and interceptors of `config`, its network settings are
+    /// ignored.
+    pub fn new_with_config(delegate: A2ADelegate, config: A2AClientConfig) -> Self {
+        Self {
+            delegate: Arc::new(delegate),
+            authentication: config.authentication.clone(),
+            interceptors: config.call_interceptors("in-process"),
+            strict: false,
+        }
+    }
+
+    /// Serializes requests, responses and errors as JSON-RPC would.
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Authenticates the call like the transports do, from the headers it carries.
+    async fn request_context(&self, headers: HeaderMap) -> Result<RequestContext, A2AError> {
+        let mut request = http::Request::new(());
+        *request.headers_mut() = headers;
+        if let Some(authentication) = &self.authentication {
+            let credentials = authentication
+                .credentials()
+                .await
+                .map_err(A2ATransportError::from)?;
+            credentials.apply(&mut request);
+        }
+        let context = self.delegate.request_context(&request).await?;
+        self.delegate.check_rate_limits(&context).await?;
+        Ok(context)
+    }
+
+    async fn dispatch(
+        &self,
+        context: &RequestContext,
+        request: A2ARequest,
+    ) -> Result<A2AResponse, A2AError> {
+        let delegate = &self.delegate;
+        match request {
+            A2ARequest::SendMessage(request) => {
+                let request = self.wire(*request)?;
+                let response = delegate.send_message_with_context(context, request).await;
+                self.wire_result(response).map(A2AResponse::SendMessage)
+            }
+            A2ARequest::GetTask(request) => {
+                let request = self.wire(request)?;
+                let response = delegate.get_task_with_context(context, request).await;
+                self.wire_result(response).map(A2AResponse::GetTask)
+            }
+            A2ARequest::GetAuthenticatedExtendedCard => {
+                let response = delegate
+                    .get_authenticated_extended_card_with_context(context)
+                    .await;
+                self.wire_result(response)
+                    .map(A2AResponse::GetAuthenticatedExtendedCard)
+            }
+        }
+    }
+
+    /// `value` as the other side of the wire would see it, in strict mode.
+    fn wire<T: Serialize + DeserializeOwned>(&self, value: T) -> Result<T, A2AError> {
+        if !self.strict {
+            return Ok(value);
+        }
+        let json = serde_json::to_vec(&value).map_err(ClientError::ParseError);
+        let value =
+            json.and_then(|json| serde_json::from_slice(&json).map_err(ClientError::ParseError));
+        value.map_err(|e| A2ATransportError::from(e).into())
+    }
+
+    fn wire_result<T: Serialize + DeserializeOwned>(
+        &self,
+        result: Result<T, A2AError>,
+    ) -> Result<T, A2AError> {
+        match result {
+            Ok(value) => self.wire(value),
+            Err(e) if self.strict => Err(protocol_error(ClientError::Call(error_object(e)))),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[async_trait]
+impl A2A for A2AInProcessClient {
+    async fn send_message(
+        &self,
+        request: SendMessageRequest,
+    ) -> Result<SendMessageResponse, A2AError> {
+        self.call(A2ARequest::SendMessage(Box::new(request)))
+            .await?
+            .into_send_message()
+    }
+
+    async fn get_task(&self, request: GetTaskRequest) -> Result<Task, A2AError> {
+        self.call(A2ARequest::GetTask(request)).await?.into_task()
+    }
+
+    async fn get_authenticated_extended_card(&self) -> Result<AgentCard, A2AError> {
+        self.call(A2ARequest::GetAuthenticatedExtendedCard)
+            .await?
+            .into_agent_card()
+    }
+}
+
+#[async_trait]
+impl ClientTransport for A2AInProcessClient {
+    fn transport(&self) -> Transport {
+        Transport::JsonRpc
+    }
+
+    fn interceptors(&self) -> &[Arc<dyn ClientInterceptor>] {
+        &self.interceptors
+    }
+
+    async fn send(&self, request: A2ARequest, headers: HeaderMap) -> Result<A2AResponse, A2AError> {
+        let context = self.request_context(headers).await?;
+        self.dispatch(&context, request).await
+    }
+}
@@ -4,4 +4,10 @@ use thiserror::Error;
 pub enum A2AJsonRpcClientError {
     #[error("Json RPC client error")]
     JsonRpc(#[from] jsonrpsee::core::ClientError),
+
+    #[error("Failed to spawn agent process")]
+    Spawn(#[source] std::io::Error),
+
+    #[error("Tls")]
+    Tls(#[from] crate::tls::TlsError),
 }
@@ -0,0 +1,166 @@
+use crate::client::auth::ClientAuthentication;
+use http::HeaderMap;
+use jsonrpsee::core::BoxError;
+use jsonrpsee::core::http_helpers::HttpError;
+use jsonrpsee::http_client::transport::Error as TransportError;
+use jsonrpsee::http_client::{HttpBody, HttpRequest};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+
+tokio::task_local! {
+    /// Headers the interceptors added to the call being sent.
+    pub(crate) static CALL_HEADERS: HeaderMap;
+}
+
+/// Http middleware adding the headers of the interceptors and the credentials required by the remote agent card to every request.
+#[derive(Debug, Clone)]
+pub struct CredentialLayer {
+    authentication: Option<ClientAuthentication>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CredentialService<S> {
+    inner: S,
+    authentication: Option<ClientAuthentication>,
+}
+
+/// Http middleware sending requests over a Unix domain socket instead of the http
+/// connection of the client, when configured with a socket path.
+#[derive(Debug, Clone)]
+pub struct UnixSocketLayer {
+    #[cfg(unix)]
+    client: Option<UnixClient>,
+}
+
+#[derive(Debug, Clone)]
+pub struct UnixSocketService<S> {
+    inner: S,
+    #[cfg(unix)]
+    client: Option<UnixClient>,
+}
+
+#[cfg(unix)]
+type UnixClient = hyper_util::client::legacy::Client<crate::client::local::UnixConnector, HttpBody>;
+
+impl CredentialLayer {
+    pub fn new(authentication: Option<ClientAuthentication>) -> Self {
+        Self { authentication }
+    }
+}
+
+impl<S> Layer<S> for CredentialLayer {
+    type Service = CredentialService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        CredentialService {
+            inner,
+            authentication: self.authentication.clone(),
+        }
+    }
+}
+
+impl<S, R> Service<HttpRequest> for CredentialService<S>
+where
+    S: Service<HttpRequest, Response = R, Error = TransportError> + Clone + Send + 'static,
+    S::Future: Send,
+{
+    type Response = R;
+    type Error = TransportError;
+    type Future = Pin<Box<dyn Future<Output = Result<R, TransportError>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: HttpRequest) -> Self::Future {
+        let _ = CALL_HEADERS.try_with(|headers| {
+            for (name, value) in headers {
+                request.headers_mut().append(name, value.clone());
+            }
+        });
+        let Some(authentication) = self.authentication.clone() else {
+            return Box::pin(self.inner.call(request));
+        };
+        // the clone is not guaranteed to be ready, call the one that is
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        Box::pin(async move {
+            let credentials = authentication
+                .credentials()
+                .await
+                .map_err(|e| TransportError::Http(HttpError::Stream(BoxError::from(e))))?;
+            credentials.apply(&mut request);
+            inner.call(request).await
+        })
+    }
+}
+
+impl UnixSocketLayer {
+    /// Leaves requests on the http connection of the client.
+    pub fn disabled() -> Self {
+        Self {
+            #[cfg(unix)]
+            client: None,
+        }
+    }
+
+    #[cfg(unix)]
+    pub fn new(path: impl Into<std::path::PathBuf>) -> Self {
+        let connector = crate::client::local::UnixConnector::new(path);
+        let client =
+            hyper_util::client::legacy::Client::builder(hyper_util::rt::TokioExecutor::new())
+                .build(connector);
+        Self {
+            client: Some(client),
+        }
+    }
+}
+
+impl<S> Layer<S> for UnixSocketLayer {
+    type Service = UnixSocketService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        UnixSocketService {
+            inner,
+            #[cfg(unix)]
+            client: self.client.clone(),
+        }
+    }
+}
+
+impl<S> Service<HttpRequest> for UnixSocketService<S>
+where
+    S: Service<
+            HttpRequest,
+            Response = http::Response<hyper::body::Incoming>,
+            Error = TransportError,
+        >,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = TransportError;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, TransportError>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        #[cfg(unix)]
+        if self.client.is_some() {
+            return Poll::Ready(Ok(()));
+        }
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: HttpRequest) -> Self::Future {
+        #[cfg(unix)]
+        if let Some(client) = &self.client {
+            let response = client.request(request);
+            return Box::pin(async move {
+                response
+                    .await
+                    .map_err(|e| TransportError::Http(HttpError::Stream(BoxError::from(e))))
+            });
+        }
+        Box::pin(self.inner.call(request))
+    }
+}
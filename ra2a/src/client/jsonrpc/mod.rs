@@ -1,5 +1,7 @@
 mod error;
+mod middleware;
 mod service;
 
 pub use error::*;
+pub use middleware::*;
 pub use service::*;
@@ -1,23 +1,147 @@
-use crate::client::jsonrpc::A2AJsonRpcClientError;
+use crate::client::jsonrpc::{
+    A2AJsonRpcClientError, CALL_HEADERS, CredentialLayer, CredentialService, UnixSocketLayer,
+    UnixSocketService,
+};
+use crate::client::local::{LineReceiver, LineSender};
+use crate::client::{A2AClientConfig, ClientInterceptor, ClientTransport};
+use crate::core::agent::AgentCard;
 use crate::core::message::{SendMessageRequest, SendMessageResponse};
 use crate::core::task::{GetTaskRequest, Task};
 use crate::core::{
-    A2A, A2AError, A2AErrorCode, A2AProtocolError, JSONRPC_GET_TASK_METHOD,
-    JSONRPC_SEND_MESSAGE_METHOD,
+    A2A, A2AError, A2AProtocolError, A2ARequest, A2AResponse,
+    JSONRPC_GET_AUTHENTICATED_EXTENDED_CARD_METHOD, JSONRPC_GET_TASK_METHOD,
+    JSONRPC_SEND_MESSAGE_METHOD, Transport,
 };
+use http::HeaderMap;
+use jsonrpsee::core::ClientError;
 use jsonrpsee::core::client::ClientT;
-use jsonrpsee::http_client::{HttpClient, HttpClientBuilder};
+use jsonrpsee::core::client::async_client::{Client, ClientBuilder};
+use jsonrpsee::core::traits::ToRpcParams;
+use jsonrpsee::http_client::{HttpBackend, HttpClient, HttpClientBuilder, RpcLogger, RpcService};
+use jsonrpsee::rpc_params;
+use serde::de::DeserializeOwned;
+use std::sync::Arc;
+use tokio::process::{Child, Command};
+
+type CredentialHttpClient =
+    HttpClient<RpcLogger<RpcService<CredentialService<UnixSocketService<HttpBackend>>>>>;
 
 #[derive(Debug, Clone)]
 pub struct A2AJsonRpcClient {
-    client: HttpClient,
+    client: RpcClient,
+    interceptors: Arc<[Arc<dyn ClientInterceptor>]>,
+}
+
+/// Sends the calls over http, or over the stdio of an agent process.
+#[derive(Debug, Clone)]
+enum RpcClient {
+    Http(CredentialHttpClient),
+    Stdio {
+        client: Arc<Client>,
+        // killed when the last clone of the client is dropped
+        _child: Arc<Child>,
+    },
 }
 
 impl A2AJsonRpcClient {
     pub fn new(url: impl AsRef<str>) -> Result<Self, A2AJsonRpcClientError> {
-        let client = HttpClientBuilder::default().build(url)?;
+        Self::new_with_config(url, A2AClientConfig::default())
+    }
 
-        Ok(A2AJsonRpcClient { client })
+    pub fn new_with_config(
+        url: impl AsRef<str>,
+        config: A2AClientConfig,
+    ) -> Result<Self, A2AJsonRpcClientError> {
+        let interceptors = config.call_interceptors(url.as_ref());
+        Self::http(url, config, UnixSocketLayer::disabled(), interceptors)
+    }
+
+    /// Creates a client of the agent listening on the Unix domain socket at `path`.
+    #[cfg(unix)]
+    pub fn new_unix(
+        path: impl AsRef<std::path::Path>,
+        config: A2AClientConfig,
+    ) -> Result<Self, A2AJsonRpcClientError> {
+        let path = path.as_ref();
+        let interceptors = config.call_interceptors(&format!("unix:{}", path.display()));
+        let unix = UnixSocketLayer::new(path);
+        Self::http("http://localhost", config, unix, interceptors)
+    }
+
+    /// Spawns `command` and talks to the agent it runs over its stdin and stdout, one
+    /// JSON-RPC message per line. The process is killed when the client is dropped.
+    /// Headers, including credentials, have no equivalent over stdio and are not sent.
+    pub fn spawn(
+        mut command: Command,
+        config: A2AClientConfig,
+    ) -> Result<Self, A2AJsonRpcClientError> {
+        let program = command
+            .as_std()
+            .get_program()
+            .to_string_lossy()
+            .into_owned();
+        let interceptors = config.call_interceptors(&format!("stdio:{program}"));
+        let mut child = command
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(A2AJsonRpcClientError::Spawn)?;
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            unreachable!("stdio of the child is piped");
+        };
+        let mut builder = ClientBuilder::default();
+        if let Some(timeout) = config.timeout {
+            builder = builder.request_timeout(timeout);
+        }
+        let client = builder.build_with_tokio(LineSender::new(stdin), LineReceiver::new(stdout));
+        Ok(A2AJsonRpcClient {
+            client: RpcClient::Stdio {
+                client: Arc::new(client),
+                _child: Arc::new(child),
+            },
+            interceptors,
+        })
+    }
+
+    fn http(
+        url: impl AsRef<str>,
+        config: A2AClientConfig,
+        unix: UnixSocketLayer,
+        interceptors: Arc<[Arc<dyn ClientInterceptor>]>,
+    ) -> Result<Self, A2AJsonRpcClientError> {
+        let mut builder = HttpClientBuilder::default();
+        if let Some(timeout) = config.timeout {
+            builder = builder.request_timeout(timeout);
+        }
+        if let Some(tls) = &config.tls {
+            builder = builder.with_custom_cert_store(tls.rustls_config()?);
+        }
+        let client = builder
+            .set_http_middleware(
+                tower::ServiceBuilder::new()
+                    .layer(CredentialLayer::new(config.authentication))
+                    .layer(unix),
+            )
+            .build(url)?;
+
+        Ok(A2AJsonRpcClient {
+            client: RpcClient::Http(client),
+            interceptors,
+        })
+    }
+}
+
+impl RpcClient {
+    async fn request<R, P>(&self, method: &str, params: P) -> Result<R, ClientError>
+    where
+        R: DeserializeOwned,
+        P: ToRpcParams + Send,
+    {
+        match self {
+            RpcClient::Http(client) => client.request(method, params).await,
+            RpcClient::Stdio { client, .. } => client.request(method, params).await,
+        }
     }
 }
 
@@ -27,31 +151,95 @@ impl A2A for A2AJsonRpcClient {
         &self,
         request: SendMessageRequest,
     ) -> Result<SendMessageResponse, A2AError> {
-        let response = self
-            .client
-            .request(JSONRPC_SEND_MESSAGE_METHOD, request)
-            .await?;
-        Ok(response)
+        self.call(A2ARequest::SendMessage(Box::new(request)))
+            .await?
+            .into_send_message()
     }
 
     async fn get_task(&self, request: GetTaskRequest) -> Result<Task, A2AError> {
+        self.call(A2ARequest::GetTask(request)).await?.into_task()
+    }
+
+    async fn get_authenticated_extended_card(&self) -> Result<AgentCard, A2AError> {
+        self.call(A2ARequest::GetAuthenticatedExtendedCard)
+            .await?
+            .into_agent_card()
+    }
+}
+
+#[async_trait::async_trait]
+impl ClientTransport for A2AJsonRpcClient {
+    fn transport(&self) -> Transport {
+        Transport::JsonRpc
+    }
+
+    fn interceptors(&self) -> &[Arc<dyn ClientInterceptor>] {
+        &self.interceptors
+    }
+
+    async fn send(&self, request: A2ARequest, headers: HeaderMap) -> Result<A2AResponse, A2AError> {
+        let send = async {
+            match request {
+                A2ARequest::SendMessage(request) => self
+                    .send_message_rpc(*request)
+                    .await
+                    .map(A2AResponse::SendMessage),
+                A2ARequest::GetTask(request) => {
+                    self.get_task_rpc(request).await.map(A2AResponse::GetTask)
+                }
+                A2ARequest::GetAuthenticatedExtendedCard => self
+                    .get_authenticated_extended_card_rpc()
+                    .await
+                    .map(A2AResponse::GetAuthenticatedExtendedCard),
+            }
+        };
+        CALL_HEADERS.scope(headers, send).await
+    }
+}
+
+impl A2AJsonRpcClient {
+    async fn send_message_rpc(
+        &self,
+        request: SendMessageRequest,
+    ) -> Result<SendMessageResponse, A2AError> {
+        self.client
+            .request(JSONRPC_SEND_MESSAGE_METHOD, request)
+            .await
+            .map_err(protocol_error)
+    }
+
+    async fn get_task_rpc(&self, request: GetTaskRequest) -> Result<Task, A2AError> {
         let task_id = request.id.to_string();
         let response = self.client.request(JSONRPC_GET_TASK_METHOD, request).await;
-        match response {
-            Ok(response) => Ok(response),
-            Err(jsonrpsee::core::client::Error::Call(e)) => {
-                if e.code() == A2AErrorCode::TaskNotFound as i32 {
-                    Err(A2AError::Protocol(A2AProtocolError::TaskNotFound {
-                        id: task_id,
-                        code: A2AErrorCode::TaskNotFound,
-                    }))
-                } else {
-                    Err(A2AError::Transport(
-                        jsonrpsee::core::client::Error::Call(e).into(),
-                    ))
-                }
+        response.map_err(|e| match protocol_error(e) {
+            // older servers do not send the task id back
+            A2AError::Protocol(A2AProtocolError::TaskNotFound { id, .. }) if id.is_empty() => {
+                A2AProtocolError::task_not_found(task_id).into()
             }
-            Err(e) => Err(A2AError::Transport(e.into())),
+            e => e,
+        })
+    }
+
+    async fn get_authenticated_extended_card_rpc(&self) -> Result<AgentCard, A2AError> {
+        self.client
+            .request(
+                JSONRPC_GET_AUTHENTICATED_EXTENDED_CARD_METHOD,
+                rpc_params![],
+            )
+            .await
+            .map_err(protocol_error)
+    }
+}
+
+/// Rebuilds protocol errors from the A2A code and data of a call error.
+pub(crate) fn protocol_error(e: ClientError) -> A2AError {
+    if let ClientError::Call(call) = &e {
+        let data = call
+            .data()
+            .and_then(|data| serde_json::from_str(data.get()).ok());
+        if let Some(err) = A2AProtocolError::from_code(call.code(), data.as_ref()) {
+            return err.into();
         }
     }
+    A2AError::Transport(e.into())
 }
@@ -0,0 +1,100 @@
+use hyper_util::rt::TokioIo;
+use jsonrpsee::core::client::{ReceivedMessage, TransportReceiverT, TransportSenderT};
+use std::future::Future;
+use std::io;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader, Lines};
+use tower::Service;
+
+/// Connects http clients to a Unix domain socket, whatever the uri they are given.
+#[cfg(unix)]
+#[derive(Debug, Clone)]
+pub(crate) struct UnixConnector {
+    path: Arc<PathBuf>,
+}
+
+/// Sends newline-delimited JSON-RPC messages, e.g. to the stdin of an agent process.
+#[derive(Debug)]
+pub(crate) struct LineSender<W> {
+    writer: W,
+}
+
+/// Receives newline-delimited JSON-RPC messages, e.g. from the stdout of an agent process.
+#[derive(Debug)]
+pub(crate) struct LineReceiver<R> {
+    lines: Lines<BufReader<R>>,
+}
+
+#[cfg(unix)]
+impl UnixConnector {
+    pub(crate) fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: Arc::new(path.into()),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Service<http::Uri> for UnixConnector {
+    type Response = TokioIo<tokio::net::UnixStream>;
+    type Error = io::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _uri: http::Uri) -> Self::Future {
+        let path = self.path.clone();
+        Box::pin(async move {
+            let stream = tokio::net::UnixStream::connect(path.as_ref()).await?;
+            Ok(TokioIo::new(stream))
+        })
+    }
+}
+
+impl<W> LineSender<W> {
+    pub(crate) fn new(writer: W) -> Self {
+        Self { writer }
+    }
+}
+
+impl<W: AsyncWrite + Send + Unpin + 'static> TransportSenderT for LineSender<W> {
+    type Error = io::Error;
+
+    async fn send(&mut self, msg: String) -> Result<(), Self::Error> {
+        // serialized messages never contain raw newlines, they are escaped in strings
+        self.writer.write_all(msg.as_bytes()).await?;
+        self.writer.write_all(b"\n").await?;
+        self.writer.flush().await
+    }
+
+    async fn close(&mut self) -> Result<(), Self::Error> {
+        self.writer.shutdown().await
+    }
+}
+
+impl<R: AsyncRead + Unpin> LineReceiver<R> {
+    pub(crate) fn new(reader: R) -> Self {
+        Self {
+            lines: BufReader::new(reader).lines(),
+        }
+    }
+}
+
+impl<R: AsyncRead + Send + Unpin + 'static> TransportReceiverT for LineReceiver<R> {
+    type Error = io::Error;
+
+    async fn receive(&mut self) -> Result<ReceivedMessage, Self::Error> {
+        loop {
+            match self.lines.next_line().await? {
+                Some(line) if line.trim().is_empty() => continue,
+                Some(line) => return Ok(ReceivedMessage::Text(line)),
+                None => return Err(io::ErrorKind::UnexpectedEof.into()),
+            }
+        }
+    }
+}
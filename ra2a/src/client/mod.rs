@@ -1,9 +1,17 @@
+mod model;
 mod service;
 
+pub mod auth;
 mod error;
 #[cfg(feature = "grpc")]
 pub mod grpc;
+#[cfg(feature = "agent")]
+pub mod inprocess;
 pub mod jsonrpc;
+mod local;
+pub mod resilience;
+pub mod watch;
 
 pub use error::*;
+pub use model::*;
 pub use service::*;
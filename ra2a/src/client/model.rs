@@ -0,0 +1,155 @@
+use crate::client::auth::ClientAuthentication;
+use crate::client::resilience::{CircuitBreaker, RetryPolicy};
+use crate::client::{ClientInterceptor, ClientTransport};
+use crate::core::{A2ARequest, Transport};
+use crate::jws::CardVerifier;
+use crate::tls::ClientTlsConfig;
+use http::HeaderMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Connection settings shared by every client transport.
+#[derive(Debug, Clone, Default)]
+pub struct A2AClientConfig {
+    pub authentication: Option<ClientAuthentication>,
+    pub tls: Option<ClientTlsConfig>,
+    pub card_verifier: Option<CardVerifier>,
+    /// Run in order around every call, the first one outermost.
+    pub interceptors: Vec<Arc<dyn ClientInterceptor>>,
+    /// Deadline of every attempt of a call.
+    pub timeout: Option<Duration>,
+    pub retry: Option<RetryPolicy>,
+    pub circuit_breaker: Option<CircuitBreaker>,
+    #[cfg(feature = "grpc")]
+    pub grpc: crate::grpc::GrpcConfig,
+}
+
+/// Builds an [`A2AClient`](crate::client::A2AClient), see [`A2AClient::builder`](crate::client::A2AClient::builder).
+#[derive(Debug, Clone)]
+pub struct A2AClientBuilder {
+    pub(crate) transport: Transport,
+    pub(crate) url: String,
+    pub(crate) config: A2AClientConfig,
+}
+
+/// An outgoing call, as seen by a [`ClientInterceptor`].
+#[derive(Debug, Clone)]
+pub struct ClientCall {
+    pub transport: Transport,
+    pub request: A2ARequest,
+    /// Sent along with the request, as http headers or gRPC metadata.
+    pub headers: HeaderMap,
+}
+
+/// The rest of the interceptor chain, ending with the transport sending the call.
+#[derive(Clone, Copy)]
+pub struct ClientNext<'a> {
+    pub(crate) transport: &'a dyn ClientTransport,
+    pub(crate) interceptors: &'a [Arc<dyn ClientInterceptor>],
+}
+
+impl A2AClientConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Attaches the credentials required by the remote agent card to every request.
+    pub fn with_authentication(mut self, authentication: ClientAuthentication) -> Self {
+        self.authentication = Some(authentication);
+        self
+    }
+
+    /// Used for `https` urls, the platform roots are trusted by default.
+    pub fn with_tls(mut self, tls: ClientTlsConfig) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+
+    /// Refuses fetched agent cards that do not verify, see
+    /// [`A2AClient::fetch_agent_card`](crate::client::A2AClient::fetch_agent_card).
+    pub fn with_card_verifier(mut self, card_verifier: CardVerifier) -> Self {
+        self.card_verifier = Some(card_verifier);
+        self
+    }
+
+    /// Appends `interceptor` to the chain wrapping every call of the client, whichever
+    /// transport it goes over.
+    pub fn with_interceptor(mut self, interceptor: impl ClientInterceptor + 'static) -> Self {
+        self.interceptors.push(Arc::new(interceptor));
+        self
+    }
+
+    /// Fails attempts of a call that take longer than `timeout`, connecting included.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Retries idempotent calls failing because the remote agent is unreachable, overloaded
+    /// or too slow.
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// Fails calls fast while the remote agent is down.
+    pub fn with_circuit_breaker(mut self, circuit_breaker: CircuitBreaker) -> Self {
+        self.circuit_breaker = Some(circuit_breaker);
+        self
+    }
+
+    /// Codec limits, compression and HTTP/2 settings of gRPC clients.
+    #[cfg(feature = "grpc")]
+    pub fn with_grpc(mut self, grpc: crate::grpc::GrpcConfig) -> Self {
+        self.grpc = grpc;
+        self
+    }
+}
+
+impl A2AClientBuilder {
+    pub fn with_config(mut self, config: A2AClientConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn with_authentication(mut self, authentication: ClientAuthentication) -> Self {
+        self.config = self.config.with_authentication(authentication);
+        self
+    }
+
+    pub fn with_tls(mut self, tls: ClientTlsConfig) -> Self {
+        self.config = self.config.with_tls(tls);
+        self
+    }
+
+    pub fn with_card_verifier(mut self, card_verifier: CardVerifier) -> Self {
+        self.config = self.config.with_card_verifier(card_verifier);
+        self
+    }
+
+    pub fn with_interceptor(mut self, interceptor: impl ClientInterceptor + 'static) -> Self {
+        self.config = self.config.with_interceptor(interceptor);
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.config = self.config.with_timeout(timeout);
+        self
+    }
+
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.config = self.config.with_retry(retry);
+        self
+    }
+
+    pub fn with_circuit_breaker(mut self, circuit_breaker: CircuitBreaker) -> Self {
+        self.config = self.config.with_circuit_breaker(circuit_breaker);
+        self
+    }
+
+    #[cfg(feature = "grpc")]
+    pub fn with_grpc(mut self, grpc: crate::grpc::GrpcConfig) -> Self {
+        self.config = self.config.with_grpc(grpc);
+        self
+    }
+}
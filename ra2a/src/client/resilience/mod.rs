@@ -0,0 +1,5 @@
+mod model;
+mod service;
+
+pub use model::*;
+pub(crate) use service::{CircuitBreakerInterceptor, RetryInterceptor, TimeoutInterceptor};
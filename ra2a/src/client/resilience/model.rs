@@ -0,0 +1,51 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Retries idempotent calls that failed because the remote agent was unreachable,
+/// overloaded or too slow, sleeping a random delay of up to `base_delay * 2^attempt`,
+/// capped at `max_delay`, between attempts.
+///
+/// Task lookups and extended card fetches are always idempotent, messages only when the
+/// client sets their `message_id`, which the agent de-duplicates them on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Attempts made in total, the first one included.
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+/// Fails calls to a remote agent fast while it is down.
+///
+/// The circuit of an endpoint opens after `failure_threshold` consecutive failed calls,
+/// rejecting calls for `open_for`. A single trial call is then let through, closing the
+/// circuit when it succeeds and opening it again when it fails. Clones share their state,
+/// so every client of an endpoint built with the same breaker sees the same circuit.
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker {
+    pub(crate) failure_threshold: u32,
+    pub(crate) open_for: Duration,
+    // endpoint -> circuit
+    pub(crate) circuits: Arc<Mutex<HashMap<String, Circuit>>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Circuit {
+    Closed {
+        failures: u32,
+    },
+    Open {
+        until: Instant,
+    },
+    /// The trial call is in flight.
+    HalfOpen,
+}
+
+/// State of the circuit of an endpoint, see [`CircuitBreaker::state`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    Closed,
+    Open,
+    HalfOpen,
+}
@@ -0,0 +1,264 @@
+use crate::client::resilience::{Circuit, CircuitBreaker, CircuitState, RetryPolicy};
+use crate::client::{ClientCall, ClientInterceptor, ClientNext};
+use crate::core::{A2AError, A2AProtocolError, A2ARequest, A2AResponse, A2ATransportError};
+use async_trait::async_trait;
+use jsonrpsee::core::ClientError;
+use ring::rand::{SecureRandom, SystemRandom};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Random delay before retry `attempt`, counted from 1.
+    fn backoff(&self, attempt: u32) -> Duration {
+        let cap = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt - 1))
+            .min(self.max_delay);
+        let mut bytes = [0; 8];
+        let fraction = match SystemRandom::new().fill(&mut bytes) {
+            Ok(()) => u64::from_le_bytes(bytes) as f64 / u64::MAX as f64,
+            Err(_) => 1.0,
+        };
+        cap.mul_f64(fraction)
+    }
+}
+
+impl CircuitBreaker {
+    pub fn new(failure_threshold: u32, open_for: Duration) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            open_for,
+            circuits: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn state(&self, endpoint: &str) -> CircuitState {
+        let circuits = self.circuits.lock().unwrap_or_else(|e| e.into_inner());
+        match circuits.get(endpoint) {
+            None | Some(Circuit::Closed { .. }) => CircuitState::Closed,
+            Some(Circuit::Open { until }) if *until > Instant::now() => CircuitState::Open,
+            Some(Circuit::Open { .. }) | Some(Circuit::HalfOpen) => CircuitState::HalfOpen,
+        }
+    }
+
+    /// A permit for a call to `endpoint`, if it may go through, turning an expired open
+    /// circuit half open for the trial call.
+    fn acquire<'a>(&'a self, endpoint: &'a str) -> Option<CircuitPermit<'a>> {
+        let mut circuits = self.circuits.lock().unwrap_or_else(|e| e.into_inner());
+        let circuit = circuits
+            .entry(endpoint.to_string())
+            .or_insert(Circuit::Closed { failures: 0 });
+        let trial = match *circuit {
+            Circuit::Closed { .. } => false,
+            Circuit::Open { until } if until <= Instant::now() => {
+                *circuit = Circuit::HalfOpen;
+                true
+            }
+            Circuit::Open { .. } | Circuit::HalfOpen => return None,
+        };
+        Some(CircuitPermit {
+            breaker: self,
+            endpoint,
+            trial,
+        })
+    }
+
+    fn record(&self, endpoint: &str, failed: bool) {
+        let mut circuits = self.circuits.lock().unwrap_or_else(|e| e.into_inner());
+        let circuit = circuits
+            .entry(endpoint.to_string())
+            .or_insert(Circuit::Closed { failures: 0 });
+        let failures = match (*circuit, failed) {
+            (_, false) => 0,
+            (Circuit::Closed { failures }, true) => failures + 1,
+            (Circuit::Open { .. } | Circuit::HalfOpen, true) => self.failure_threshold,
+        };
+        *circuit = match failures >= self.failure_threshold {
+            true => {
+                tracing::warn!(endpoint, failures, "opening circuit");
+                Circuit::Open {
+                    until: Instant::now() + self.open_for,
+                }
+            }
+            false => Circuit::Closed { failures },
+        };
+    }
+}
+
+/// Lets a call through the circuit of an endpoint. A trial call that is dropped before
+/// it completes counts as failed, so that the circuit does not stay half open.
+struct CircuitPermit<'a> {
+    breaker: &'a CircuitBreaker,
+    endpoint: &'a str,
+    trial: bool,
+}
+
+impl CircuitPermit<'_> {
+    fn record(mut self, failed: bool) {
+        self.trial = false;
+        self.breaker.record(self.endpoint, failed);
+    }
+}
+
+impl Drop for CircuitPermit<'_> {
+    fn drop(&mut self) {
+        if self.trial {
+            tracing::debug!(endpoint = self.endpoint, "trial call cancelled");
+            self.breaker.record(self.endpoint, true);
+        }
+    }
+}
+
+/// Fails attempts that take longer than the timeout with [`A2ATransportError::Timeout`].
+#[derive(Debug)]
+pub(crate) struct TimeoutInterceptor(pub(crate) Duration);
+
+#[async_trait]
+impl ClientInterceptor for TimeoutInterceptor {
+    async fn intercept(
+        &self,
+        call: ClientCall,
+        next: ClientNext<'_>,
+    ) -> Result<A2AResponse, A2AError> {
+        // The http client of JSON-RPC shares the deadline and may report it first.
+        match tokio::time::timeout(self.0, next.run(call)).await {
+            Ok(Err(A2AError::Transport(A2ATransportError::JsonRpc(
+                ClientError::RequestTimeout,
+            ))))
+            | Err(_) => Err(A2ATransportError::Timeout(self.0).into()),
+            Ok(res) => res,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct RetryInterceptor(pub(crate) RetryPolicy);
+
+#[async_trait]
+impl ClientInterceptor for RetryInterceptor {
+    async fn intercept(
+        &self,
+        call: ClientCall,
+        next: ClientNext<'_>,
+    ) -> Result<A2AResponse, A2AError> {
+        if !is_idempotent(&call.request) {
+            return next.run(call).await;
+        }
+        let mut attempt = 1;
+        loop {
+            let e = match next.run(call.clone()).await {
+                Ok(response) => return Ok(response),
+                Err(e) => e,
+            };
+            if attempt >= self.0.max_attempts {
+                return Err(e);
+            }
+            let delay = match (&e, is_endpoint_failure(&e)) {
+                (
+                    A2AError::Protocol(A2AProtocolError::RateLimitExceeded { retry_after, .. }),
+                    _,
+                ) => match Duration::from_secs(*retry_after) {
+                    delay if delay <= self.0.max_delay => delay,
+                    _ => return Err(e),
+                },
+                (_, true) => self.0.backoff(attempt),
+                (_, false) => return Err(e),
+            };
+            tracing::debug!(error = %e, attempt, ?delay, method = call.request.method(), "retrying call");
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+}
+
+/// Runs calls through the circuit of an endpoint.
+#[derive(Debug)]
+pub(crate) struct CircuitBreakerInterceptor {
+    pub(crate) breaker: CircuitBreaker,
+    pub(crate) endpoint: String,
+}
+
+#[async_trait]
+impl ClientInterceptor for CircuitBreakerInterceptor {
+    async fn intercept(
+        &self,
+        call: ClientCall,
+        next: ClientNext<'_>,
+    ) -> Result<A2AResponse, A2AError> {
+        let Some(permit) = self.breaker.acquire(&self.endpoint) else {
+            return Err(A2ATransportError::CircuitOpen(self.endpoint.clone()).into());
+        };
+        let res = next.run(call).await;
+        permit.record(res.as_ref().is_err_and(is_endpoint_failure));
+        res
+    }
+}
+
+/// Messages are idempotent when the client names them: agents answer a message sent again
+/// with the same id with the response to the first one. Agents assign ids to unnamed
+/// messages, which would be handled again.
+fn is_idempotent(request: &A2ARequest) -> bool {
+    match request {
+        A2ARequest::GetTask(_) | A2ARequest::GetAuthenticatedExtendedCard => true,
+        A2ARequest::SendMessage(request) => request
+            .message
+            .as_ref()
+            .is_some_and(|message| !message.message_id.is_empty()),
+    }
+}
+
+/// Whether the call failed because the endpoint was unreachable, overloaded or too slow,
+/// rather than answering.
+fn is_endpoint_failure(e: &A2AError) -> bool {
+    let A2AError::Transport(e) = e else {
+        return false;
+    };
+    match e {
+        A2ATransportError::Timeout(_) => true,
+        A2ATransportError::JsonRpc(ClientError::Transport(e)) => {
+            use jsonrpsee::http_client::transport::Error;
+            match e.downcast_ref::<Error>() {
+                Some(Error::Rejected { status_code }) => *status_code >= 500,
+                Some(Error::Http(_)) | None => true,
+                Some(_) => false,
+            }
+        }
+        A2ATransportError::JsonRpc(ClientError::RequestTimeout | ClientError::RestartNeeded(_)) => {
+            true
+        }
+        #[cfg(feature = "grpc")]
+        A2ATransportError::Grcp(status) => matches!(
+            status.code(),
+            tonic::Code::Unavailable | tonic::Code::DeadlineExceeded
+        ),
+        _ => false,
+    }
+}
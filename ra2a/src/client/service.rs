@@ -1,28 +1,264 @@
-use crate::client::A2AClientError;
+use crate::client::auth::ClientAuthentication;
 use crate::client::jsonrpc::A2AJsonRpcClient;
+use crate::client::resilience::{CircuitBreakerInterceptor, RetryInterceptor, TimeoutInterceptor};
+use crate::client::watch::{TaskWatcher, WaitError};
+use crate::client::{A2AClientBuilder, A2AClientConfig, A2AClientError, ClientCall, ClientNext};
+use crate::core::agent::AgentCard;
+use crate::core::delegation::DelegationChain;
 use crate::core::message::{SendMessageRequest, SendMessageResponse};
 use crate::core::task::{GetTaskRequest, Task};
-use crate::core::{A2A, A2AError, Transport};
+use crate::core::{A2A, A2AError, A2ARequest, A2AResponse, Transport};
 use async_trait::async_trait;
+use http::HeaderMap;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/// Wraps the calls of a client, the same way for every transport, e.g. to add headers,
+/// activate extensions, log or change outgoing messages.
+#[async_trait]
+pub trait ClientInterceptor: Debug + Send + Sync {
+    /// Handles `call`, usually by passing it on with [`ClientNext::run`]. Returning
+    /// without calling `next` answers the call without sending it.
+    async fn intercept(
+        &self,
+        call: ClientCall,
+        next: ClientNext<'_>,
+    ) -> Result<A2AResponse, A2AError>;
+}
+
+/// Sends the calls that made it through the interceptors of a transport client.
+#[async_trait]
+pub(crate) trait ClientTransport: Send + Sync {
+    fn transport(&self) -> Transport;
+
+    fn interceptors(&self) -> &[Arc<dyn ClientInterceptor>];
+
+    async fn send(&self, request: A2ARequest, headers: HeaderMap) -> Result<A2AResponse, A2AError>;
+
+    /// Runs `request` through the interceptors, then sends it.
+    async fn call(&self, request: A2ARequest) -> Result<A2AResponse, A2AError>
+    where
+        Self: Sized,
+    {
+        let call = ClientCall {
+            transport: self.transport(),
+            request,
+            headers: HeaderMap::new(),
+        };
+        ClientNext {
+            transport: self,
+            interceptors: self.interceptors(),
+        }
+        .run(call)
+        .await
+    }
+}
+
+impl ClientNext<'_> {
+    /// Passes the call on to the rest of the chain.
+    pub async fn run(self, call: ClientCall) -> Result<A2AResponse, A2AError> {
+        match self.interceptors.split_first() {
+            Some((interceptor, interceptors)) => {
+                let next = ClientNext {
+                    transport: self.transport,
+                    interceptors,
+                };
+                interceptor.intercept(call, next).await
+            }
+            None => self.transport.send(call.request, call.headers).await,
+        }
+    }
+}
+
+impl Debug for ClientNext<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClientNext")
+            .field("transport", &self.transport.transport())
+            .field("interceptors", &self.interceptors.len())
+            .finish()
+    }
+}
 
 #[derive(Debug, Clone)]
 pub enum A2AClient {
     JsonRpc(A2AJsonRpcClient),
     #[cfg(feature = "grpc")]
     Grpc(crate::client::grpc::A2AGrpcClient),
+    #[cfg(feature = "agent")]
+    InProcess(crate::client::inprocess::A2AInProcessClient),
+}
+
+impl A2AClientConfig {
+    /// The interceptors of a client of `endpoint`: retries outermost, so that every attempt
+    /// goes through the circuit breaker and gets its own deadline, then the ones of the user.
+    pub(crate) fn call_interceptors(&self, endpoint: &str) -> Arc<[Arc<dyn ClientInterceptor>]> {
+        let mut interceptors: Vec<Arc<dyn ClientInterceptor>> = Vec::new();
+        if let Some(retry) = &self.retry {
+            interceptors.push(Arc::new(RetryInterceptor(retry.clone())));
+        }
+        if let Some(breaker) = &self.circuit_breaker {
+            interceptors.push(Arc::new(CircuitBreakerInterceptor {
+                breaker: breaker.clone(),
+                endpoint: endpoint.to_string(),
+            }));
+        }
+        if let Some(timeout) = self.timeout {
+            interceptors.push(Arc::new(TimeoutInterceptor(timeout)));
+        }
+        interceptors.extend(self.interceptors.iter().cloned());
+        interceptors.into()
+    }
+}
+
+impl A2AClientBuilder {
+    pub async fn build(self) -> Result<A2AClient, A2AClientError> {
+        A2AClient::new_with_config(self.transport, self.url, self.config).await
+    }
 }
 
 impl A2AClient {
+    pub fn builder(transport: Transport, url: impl Into<String>) -> A2AClientBuilder {
+        A2AClientBuilder {
+            transport,
+            url: url.into(),
+            config: A2AClientConfig::default(),
+        }
+    }
+
     pub async fn new(transport: Transport, url: impl AsRef<str>) -> Result<Self, A2AClientError> {
+        Self::new_with_config(transport, url, A2AClientConfig::default()).await
+    }
+
+    /// Creates a client that attaches the credentials required by the remote agent card
+    /// to every request, see [`ClientAuthentication`].
+    pub async fn new_with_authentication(
+        transport: Transport,
+        url: impl AsRef<str>,
+        authentication: Option<ClientAuthentication>,
+    ) -> Result<Self, A2AClientError> {
+        let config = A2AClientConfig {
+            authentication,
+            ..Default::default()
+        };
+        Self::new_with_config(transport, url, config).await
+    }
+
+    pub async fn new_with_config(
+        transport: Transport,
+        url: impl AsRef<str>,
+        config: A2AClientConfig,
+    ) -> Result<Self, A2AClientError> {
+        let url = url.as_ref().to_string();
         let client = match transport {
             #[cfg(feature = "grpc")]
             Transport::Grpc => {
-                Self::Grpc(crate::client::grpc::A2AGrpcClient::new(url.as_ref().to_string()).await?)
+                Self::Grpc(crate::client::grpc::A2AGrpcClient::new_with_config(url, config).await?)
             }
-            Transport::JsonRpc => Self::JsonRpc(A2AJsonRpcClient::new(url.as_ref().to_string())?),
+            Transport::JsonRpc => Self::JsonRpc(A2AJsonRpcClient::new_with_config(url, config)?),
         };
         Ok(client)
     }
+
+    /// Creates a client of an agent listening on the Unix domain socket at `path`, e.g. a
+    /// sidecar.
+    #[cfg(unix)]
+    pub async fn new_unix(
+        transport: Transport,
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<Self, A2AClientError> {
+        Self::new_unix_with_config(transport, path, A2AClientConfig::default()).await
+    }
+
+    #[cfg(unix)]
+    pub async fn new_unix_with_config(
+        transport: Transport,
+        path: impl AsRef<std::path::Path>,
+        config: A2AClientConfig,
+    ) -> Result<Self, A2AClientError> {
+        let client = match transport {
+            #[cfg(feature = "grpc")]
+            Transport::Grpc => {
+                Self::Grpc(crate::client::grpc::A2AGrpcClient::new_unix(path, config).await?)
+            }
+            Transport::JsonRpc => Self::JsonRpc(A2AJsonRpcClient::new_unix(path, config)?),
+        };
+        Ok(client)
+    }
+
+    /// Spawns `command` and talks JSON-RPC to the agent it runs over its stdin and stdout,
+    /// see [`A2AJsonRpcClient::spawn`].
+    pub fn spawn(command: tokio::process::Command) -> Result<Self, A2AClientError> {
+        Self::spawn_with_config(command, A2AClientConfig::default())
+    }
+
+    pub fn spawn_with_config(
+        command: tokio::process::Command,
+        config: A2AClientConfig,
+    ) -> Result<Self, A2AClientError> {
+        Ok(Self::JsonRpc(A2AJsonRpcClient::spawn(command, config)?))
+    }
+
+    /// Calls `delegate` directly, in this process, see
+    /// [`A2AInProcessClient`](crate::client::inprocess::A2AInProcessClient).
+    #[cfg(feature = "agent")]
+    pub fn in_process(delegate: crate::agent::A2ADelegate) -> Self {
+        Self::in_process_with_config(delegate, A2AClientConfig::default())
+    }
+
+    #[cfg(feature = "agent")]
+    pub fn in_process_with_config(
+        delegate: crate::agent::A2ADelegate,
+        config: A2AClientConfig,
+    ) -> Self {
+        Self::InProcess(
+            crate::client::inprocess::A2AInProcessClient::new_with_config(delegate, config),
+        )
+    }
+
+    /// Waits for task `id` to reach a terminal or interrupted state, see [`TaskWatcher`].
+    pub async fn wait_for_task(
+        &self,
+        id: impl Into<String>,
+        watcher: &TaskWatcher,
+    ) -> Result<Task, WaitError> {
+        watcher.wait(self, id).await
+    }
+
+    /// Fetches the agent card at `url` over the TLS settings of `config`, verifying the
+    /// signatures of the received JSON when `config` has a
+    /// [`CardVerifier`](crate::jws::CardVerifier).
+    pub async fn fetch_agent_card(
+        url: impl AsRef<str>,
+        config: &A2AClientConfig,
+    ) -> Result<AgentCard, A2AClientError> {
+        let url = url.as_ref();
+        let mut builder = reqwest::Client::builder();
+        if let Some(tls) = &config.tls {
+            builder = builder.use_preconfigured_tls(tls.rustls_config()?);
+        }
+        let fetch = async {
+            builder
+                .build()?
+                .get(url)
+                .send()
+                .await?
+                .error_for_status()?
+                .json::<serde_json::Value>()
+                .await
+        };
+        let card = fetch.await.map_err(|source| A2AClientError::CardFetch {
+            url: url.to_string(),
+            source,
+        })?;
+        // signatures cover the card as published, not as modeled
+        if let Some(verifier) = &config.card_verifier {
+            verifier.verify_json(&card)?;
+        }
+        serde_json::from_value(card).map_err(|source| A2AClientError::CardDecode {
+            url: url.to_string(),
+            source,
+        })
+    }
 }
 
 #[async_trait]
@@ -31,10 +267,13 @@ impl A2A for A2AClient {
         &self,
         request: SendMessageRequest,
     ) -> Result<SendMessageResponse, A2AError> {
+        let request = DelegationChain::propagate(request);
         match self {
             A2AClient::JsonRpc(c) => c.send_message(request).await,
             #[cfg(feature = "grpc")]
             A2AClient::Grpc(c) => c.send_message(request).await,
+            #[cfg(feature = "agent")]
+            A2AClient::InProcess(c) => c.send_message(request).await,
         }
     }
 
@@ -43,6 +282,18 @@ impl A2A for A2AClient {
             A2AClient::JsonRpc(c) => c.get_task(request).await,
             #[cfg(feature = "grpc")]
             A2AClient::Grpc(c) => c.get_task(request).await,
+            #[cfg(feature = "agent")]
+            A2AClient::InProcess(c) => c.get_task(request).await,
+        }
+    }
+
+    async fn get_authenticated_extended_card(&self) -> Result<AgentCard, A2AError> {
+        match self {
+            A2AClient::JsonRpc(c) => c.get_authenticated_extended_card().await,
+            #[cfg(feature = "grpc")]
+            A2AClient::Grpc(c) => c.get_authenticated_extended_card().await,
+            #[cfg(feature = "agent")]
+            A2AClient::InProcess(c) => c.get_authenticated_extended_card().await,
         }
     }
 }
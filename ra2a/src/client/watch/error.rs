@@ -0,0 +1,14 @@
+use crate::core::A2AError;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum WaitError {
+    #[error("Task {id} did not settle before the deadline")]
+    Deadline { id: String },
+
+    #[error("Waiting for task {id} was cancelled")]
+    Cancelled { id: String },
+
+    #[error("a2a")]
+    A2A(#[from] A2AError),
+}
@@ -0,0 +1,21 @@
+use crate::core::task::Task;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+/// Called with a task whose status changed, see [`TaskWatcher::on_status`].
+pub type StatusCallback = Arc<dyn Fn(&Task) + Send + Sync>;
+
+/// Waits for tasks to reach a terminal or interrupted state, see [`TaskWatcher::wait`].
+///
+/// The clients have no streaming transport, so tasks are polled: every `min_interval`
+/// while their status changes, backing off to `max_interval` while it does not.
+#[derive(Clone)]
+pub struct TaskWatcher {
+    pub(crate) deadline: Option<Duration>,
+    pub(crate) cancellation: Option<CancellationToken>,
+    pub(crate) on_status: Option<StatusCallback>,
+    pub(crate) min_interval: Duration,
+    pub(crate) max_interval: Duration,
+    pub(crate) history_length: Option<i32>,
+}
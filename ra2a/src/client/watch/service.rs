@@ -0,0 +1,136 @@
+use crate::client::watch::{TaskWatcher, WaitError};
+use crate::core::A2A;
+use crate::core::task::{GetTaskRequest, Task, TaskState};
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+
+impl Default for TaskWatcher {
+    fn default() -> Self {
+        Self {
+            deadline: None,
+            cancellation: None,
+            on_status: None,
+            min_interval: Duration::from_millis(250),
+            max_interval: Duration::from_secs(5),
+            history_length: None,
+        }
+    }
+}
+
+impl Debug for TaskWatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TaskWatcher")
+            .field("deadline", &self.deadline)
+            .field("cancellation", &self.cancellation)
+            .field("on_status", &self.on_status.is_some())
+            .field("min_interval", &self.min_interval)
+            .field("max_interval", &self.max_interval)
+            .field("history_length", &self.history_length)
+            .finish()
+    }
+}
+
+impl TaskWatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gives up on tasks that have not settled `deadline` after the wait started.
+    pub fn with_deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Stops waiting as soon as `cancellation` is cancelled.
+    pub fn with_cancellation(mut self, cancellation: CancellationToken) -> Self {
+        self.cancellation = Some(cancellation);
+        self
+    }
+
+    /// Called with the task whenever its status changes before it settles.
+    pub fn on_status(mut self, on_status: impl Fn(&Task) + Send + Sync + 'static) -> Self {
+        self.on_status = Some(Arc::new(on_status));
+        self
+    }
+
+    pub fn with_poll_interval(mut self, min_interval: Duration, max_interval: Duration) -> Self {
+        self.min_interval = min_interval;
+        self.max_interval = max_interval.max(min_interval);
+        self
+    }
+
+    /// History length requested along with the task.
+    pub fn with_history_length(mut self, history_length: i32) -> Self {
+        self.history_length = Some(history_length);
+        self
+    }
+
+    /// Polls task `id` until it is in a terminal or interrupted state, and returns it.
+    pub async fn wait<C>(&self, client: &C, id: impl Into<String>) -> Result<Task, WaitError>
+    where
+        C: A2A + ?Sized,
+    {
+        let id = id.into();
+        let deadline = self.deadline.map(|deadline| Instant::now() + deadline);
+        let mut interval = self.min_interval;
+        let mut last = None;
+        loop {
+            let request = GetTaskRequest {
+                id: id.clone(),
+                history_length: self.history_length,
+                metadata: None,
+            };
+            let task = self
+                .guard(&id, deadline, client.get_task(request))
+                .await??;
+            let state = task
+                .status
+                .as_ref()
+                .and_then(|status| TaskState::try_from(status.state).ok());
+            if state.is_some_and(|state| state.is_terminal() || state.is_interrupted()) {
+                return Ok(task);
+            }
+            match state == last {
+                true => interval = interval.mul_f64(1.5).min(self.max_interval),
+                false => {
+                    if let Some(on_status) = &self.on_status {
+                        on_status(&task);
+                    }
+                    interval = self.min_interval;
+                    last = state;
+                }
+            }
+            self.guard(&id, deadline, tokio::time::sleep(interval))
+                .await?;
+        }
+    }
+
+    /// Runs `future` unless the deadline passes or the wait is cancelled first.
+    async fn guard<F: Future>(
+        &self,
+        id: &str,
+        deadline: Option<Instant>,
+        future: F,
+    ) -> Result<F::Output, WaitError> {
+        let deadline = async {
+            match deadline {
+                Some(deadline) => tokio::time::sleep_until(deadline).await,
+                None => std::future::pending().await,
+            }
+        };
+        let cancelled = async {
+            match &self.cancellation {
+                Some(cancellation) => cancellation.cancelled().await,
+                None => std::future::pending().await,
+            }
+        };
+        tokio::select! {
+            output = future => Ok(output),
+            _ = deadline => Err(WaitError::Deadline { id: id.to_string() }),
+            _ = cancelled => Err(WaitError::Cancelled { id: id.to_string() }),
+        }
+    }
+}
@@ -288,6 +288,13 @@ pub struct AgentCardSignature {
     pub header: Option<Object>,
 }
 
+/// Request of the gRPC `GetAgentCard` method, which answers with the authenticated
+/// extended card.
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "grpc", derive(prost::Message))]
+#[cfg_attr(not(feature = "grpc"), derive(Debug, Default))]
+pub struct GetAgentCardRequest {}
+
 impl Security {
     pub fn empty() -> Self {
         Self {
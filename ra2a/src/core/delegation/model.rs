@@ -0,0 +1,80 @@
+use crate::core::message::SendMessageRequest;
+use crate::core::util::Object;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+
+/// Request metadata key holding the [`DelegationChain`].
+pub const DELEGATION_METADATA_KEY: &str = "delegation";
+
+/// The agents a request has passed through, in order, as it is delegated from agent to agent.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DelegationChain {
+    pub agents: Vec<String>,
+    pub depth: usize,
+}
+
+tokio::task_local! {
+    static CURRENT: DelegationChain;
+}
+
+impl DelegationChain {
+    /// Reads the chain from the metadata of a send message request.
+    pub fn from_metadata(metadata: Option<&Object>) -> Option<Self> {
+        let value = serde_json::to_value(metadata?).ok()?;
+        let chain = value.get(DELEGATION_METADATA_KEY)?;
+        let agents: Vec<String> = serde_json::from_value(chain.get("agents")?.clone()).ok()?;
+        // metadata numbers are stored as floats
+        let depth = chain
+            .get("depth")
+            .and_then(|d| d.as_f64())
+            .map_or(agents.len(), |d| d as usize);
+        Some(Self { agents, depth })
+    }
+
+    pub fn contains(&self, agent: &str) -> bool {
+        self.agents.iter().any(|a| a == agent)
+    }
+
+    /// Returns a new chain with `agent` appended.
+    pub fn with_agent(&self, agent: impl Into<String>) -> Self {
+        let mut chain = self.with_hop();
+        chain.agents.push(agent.into());
+        chain
+    }
+
+    /// Returns a new chain one hop deeper, for a hop through an agent without a name.
+    pub fn with_hop(&self) -> Self {
+        Self {
+            agents: self.agents.clone(),
+            depth: self.depth.max(self.agents.len()) + 1,
+        }
+    }
+
+    /// The chain of the request currently being handled by this task, if any.
+    pub fn current() -> Option<Self> {
+        CURRENT.try_with(Clone::clone).ok()
+    }
+
+    /// Runs `fut` with this chain as the current chain, so that requests sent by
+    /// [`crate::client::A2AClient`] from within `fut` carry it along.
+    pub async fn scope<F: Future>(self, fut: F) -> F::Output {
+        CURRENT.scope(self, fut).await
+    }
+
+    /// Writes the current chain into the request metadata, unless the request already has
+    /// one at least as deep, e.g. a request forwarded as received.
+    pub fn propagate(mut request: SendMessageRequest) -> SendMessageRequest {
+        let Some(chain) = Self::current() else {
+            return request;
+        };
+        let metadata = request.metadata.get_or_insert_with(Object::empty);
+        if Self::from_metadata(Some(metadata)).is_none_or(|own| own.depth < chain.depth) {
+            metadata.insert(
+                DELEGATION_METADATA_KEY,
+                serde_json::to_value(chain).unwrap_or_default(),
+            );
+        }
+        request
+    }
+}
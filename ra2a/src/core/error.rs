@@ -1,5 +1,6 @@
 use jsonrpsee::core::ClientError;
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use std::fmt::{Display, Formatter};
 use thiserror::Error;
 use tonic::Code;
@@ -14,6 +15,11 @@ pub enum A2AErrorCode {
     ContentTypeNotSupported = -32005,
     InvalidAgentResponse = -32006,
     AuthenticatedExtendedCardNotConfigured = -32007,
+    DelegationLoop = -32050,
+    DelegationDepthExceeded = -32051,
+    RateLimitExceeded = -32052,
+    QuotaExceeded = -32053,
+    InvalidSkill = -32054,
 }
 
 #[derive(Debug, Error)]
@@ -22,9 +28,23 @@ pub enum A2AError {
     #[error(transparent)]
     Agent(#[from] crate::agent::A2AAgentError),
 
+    #[cfg(feature = "agent")]
+    #[error(transparent)]
+    Auth(#[from] crate::auth::AuthError),
+
+    #[error(transparent)]
+    Broker(#[from] crate::broker::AgentBrokerError),
+
     #[error(transparent)]
     Protocol(#[from] A2AProtocolError),
 
+    #[error(transparent)]
+    Push(#[from] crate::push::PushError),
+
+    #[cfg(feature = "agent")]
+    #[error(transparent)]
+    QuotaStore(#[from] crate::limit::QuotaStoreError),
+
     #[error(transparent)]
     Transport(#[from] A2ATransportError),
 
@@ -78,6 +98,43 @@ pub enum A2AProtocolError {
     /// The agent does not have an Authenticated Extended Card configured.
     #[error("Authenticated Extended Card not configured")]
     AuthenticatedExtendedCardNotConfigured { code: A2AErrorCode },
+
+    /// The request was already handled by this agent further up the delegation chain,
+    /// so handling it again would loop.
+    #[error("Delegation loop detected, {agent} is already in the delegation chain")]
+    DelegationLoop {
+        agent: String,
+        chain: Vec<String>,
+        code: A2AErrorCode,
+    },
+
+    /// The request has been delegated between agents more times than this agent allows.
+    #[error("Delegation depth {depth} exceeds the maximum of {max_depth}")]
+    DelegationDepthExceeded {
+        depth: usize,
+        max_depth: usize,
+        code: A2AErrorCode,
+    },
+
+    /// The caller sent requests faster than a rate limit of this agent allows.
+    #[error("Rate limit exceeded, retry after {retry_after} seconds")]
+    RateLimitExceeded {
+        retry_after: u64,
+        code: A2AErrorCode,
+    },
+
+    /// The caller used up a quota of this agent for the current window.
+    #[error("Quota of {limit} exceeded, retry after {retry_after} seconds")]
+    QuotaExceeded {
+        limit: u64,
+        retry_after: u64,
+        code: A2AErrorCode,
+    },
+
+    /// The message names a skill the agent card does not declare, or another skill than
+    /// the one the agent routes it to.
+    #[error("Message cannot target skill {skill}")]
+    InvalidSkill { skill: String, code: A2AErrorCode },
 }
 
 #[derive(Debug, Error)]
@@ -91,6 +148,15 @@ pub enum A2ATransportError {
 
     #[error("Json RPC")]
     JsonRpc(#[from] ClientError),
+
+    #[error("Credentials")]
+    Credentials(#[from] crate::client::auth::CredentialError),
+
+    #[error("Call timed out after {0:?}")]
+    Timeout(std::time::Duration),
+
+    #[error("Circuit of {0} is open")]
+    CircuitOpen(String),
 }
 
 impl A2AProtocolError {
@@ -137,6 +203,158 @@ impl A2AProtocolError {
             code: A2AErrorCode::AuthenticatedExtendedCardNotConfigured,
         }
     }
+
+    pub fn delegation_loop(agent: String, chain: Vec<String>) -> Self {
+        A2AProtocolError::DelegationLoop {
+            agent,
+            chain,
+            code: A2AErrorCode::DelegationLoop,
+        }
+    }
+
+    pub fn delegation_depth_exceeded(depth: usize, max_depth: usize) -> Self {
+        A2AProtocolError::DelegationDepthExceeded {
+            depth,
+            max_depth,
+            code: A2AErrorCode::DelegationDepthExceeded,
+        }
+    }
+
+    pub fn rate_limit_exceeded(retry_after: u64) -> Self {
+        A2AProtocolError::RateLimitExceeded {
+            retry_after,
+            code: A2AErrorCode::RateLimitExceeded,
+        }
+    }
+
+    pub fn quota_exceeded(limit: u64, retry_after: u64) -> Self {
+        A2AProtocolError::QuotaExceeded {
+            limit,
+            retry_after,
+            code: A2AErrorCode::QuotaExceeded,
+        }
+    }
+
+    pub fn invalid_skill(skill: String) -> Self {
+        A2AProtocolError::InvalidSkill {
+            skill,
+            code: A2AErrorCode::InvalidSkill,
+        }
+    }
+
+    /// Seconds after which a rate limited or over quota caller may try again.
+    pub fn retry_after(&self) -> Option<u64> {
+        match self {
+            A2AProtocolError::RateLimitExceeded { retry_after, .. }
+            | A2AProtocolError::QuotaExceeded { retry_after, .. } => Some(*retry_after),
+            _ => None,
+        }
+    }
+
+    pub fn code(&self) -> A2AErrorCode {
+        match self {
+            A2AProtocolError::TaskNotFound { code, .. }
+            | A2AProtocolError::TaskNotCancelable { code, .. }
+            | A2AProtocolError::PushNotificationNotSupported { code }
+            | A2AProtocolError::UnsupportedOperation { code }
+            | A2AProtocolError::ContentTypeNotSupported { code }
+            | A2AProtocolError::InvalidAgentResponse { code }
+            | A2AProtocolError::AuthenticatedExtendedCardNotConfigured { code }
+            | A2AProtocolError::DelegationLoop { code, .. }
+            | A2AProtocolError::DelegationDepthExceeded { code, .. }
+            | A2AProtocolError::RateLimitExceeded { code, .. }
+            | A2AProtocolError::QuotaExceeded { code, .. }
+            | A2AProtocolError::InvalidSkill { code, .. } => *code,
+        }
+    }
+
+    /// Structured details of the error, sent alongside the code so clients can rebuild it.
+    pub fn data(&self) -> Option<serde_json::Value> {
+        match self {
+            A2AProtocolError::TaskNotFound { id, .. }
+            | A2AProtocolError::TaskNotCancelable { id, .. } => Some(json!({ "id": id })),
+            A2AProtocolError::DelegationLoop { agent, chain, .. } => {
+                Some(json!({ "agent": agent, "chain": chain }))
+            }
+            A2AProtocolError::DelegationDepthExceeded {
+                depth, max_depth, ..
+            } => Some(json!({ "depth": depth, "maxDepth": max_depth })),
+            A2AProtocolError::RateLimitExceeded { retry_after, .. } => {
+                Some(json!({ "retryAfter": retry_after }))
+            }
+            A2AProtocolError::QuotaExceeded {
+                limit, retry_after, ..
+            } => Some(json!({ "limit": limit, "retryAfter": retry_after })),
+            A2AProtocolError::InvalidSkill { skill, .. } => Some(json!({ "skill": skill })),
+            _ => None,
+        }
+    }
+
+    /// Rebuilds a protocol error from its wire code and data, see [`A2AProtocolError::data`].
+    pub fn from_code(code: i32, data: Option<&serde_json::Value>) -> Option<Self> {
+        let str_field = |key: &str| {
+            data.and_then(|d| d.get(key))
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string()
+        };
+        let u64_field = |key: &str| {
+            data.and_then(|d| d.get(key))
+                .and_then(|v| v.as_u64())
+                .unwrap_or_default()
+        };
+        let usize_field = |key: &str| u64_field(key) as usize;
+        let err = match A2AErrorCode::try_from(code).ok()? {
+            A2AErrorCode::TaskNotFound => Self::task_not_found(str_field("id")),
+            A2AErrorCode::TaskNotCancelable => Self::task_not_cancelable(str_field("id")),
+            A2AErrorCode::PushNotificationNotSupported => Self::push_notification_not_supported(),
+            A2AErrorCode::UnsupportedOperation => Self::unsupported_operation(),
+            A2AErrorCode::ContentTypeNotSupported => Self::content_type_not_supported(),
+            A2AErrorCode::InvalidAgentResponse => Self::invalid_agent_response(),
+            A2AErrorCode::AuthenticatedExtendedCardNotConfigured => {
+                Self::authenticated_extended_card_not_configured()
+            }
+            A2AErrorCode::DelegationLoop => {
+                let chain = data
+                    .and_then(|d| d.get("chain"))
+                    .and_then(|c| serde_json::from_value(c.clone()).ok())
+                    .unwrap_or_default();
+                Self::delegation_loop(str_field("agent"), chain)
+            }
+            A2AErrorCode::DelegationDepthExceeded => {
+                Self::delegation_depth_exceeded(usize_field("depth"), usize_field("maxDepth"))
+            }
+            A2AErrorCode::RateLimitExceeded => Self::rate_limit_exceeded(u64_field("retryAfter")),
+            A2AErrorCode::QuotaExceeded => {
+                Self::quota_exceeded(u64_field("limit"), u64_field("retryAfter"))
+            }
+            A2AErrorCode::InvalidSkill => Self::invalid_skill(str_field("skill")),
+        };
+        Some(err)
+    }
+}
+
+impl TryFrom<i32> for A2AErrorCode {
+    type Error = i32;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        let code = match value {
+            -32001 => A2AErrorCode::TaskNotFound,
+            -32002 => A2AErrorCode::TaskNotCancelable,
+            -32003 => A2AErrorCode::PushNotificationNotSupported,
+            -32004 => A2AErrorCode::UnsupportedOperation,
+            -32005 => A2AErrorCode::ContentTypeNotSupported,
+            -32006 => A2AErrorCode::InvalidAgentResponse,
+            -32007 => A2AErrorCode::AuthenticatedExtendedCardNotConfigured,
+            -32050 => A2AErrorCode::DelegationLoop,
+            -32051 => A2AErrorCode::DelegationDepthExceeded,
+            -32052 => A2AErrorCode::RateLimitExceeded,
+            -32053 => A2AErrorCode::QuotaExceeded,
+            -32054 => A2AErrorCode::InvalidSkill,
+            _ => return Err(value),
+        };
+        Ok(code)
+    }
 }
 
 #[cfg(feature = "grpc")]
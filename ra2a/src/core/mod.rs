@@ -1,5 +1,6 @@
 pub mod agent;
 pub mod artifact;
+pub mod delegation;
 pub mod message;
 pub mod part;
 pub mod push_notification;
@@ -1,14 +1,29 @@
-use crate::core::A2AError;
+use crate::core::agent::AgentCard;
 use crate::core::message::{SendMessageRequest, SendMessageResponse};
 use crate::core::task::{GetTaskRequest, Task};
+use crate::core::{A2AError, A2AProtocolError};
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 
 pub const GRPC_SERVICE_NAME: &str = "a2a.v1.A2AService";
 pub const GRPC_SEND_MESSAGE_PATH: &str = "/a2a.v1.A2AService/SendMessage";
 pub const GRPC_GET_TASK_PATH: &str = "/a2a.v1.A2AService/GetTask";
+pub const GRPC_GET_AGENT_CARD_PATH: &str = "/a2a.v1.A2AService/GetAgentCard";
 pub const JSONRPC_SEND_MESSAGE_METHOD: &str = "message/send";
 pub const JSONRPC_GET_TASK_METHOD: &str = "tasks/get";
+pub const JSONRPC_GET_AUTHENTICATED_EXTENDED_CARD_METHOD: &str =
+    "agent/getAuthenticatedExtendedCard";
+/// Http route serving the authenticated extended card next to the json-rpc endpoint.
+pub const REST_GET_AUTHENTICATED_EXTENDED_CARD_PATH: &str = "/v1/card";
+/// Well-known http route serving the public card of an agent.
+pub const WELL_KNOWN_AGENT_CARD_PATH: &str = "/.well-known/agent-card.json";
+/// Path prefix under which a server hosting several agents serves each of them, followed
+/// by the name of the agent.
+pub const AGENT_PATH_PREFIX: &str = "/agents/";
+/// Header, or gRPC metadata, naming the agent a request is for on a server hosting several.
+pub const AGENT_ROUTING_HEADER: &str = "x-a2a-agent";
+/// Metadata key (on the request or the message) naming the card skill a message targets.
+pub const SKILL_METADATA_KEY: &str = "skillId";
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum Transport {
@@ -24,6 +39,73 @@ pub trait A2A {
     ) -> Result<SendMessageResponse, A2AError>;
 
     async fn get_task(&self, request: GetTaskRequest) -> Result<Task, A2AError>;
+
+    /// Fetches the extended agent card, with the skills and details only authenticated
+    /// callers get to see. Agents without one answer with
+    /// [`A2AProtocolError::AuthenticatedExtendedCardNotConfigured`].
+    async fn get_authenticated_extended_card(&self) -> Result<AgentCard, A2AError> {
+        Err(A2AProtocolError::authenticated_extended_card_not_configured().into())
+    }
+}
+
+/// An [`A2A`] call, as seen by the server middlewares and client interceptors whichever
+/// transport it goes over.
+#[derive(Debug, Clone)]
+pub enum A2ARequest {
+    SendMessage(Box<SendMessageRequest>),
+    GetTask(GetTaskRequest),
+    GetAuthenticatedExtendedCard,
+}
+
+/// Result of an [`A2ARequest`], of the variant matching the request.
+#[derive(Debug, Clone)]
+pub enum A2AResponse {
+    SendMessage(SendMessageResponse),
+    GetTask(Task),
+    GetAuthenticatedExtendedCard(AgentCard),
+}
+
+impl A2ARequest {
+    /// Name of the call, the json-rpc method it maps to on every transport.
+    pub fn method(&self) -> &'static str {
+        match self {
+            A2ARequest::SendMessage(_) => JSONRPC_SEND_MESSAGE_METHOD,
+            A2ARequest::GetTask(_) => JSONRPC_GET_TASK_METHOD,
+            A2ARequest::GetAuthenticatedExtendedCard => {
+                JSONRPC_GET_AUTHENTICATED_EXTENDED_CARD_METHOD
+            }
+        }
+    }
+}
+
+impl A2AResponse {
+    pub fn into_send_message(self) -> Result<SendMessageResponse, A2AError> {
+        match self {
+            A2AResponse::SendMessage(response) => Ok(response),
+            response => Err(mismatched(response)),
+        }
+    }
+
+    pub fn into_task(self) -> Result<Task, A2AError> {
+        match self {
+            A2AResponse::GetTask(task) => Ok(task),
+            response => Err(mismatched(response)),
+        }
+    }
+
+    pub fn into_agent_card(self) -> Result<AgentCard, A2AError> {
+        match self {
+            A2AResponse::GetAuthenticatedExtendedCard(card) => Ok(card),
+            response => Err(mismatched(response)),
+        }
+    }
+}
+
+/// Error for a middleware or interceptor answering a call with the response of another
+/// kind of call.
+fn mismatched(response: A2AResponse) -> A2AError {
+    tracing::warn!(?response, "call answered with a mismatched response");
+    A2AProtocolError::invalid_agent_response().into()
 }
 
 impl Display for Transport {
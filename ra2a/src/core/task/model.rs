@@ -102,7 +102,7 @@ pub struct TaskStatus {
     pub timestamp: Option<Timestamp>,
 }
 
-#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GetTaskRequest {
     pub id: String,
@@ -180,4 +180,18 @@ impl TaskState {
     pub fn into_i32(self) -> i32 {
         self.into()
     }
+
+    /// Whether the task is over and will not change anymore.
+    pub fn is_terminal(self) -> bool {
+        matches!(
+            self,
+            Self::Completed | Self::Failed | Self::Cancelled | Self::Rejected
+        )
+    }
+
+    /// Whether the task waits for the client, for input or authentication, before it can
+    /// go on.
+    pub fn is_interrupted(self) -> bool {
+        matches!(self, Self::InputRequired | Self::AuthRequired)
+    }
 }
@@ -15,6 +15,19 @@ impl Object {
     pub fn is_empty(&self) -> bool {
         self.0.fields.is_empty()
     }
+
+    /// Sets `key` to the given json value, replacing any existing value.
+    pub fn insert(&mut self, key: impl Into<String>, value: serde_json::Value) {
+        self.0.fields.insert(key.into(), json_to_value(value));
+    }
+
+    /// Returns the string stored under `key`, if the field exists and is a string.
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        match self.0.fields.get(key)?.kind.as_ref()? {
+            prost_types::value::Kind::StringValue(s) => Some(s),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Debug for Object {
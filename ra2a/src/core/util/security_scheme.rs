@@ -152,8 +152,9 @@ pub struct OAuth2Flows {
     pub flow: Option<Flow>,
 }
 
+/// Keyed by flow name in json, e.g. `"flows": { "clientCredentials": { ... } }`.
 #[derive(Clone, Serialize, Deserialize, PartialEq)]
-#[serde(tag = "type")]
+#[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "grpc", derive(prost::Oneof))]
 #[cfg_attr(not(feature = "grpc"), derive(Debug))]
 pub enum Flow {
@@ -181,6 +182,7 @@ pub struct ImplicitOAuth2Flow {
     pub authorization_url: String,
     /// The URL to be used for obtaining refresh tokens. This MUST be in the
     /// form of a URL.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     #[cfg_attr(feature = "grpc", prost(string, tag = "2"))]
     pub refresh_url: String,
     /// The available scopes for the OAuth2 security scheme. A map between the
@@ -201,6 +203,7 @@ pub struct PasswordOAuth2Flow {
 
     /// The URL to be used for obtaining refresh tokens. This MUST be in the
     /// form of a URL.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     #[cfg_attr(feature = "grpc", prost(string, tag = "2"))]
     pub refresh_url: String,
 
@@ -221,6 +224,7 @@ pub struct ClientCredentialsOAuth2Flow {
 
     /// The URL to be used for obtaining refresh tokens. This MUST be in the
     /// form of a URL.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     #[cfg_attr(feature = "grpc", prost(string, tag = "2"))]
     pub refresh_url: String,
 
@@ -247,6 +251,7 @@ pub struct AuthorizationCodeOAuth2Flow {
 
     /// The URL to be used for obtaining refresh tokens. This MUST be in the
     /// form of a URL.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     #[cfg_attr(feature = "grpc", prost(string, tag = "3"))]
     pub refresh_url: String,
 
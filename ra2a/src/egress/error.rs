@@ -0,0 +1,43 @@
+use std::net::IpAddr;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum EgressError {
+    #[error("Invalid url {0}")]
+    InvalidUrl(String),
+
+    #[error("Scheme {0} is not allowed")]
+    SchemeNotAllowed(String),
+
+    #[error("Host {0} is not allowed")]
+    HostNotAllowed(String),
+
+    #[error("Address {addr} of host {host} is not allowed")]
+    AddressNotAllowed { host: String, addr: IpAddr },
+
+    #[error("Failed to resolve host {host}")]
+    Resolve {
+        host: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Failed to build http client")]
+    Client(#[source] reqwest::Error),
+
+    #[error("Request to {url} failed")]
+    Request {
+        url: String,
+        #[source]
+        source: reqwest::Error,
+    },
+
+    #[error("Request to {url} failed with status {status}")]
+    Status {
+        url: String,
+        status: reqwest::StatusCode,
+    },
+
+    #[error("Content of {url} exceeds {limit} bytes")]
+    TooLarge { url: String, limit: usize },
+}
@@ -0,0 +1,35 @@
+use std::collections::HashSet;
+use std::net::IpAddr;
+
+/// Which caller supplied urls, such as push notification and file urls, an agent may
+/// reach.
+///
+/// Only `https` urls are allowed by default. Hosts resolving to a private, loopback,
+/// link-local or otherwise non public address are rejected, unless that address is
+/// explicitly allowed. Denied hosts always win over allowed ones, and once a host is
+/// allowed every other host is denied. Host patterns are exact names or `*.suffix`,
+/// matching every subdomain of `suffix`.
+#[derive(Debug, Clone)]
+pub struct UrlPolicy {
+    pub(crate) schemes: HashSet<String>,
+    pub(crate) allowed_hosts: Vec<String>,
+    pub(crate) denied_hosts: Vec<String>,
+    pub(crate) allowed_addrs: HashSet<IpAddr>,
+}
+
+/// Http client for caller supplied urls. The url of every request, every redirect and
+/// every address connected to is checked against a [`UrlPolicy`], so a host cannot be
+/// rebound to a private address between the check and the connection.
+#[derive(Debug, Clone)]
+pub struct EgressClient {
+    pub(crate) policy: UrlPolicy,
+    pub(crate) http: reqwest::Client,
+}
+
+/// Resolves file parts to their content, fetching `FileWithUri` files through an
+/// [`EgressClient`].
+#[derive(Debug, Clone)]
+pub struct FileResolver {
+    pub(crate) client: EgressClient,
+    pub(crate) max_size: usize,
+}
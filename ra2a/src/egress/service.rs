@@ -0,0 +1,295 @@
+use crate::core::part::File;
+use crate::egress::{EgressClient, EgressError, FileResolver, UrlPolicy};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::{Method, RequestBuilder, Url};
+use std::collections::HashSet;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::sync::Arc;
+
+/// Maximum number of redirects an [`EgressClient`] follows.
+const MAX_REDIRECTS: usize = 10;
+
+/// Default maximum size of the files a [`FileResolver`] fetches.
+const DEFAULT_MAX_FILE_SIZE: usize = 16 * 1024 * 1024;
+
+impl Default for UrlPolicy {
+    fn default() -> Self {
+        Self {
+            schemes: HashSet::from(["https".to_string()]),
+            allowed_hosts: vec![],
+            denied_hosts: vec![],
+            allowed_addrs: HashSet::new(),
+        }
+    }
+}
+
+impl UrlPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allows urls with `scheme`, in addition to `https`.
+    pub fn with_scheme(mut self, scheme: impl Into<String>) -> Self {
+        self.schemes.insert(scheme.into().to_ascii_lowercase());
+        self
+    }
+
+    /// Only allows hosts matching one of the allowed patterns.
+    pub fn with_allowed_host(mut self, pattern: impl Into<String>) -> Self {
+        self.allowed_hosts.push(pattern.into().to_ascii_lowercase());
+        self
+    }
+
+    pub fn with_denied_host(mut self, pattern: impl Into<String>) -> Self {
+        self.denied_hosts.push(pattern.into().to_ascii_lowercase());
+        self
+    }
+
+    /// Allows `addr` even when it is not a public address, such as a loopback address in
+    /// tests or an internal service the agent is meant to reach.
+    pub fn with_allowed_addr(mut self, addr: IpAddr) -> Self {
+        self.allowed_addrs.insert(addr);
+        self
+    }
+
+    /// Checks the scheme and host of `url`, and its address when the host is an ip
+    /// address. Host names are checked once resolved, see [`UrlPolicy::check`].
+    pub fn check_url(&self, url: &str) -> Result<Url, EgressError> {
+        let url = Url::parse(url).map_err(|_| EgressError::InvalidUrl(url.to_string()))?;
+        if !self.schemes.contains(url.scheme()) {
+            return Err(EgressError::SchemeNotAllowed(url.scheme().to_string()));
+        }
+        let Some(host) = url.host_str() else {
+            return Err(EgressError::InvalidUrl(url.to_string()));
+        };
+        self.check_host(host)?;
+        if let Some(addr) = ip_host(host) {
+            self.check_addr(host, addr)?;
+        }
+        Ok(url)
+    }
+
+    /// Checks `url` and every address its host resolves to.
+    pub async fn check(&self, url: &str) -> Result<Url, EgressError> {
+        let url = self.check_url(url)?;
+        if let Some(host) = url.host_str().filter(|host| ip_host(host).is_none()) {
+            self.resolve(host).await?;
+        }
+        Ok(url)
+    }
+
+    pub fn check_host(&self, host: &str) -> Result<(), EgressError> {
+        let host = host.trim_end_matches('.').to_ascii_lowercase();
+        let denied = self.denied_hosts.iter().any(|p| host_matches(p, &host));
+        let allowed = self.allowed_hosts.is_empty()
+            || self.allowed_hosts.iter().any(|p| host_matches(p, &host));
+        if denied || !allowed {
+            return Err(EgressError::HostNotAllowed(host));
+        }
+        Ok(())
+    }
+
+    pub fn check_addr(&self, host: &str, addr: IpAddr) -> Result<(), EgressError> {
+        if self.allowed_addrs.contains(&addr) || is_public(addr) {
+            return Ok(());
+        }
+        Err(EgressError::AddressNotAllowed {
+            host: host.to_string(),
+            addr,
+        })
+    }
+
+    /// Resolves `host`, failing when any of its addresses is not allowed.
+    async fn resolve(&self, host: &str) -> Result<Vec<IpAddr>, EgressError> {
+        self.check_host(host)?;
+        let addrs = tokio::net::lookup_host((host, 0))
+            .await
+            .map_err(|source| EgressError::Resolve {
+                host: host.to_string(),
+                source,
+            })?
+            .map(|addr| addr.ip())
+            .collect::<Vec<_>>();
+        for addr in &addrs {
+            self.check_addr(host, *addr)?;
+        }
+        Ok(addrs)
+    }
+}
+
+/// Resolves the hosts an [`EgressClient`] connects to, so the addresses actually
+/// connected to are the ones checked against the policy.
+struct PolicyResolver(UrlPolicy);
+
+impl Resolve for PolicyResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let policy = self.0.clone();
+        Box::pin(async move {
+            let addrs = policy.resolve(name.as_str()).await?;
+            let addrs: Addrs = Box::new(addrs.into_iter().map(|addr| (addr, 0).into()));
+            Ok(addrs)
+        })
+    }
+}
+
+impl EgressClient {
+    pub fn new(policy: UrlPolicy) -> Result<Self, EgressError> {
+        let redirect_policy = policy.clone();
+        let http = reqwest::Client::builder()
+            .dns_resolver(Arc::new(PolicyResolver(policy.clone())))
+            .redirect(reqwest::redirect::Policy::custom(move |attempt| {
+                if attempt.previous().len() >= MAX_REDIRECTS {
+                    return attempt.error("too many redirects");
+                }
+                match redirect_policy.check_url(attempt.url().as_str()) {
+                    Ok(_) => attempt.follow(),
+                    Err(e) => attempt.error(e),
+                }
+            }))
+            // a proxy would resolve hosts itself, out of reach of the policy
+            .no_proxy()
+            .build()
+            .map_err(EgressError::Client)?;
+        Ok(Self { policy, http })
+    }
+
+    pub fn policy(&self) -> &UrlPolicy {
+        &self.policy
+    }
+
+    pub fn request(&self, method: Method, url: &str) -> Result<RequestBuilder, EgressError> {
+        let url = self.policy.check_url(url)?;
+        Ok(self.http.request(method, url))
+    }
+
+    pub fn get(&self, url: &str) -> Result<RequestBuilder, EgressError> {
+        self.request(Method::GET, url)
+    }
+
+    pub fn post(&self, url: &str) -> Result<RequestBuilder, EgressError> {
+        self.request(Method::POST, url)
+    }
+}
+
+impl FileResolver {
+    pub fn new(client: EgressClient) -> Self {
+        Self {
+            client,
+            max_size: DEFAULT_MAX_FILE_SIZE,
+        }
+    }
+
+    /// Maximum size of fetched files, 16 MiB by default.
+    pub fn with_max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Content of `file`, fetched when the file is given by uri.
+    pub async fn resolve(&self, file: &File) -> Result<Vec<u8>, EgressError> {
+        let url = match file {
+            File::FileWithBytes(bytes) => return Ok(bytes.clone()),
+            File::FileWithUri(url) => url,
+        };
+        let request_error = |source| EgressError::Request {
+            url: url.clone(),
+            source,
+        };
+        let mut res = self.client.get(url)?.send().await.map_err(request_error)?;
+        if !res.status().is_success() {
+            return Err(EgressError::Status {
+                url: url.clone(),
+                status: res.status(),
+            });
+        }
+        let too_large = || EgressError::TooLarge {
+            url: url.clone(),
+            limit: self.max_size,
+        };
+        if res.content_length().unwrap_or_default() > self.max_size as u64 {
+            return Err(too_large());
+        }
+        let mut content = vec![];
+        while let Some(chunk) = res.chunk().await.map_err(request_error)? {
+            if content.len() + chunk.len() > self.max_size {
+                return Err(too_large());
+            }
+            content.extend_from_slice(&chunk);
+        }
+        Ok(content)
+    }
+}
+
+/// Address of `host` when it is an ip address, ipv6 addresses being bracketed in urls.
+fn ip_host(host: &str) -> Option<IpAddr> {
+    host.trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse()
+        .ok()
+}
+
+/// Whether `pattern` is `host` or, for `*.suffix` patterns, a subdomain of `suffix`.
+fn host_matches(pattern: &str, host: &str) -> bool {
+    match pattern.strip_prefix("*.") {
+        Some(suffix) => host
+            .strip_suffix(suffix)
+            .is_some_and(|sub| sub.ends_with('.') && sub.len() > 1),
+        None => pattern == host,
+    }
+}
+
+/// Whether `addr` is a globally routable address.
+fn is_public(addr: IpAddr) -> bool {
+    match addr {
+        IpAddr::V4(addr) => is_public_v4(addr),
+        IpAddr::V6(addr) => is_public_v6(addr),
+    }
+}
+
+fn is_public_v4(addr: Ipv4Addr) -> bool {
+    let [a, b, c, _] = addr.octets();
+    !(addr.is_unspecified()
+        || addr.is_loopback()
+        || addr.is_private()
+        || addr.is_link_local()
+        || addr.is_broadcast()
+        || addr.is_documentation()
+        || addr.is_multicast()
+        || a == 0
+        // shared address space
+        || (a == 100 && (64..128).contains(&b))
+        // ietf protocol assignments
+        || (a == 192 && b == 0 && c == 0)
+        // benchmarking
+        || (a == 198 && (18..20).contains(&b))
+        // reserved
+        || a >= 240)
+}
+
+fn is_public_v6(addr: Ipv6Addr) -> bool {
+    if let Some(v4) = addr.to_ipv4_mapped() {
+        return is_public_v4(v4);
+    }
+    let segments = addr.segments();
+    // nat64 and 6to4 addresses reach the ipv4 address they embed
+    if segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+        return is_public_v4(Ipv4Addr::from(
+            ((segments[6] as u32) << 16) | segments[7] as u32,
+        ));
+    }
+    if segments[0] == 0x2002 {
+        return is_public_v4(Ipv4Addr::from(
+            ((segments[1] as u32) << 16) | segments[2] as u32,
+        ));
+    }
+    !(addr.is_unspecified()
+        || addr.is_loopback()
+        || addr.is_multicast()
+        // unique local
+        || (segments[0] & 0xfe00) == 0xfc00
+        // link local and deprecated site local
+        || (segments[0] & 0xffc0) == 0xfe80
+        || (segments[0] & 0xffc0) == 0xfec0
+        // documentation
+        || (segments[0] == 0x2001 && segments[1] == 0x0db8))
+}
@@ -0,0 +1,19 @@
+use std::time::Duration;
+use tonic::codec::CompressionEncoding;
+
+/// Codec and HTTP/2 settings of a gRPC server or client, applied to every RPC.
+///
+/// Unset settings keep the defaults of tonic and hyper.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GrpcConfig {
+    pub(crate) max_decoding_message_size: usize,
+    pub(crate) max_encoding_message_size: usize,
+    pub(crate) accept_compression: Vec<CompressionEncoding>,
+    pub(crate) send_compression: Option<CompressionEncoding>,
+    pub(crate) keepalive_interval: Option<Duration>,
+    pub(crate) keepalive_timeout: Option<Duration>,
+    pub(crate) tcp_keepalive: Option<Duration>,
+    pub(crate) initial_stream_window_size: Option<u32>,
+    pub(crate) initial_connection_window_size: Option<u32>,
+    pub(crate) concurrency_limit: Option<usize>,
+}
@@ -0,0 +1,165 @@
+use crate::grpc::GrpcConfig;
+use std::time::Duration;
+use tonic::codec::{Codec, CompressionEncoding};
+use tonic::transport::{Endpoint, Server};
+
+const DEFAULT_MAX_MESSAGE_SIZE: usize = 4 * 1024 * 1024;
+
+impl Default for GrpcConfig {
+    fn default() -> Self {
+        Self {
+            max_decoding_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            max_encoding_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            accept_compression: vec![CompressionEncoding::Gzip],
+            send_compression: Some(CompressionEncoding::Gzip),
+            keepalive_interval: None,
+            keepalive_timeout: None,
+            tcp_keepalive: None,
+            initial_stream_window_size: None,
+            initial_connection_window_size: None,
+            concurrency_limit: None,
+        }
+    }
+}
+
+impl GrpcConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Largest message accepted from the peer, 4 MiB by default.
+    pub fn with_max_decoding_message_size(mut self, size: usize) -> Self {
+        self.max_decoding_message_size = size;
+        self
+    }
+
+    /// Largest message sent to the peer, 4 MiB by default.
+    pub fn with_max_encoding_message_size(mut self, size: usize) -> Self {
+        self.max_encoding_message_size = size;
+        self
+    }
+
+    /// Replaces the compressions accepted from the peer, only gzip by default.
+    pub fn with_accept_compression(
+        mut self,
+        encodings: impl IntoIterator<Item = CompressionEncoding>,
+    ) -> Self {
+        self.accept_compression = encodings.into_iter().collect();
+        self
+    }
+
+    /// Compresses sent messages, with gzip by default. Servers only compress responses to
+    /// clients accepting the encoding, clients compress every request so the server must
+    /// accept it.
+    pub fn with_send_compression(mut self, encoding: CompressionEncoding) -> Self {
+        self.send_compression = Some(encoding);
+        self
+    }
+
+    /// Sends messages uncompressed, e.g. to servers not accepting gzip.
+    pub fn without_send_compression(mut self) -> Self {
+        self.send_compression = None;
+        self
+    }
+
+    /// Pings the peer every `interval` and closes the connection when a ping is not
+    /// acknowledged within `timeout`.
+    pub fn with_keepalive(mut self, interval: Duration, timeout: Duration) -> Self {
+        self.keepalive_interval = Some(interval);
+        self.keepalive_timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_tcp_keepalive(mut self, tcp_keepalive: Duration) -> Self {
+        self.tcp_keepalive = Some(tcp_keepalive);
+        self
+    }
+
+    /// HTTP/2 flow control windows of each stream and of the whole connection.
+    pub fn with_window_sizes(mut self, stream: u32, connection: u32) -> Self {
+        self.initial_stream_window_size = Some(stream);
+        self.initial_connection_window_size = Some(connection);
+        self
+    }
+
+    /// Maximum number of RPCs in flight, per connection on servers and per channel on
+    /// clients.
+    pub fn with_concurrency_limit(mut self, limit: usize) -> Self {
+        self.concurrency_limit = Some(limit);
+        self
+    }
+
+    pub(crate) fn server(&self, server: Server) -> Server {
+        let server = server
+            .http2_keepalive_interval(self.keepalive_interval)
+            .http2_keepalive_timeout(self.keepalive_timeout)
+            .tcp_keepalive(self.tcp_keepalive)
+            .initial_stream_window_size(self.initial_stream_window_size)
+            .initial_connection_window_size(self.initial_connection_window_size);
+        match self.concurrency_limit {
+            Some(limit) => server.concurrency_limit_per_connection(limit),
+            None => server,
+        }
+    }
+
+    /// Applies the HTTP/2 keepalive and window settings to the connections of a server not
+    /// built with tonic, which leaves the TCP keepalive and concurrency limit to the server.
+    #[cfg(feature = "agent")]
+    pub(crate) fn connection(
+        &self,
+        mut builder: crate::server::ConnectionBuilder,
+    ) -> crate::server::ConnectionBuilder {
+        let mut http2 = builder.http2();
+        http2
+            .keep_alive_interval(self.keepalive_interval)
+            .initial_stream_window_size(self.initial_stream_window_size)
+            .initial_connection_window_size(self.initial_connection_window_size);
+        if let Some(timeout) = self.keepalive_timeout {
+            http2.keep_alive_timeout(timeout);
+        }
+        builder
+    }
+
+    pub(crate) fn endpoint(&self, endpoint: Endpoint) -> Endpoint {
+        let mut endpoint = endpoint
+            .tcp_keepalive(self.tcp_keepalive)
+            .initial_stream_window_size(self.initial_stream_window_size)
+            .initial_connection_window_size(self.initial_connection_window_size);
+        if let Some(interval) = self.keepalive_interval {
+            endpoint = endpoint.http2_keep_alive_interval(interval);
+        }
+        if let Some(timeout) = self.keepalive_timeout {
+            endpoint = endpoint.keep_alive_timeout(timeout);
+        }
+        if let Some(limit) = self.concurrency_limit {
+            endpoint = endpoint.concurrency_limit(limit);
+        }
+        endpoint
+    }
+
+    pub(crate) fn server_codec<C: Codec>(&self, codec: C) -> tonic::server::Grpc<C> {
+        let mut grpc = tonic::server::Grpc::new(codec)
+            .max_decoding_message_size(self.max_decoding_message_size)
+            .max_encoding_message_size(self.max_encoding_message_size);
+        for encoding in &self.accept_compression {
+            grpc = grpc.accept_compressed(*encoding);
+        }
+        match self.send_compression {
+            Some(encoding) => grpc.send_compressed(encoding),
+            None => grpc,
+        }
+    }
+
+    pub(crate) fn client<T>(&self, grpc: tonic::client::Grpc<T>) -> tonic::client::Grpc<T> {
+        let mut grpc = grpc
+            .max_decoding_message_size(self.max_decoding_message_size)
+            .max_encoding_message_size(self.max_encoding_message_size);
+        for encoding in &self.accept_compression {
+            grpc = grpc.accept_compressed(*encoding);
+        }
+        match self.send_compression {
+            Some(encoding) => grpc.send_compressed(encoding),
+            None => grpc,
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum JwsError {
+    #[error("Invalid PEM")]
+    Pem(#[from] rustls::pki_types::pem::Error),
+
+    #[error("Expected a PKCS#8 private key")]
+    NotPkcs8,
+
+    #[error("Invalid {algorithm} key: {reason}")]
+    InvalidKey {
+        algorithm: &'static str,
+        reason: String,
+    },
+
+    #[error("Unsupported JWS algorithm: {0}")]
+    UnsupportedAlgorithm(String),
+
+    #[error("Failed to sign agent card")]
+    Signing,
+
+    #[error("Failed to encode agent card")]
+    Encoding(#[from] serde_json::Error),
+
+    #[error("Invalid base64url")]
+    Base64(#[from] base64::DecodeError),
+
+    #[error("Agent card is not signed")]
+    Unsigned,
+
+    #[error("No agent card signature verifies against a trusted key")]
+    InvalidSignature,
+
+    #[error("Failed to fetch JWKS from {url}")]
+    JwksFetch {
+        url: String,
+        #[source]
+        source: reqwest::Error,
+    },
+}
@@ -0,0 +1,100 @@
+use ring::signature::{EcdsaKeyPair, Ed25519KeyPair, RsaKeyPair};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+/// JWS algorithms agent cards can be signed with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Algorithm {
+    /// ECDSA using P-256 and SHA-256.
+    ES256,
+    /// RSASSA-PKCS1-v1_5 using SHA-256.
+    RS256,
+    /// Ed25519.
+    EdDSA,
+}
+
+/// Private key agent cards are signed with.
+#[derive(Clone)]
+pub struct SigningKey {
+    pub(crate) key: Arc<KeyPair>,
+    pub(crate) key_id: Option<String>,
+    pub(crate) jku: Option<String>,
+}
+
+pub(crate) enum KeyPair {
+    Ecdsa(EcdsaKeyPair),
+    Rsa(RsaKeyPair),
+    Ed25519(Ed25519KeyPair),
+}
+
+/// Public JSON Web Key, RFC 7517, of type `EC` (P-256), `RSA` or `OKP` (Ed25519).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Jwk {
+    pub kty: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kid: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alg: Option<Algorithm>,
+    #[serde(rename = "use", default, skip_serializing_if = "Option::is_none")]
+    pub key_use: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crv: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub x: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub y: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub n: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub e: Option<String>,
+}
+
+/// JSON Web Key Set, as served at a `jku` url.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JwkSet {
+    pub keys: Vec<Jwk>,
+}
+
+/// Checks the signatures of agent cards against a set of trusted keys.
+///
+/// A card is accepted when at least one of its signatures verifies against a trusted
+/// key. Unsigned cards are refused unless allowed with
+/// [`CardVerifier::with_allow_unsigned`].
+#[derive(Debug, Clone, Default)]
+pub struct CardVerifier {
+    pub(crate) keys: Vec<Jwk>,
+    pub(crate) allow_unsigned: bool,
+}
+
+/// Protected header of an agent card signature.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct ProtectedHeader {
+    pub alg: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub typ: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kid: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jku: Option<String>,
+}
+
+impl Debug for SigningKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SigningKey")
+            .field("algorithm", &self.algorithm())
+            .field("key_id", &self.key_id)
+            .field("jku", &self.jku)
+            .finish()
+    }
+}
+
+impl KeyPair {
+    pub(crate) fn algorithm(&self) -> Algorithm {
+        match self {
+            KeyPair::Ecdsa(_) => Algorithm::ES256,
+            KeyPair::Rsa(_) => Algorithm::RS256,
+            KeyPair::Ed25519(_) => Algorithm::EdDSA,
+        }
+    }
+}
@@ -0,0 +1,429 @@
+use crate::core::agent::{AgentCard, AgentCardSignature};
+use crate::jws::{
+    Algorithm, CardVerifier, Jwk, JwkSet, JwsError, KeyPair, ProtectedHeader, SigningKey,
+};
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use ring::rand::SystemRandom;
+use ring::rsa::PublicKeyComponents;
+use ring::signature::{
+    ECDSA_P256_SHA256_FIXED, ECDSA_P256_SHA256_FIXED_SIGNING, ED25519, EcdsaKeyPair,
+    Ed25519KeyPair, KeyPair as _, RSA_PKCS1_2048_8192_SHA256, RSA_PKCS1_SHA256, RsaKeyPair,
+    UnparsedPublicKey,
+};
+use rustls::pki_types::PrivateKeyDer;
+use rustls::pki_types::pem::PemObject;
+use serde_json::{Number, Value};
+use std::fmt::Write;
+use std::sync::Arc;
+
+/// `typ` of the protected header of agent card signatures.
+pub const AGENT_CARD_JWS_TYPE: &str = "JOSE";
+
+impl Algorithm {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Algorithm::ES256 => "ES256",
+            Algorithm::RS256 => "RS256",
+            Algorithm::EdDSA => "EdDSA",
+        }
+    }
+
+    pub(crate) fn parse(alg: &str) -> Option<Self> {
+        match alg {
+            "ES256" => Some(Algorithm::ES256),
+            "RS256" => Some(Algorithm::RS256),
+            "EdDSA" => Some(Algorithm::EdDSA),
+            _ => None,
+        }
+    }
+}
+
+impl SigningKey {
+    /// Loads a PEM encoded PKCS#8 private key, as written by
+    /// `openssl genpkey`.
+    pub fn from_pkcs8_pem(algorithm: Algorithm, pem: impl AsRef<[u8]>) -> Result<Self, JwsError> {
+        match PrivateKeyDer::from_pem_slice(pem.as_ref())? {
+            PrivateKeyDer::Pkcs8(der) => Self::from_pkcs8_der(algorithm, der.secret_pkcs8_der()),
+            _ => Err(JwsError::NotPkcs8),
+        }
+    }
+
+    /// Generates a new key. RSA keys cannot be generated and must be loaded instead.
+    pub fn generate(algorithm: Algorithm) -> Result<Self, JwsError> {
+        let rng = SystemRandom::new();
+        let der = match algorithm {
+            Algorithm::ES256 => {
+                EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &rng)
+            }
+            Algorithm::EdDSA => Ed25519KeyPair::generate_pkcs8(&rng),
+            Algorithm::RS256 => {
+                return Err(JwsError::UnsupportedAlgorithm(
+                    "RS256 key generation".to_string(),
+                ));
+            }
+        }
+        .map_err(|_| JwsError::Signing)?;
+        Self::from_pkcs8_der(algorithm, der.as_ref())
+    }
+
+    pub fn from_pkcs8_der(algorithm: Algorithm, der: &[u8]) -> Result<Self, JwsError> {
+        let rejected = |e: ring::error::KeyRejected| JwsError::InvalidKey {
+            algorithm: algorithm.as_str(),
+            reason: e.to_string(),
+        };
+        let key = match algorithm {
+            Algorithm::ES256 => KeyPair::Ecdsa(
+                EcdsaKeyPair::from_pkcs8(
+                    &ECDSA_P256_SHA256_FIXED_SIGNING,
+                    der,
+                    &SystemRandom::new(),
+                )
+                .map_err(rejected)?,
+            ),
+            Algorithm::RS256 => KeyPair::Rsa(RsaKeyPair::from_pkcs8(der).map_err(rejected)?),
+            Algorithm::EdDSA => {
+                KeyPair::Ed25519(Ed25519KeyPair::from_pkcs8_maybe_unchecked(der).map_err(rejected)?)
+            }
+        };
+        Ok(Self {
+            key: Arc::new(key),
+            key_id: None,
+            jku: None,
+        })
+    }
+
+    /// Sets the `kid` of signatures and of the public key, letting verifiers pick the key
+    /// out of a key set.
+    pub fn with_key_id(mut self, key_id: impl Into<String>) -> Self {
+        self.key_id = Some(key_id.into());
+        self
+    }
+
+    /// Sets the `jku`, the url the key set with the public key is published at.
+    pub fn with_jku(mut self, jku: impl Into<String>) -> Self {
+        self.jku = Some(jku.into());
+        self
+    }
+
+    pub fn algorithm(&self) -> Algorithm {
+        self.key.algorithm()
+    }
+
+    /// The public key, to be published in the JWKS or pinned by clients.
+    pub fn public_jwk(&self) -> Jwk {
+        let mut jwk = Jwk {
+            kty: String::new(),
+            kid: self.key_id.clone(),
+            alg: Some(self.algorithm()),
+            key_use: Some("sig".to_string()),
+            crv: None,
+            x: None,
+            y: None,
+            n: None,
+            e: None,
+        };
+        match self.key.as_ref() {
+            KeyPair::Ecdsa(key) => {
+                // uncompressed point: 0x04 | x | y
+                let point = key.public_key().as_ref();
+                jwk.kty = "EC".to_string();
+                jwk.crv = Some("P-256".to_string());
+                jwk.x = Some(URL_SAFE_NO_PAD.encode(&point[1..33]));
+                jwk.y = Some(URL_SAFE_NO_PAD.encode(&point[33..65]));
+            }
+            KeyPair::Rsa(key) => {
+                let components = PublicKeyComponents::<Vec<u8>>::from(key.public());
+                jwk.kty = "RSA".to_string();
+                jwk.n = Some(URL_SAFE_NO_PAD.encode(&components.n));
+                jwk.e = Some(URL_SAFE_NO_PAD.encode(&components.e));
+            }
+            KeyPair::Ed25519(key) => {
+                jwk.kty = "OKP".to_string();
+                jwk.crv = Some("Ed25519".to_string());
+                jwk.x = Some(URL_SAFE_NO_PAD.encode(key.public_key().as_ref()));
+            }
+        }
+        jwk
+    }
+
+    /// Computes a detached JWS over the JCS canonical form of the card, without its
+    /// `signatures`.
+    pub fn sign(&self, card: &AgentCard) -> Result<AgentCardSignature, JwsError> {
+        self.sign_json(&serde_json::to_value(card)?)
+    }
+
+    /// Like [`SigningKey::sign`], for a card in its JSON form, covering every member it has.
+    pub fn sign_json(&self, card: &Value) -> Result<AgentCardSignature, JwsError> {
+        let header = ProtectedHeader {
+            alg: self.algorithm().as_str().to_string(),
+            typ: Some(AGENT_CARD_JWS_TYPE.to_string()),
+            kid: self.key_id.clone(),
+            jku: self.jku.clone(),
+        };
+        let protected = URL_SAFE_NO_PAD.encode(serde_json::to_vec(&header)?);
+        let input = signing_input(&protected, card)?;
+        Ok(AgentCardSignature {
+            protected,
+            signature: URL_SAFE_NO_PAD.encode(self.sign_input(&input)?),
+            header: None,
+        })
+    }
+
+    /// Signs `payload` as a compact JWS, `header.payload.signature`, with `typ` in the
+    /// protected header.
+    pub(crate) fn sign_compact(&self, typ: &str, payload: &[u8]) -> Result<String, JwsError> {
+        let header = ProtectedHeader {
+            alg: self.algorithm().as_str().to_string(),
+            typ: Some(typ.to_string()),
+            kid: self.key_id.clone(),
+            jku: self.jku.clone(),
+        };
+        let input = format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(serde_json::to_vec(&header)?),
+            URL_SAFE_NO_PAD.encode(payload)
+        );
+        let signature = URL_SAFE_NO_PAD.encode(self.sign_input(input.as_bytes())?);
+        Ok(format!("{input}.{signature}"))
+    }
+
+    fn sign_input(&self, input: &[u8]) -> Result<Vec<u8>, JwsError> {
+        let rng = SystemRandom::new();
+        let signature = match self.key.as_ref() {
+            KeyPair::Ecdsa(key) => key
+                .sign(&rng, input)
+                .map_err(|_| JwsError::Signing)?
+                .as_ref()
+                .to_vec(),
+            KeyPair::Rsa(key) => {
+                let mut signature = vec![0; key.public().modulus_len()];
+                key.sign(&RSA_PKCS1_SHA256, &rng, input, &mut signature)
+                    .map_err(|_| JwsError::Signing)?;
+                signature
+            }
+            KeyPair::Ed25519(key) => key.sign(input).as_ref().to_vec(),
+        };
+        Ok(signature)
+    }
+}
+
+impl AgentCard {
+    /// Appends a signature of the card by `key`, see [`SigningKey::sign`].
+    pub fn with_signature(mut self, key: &SigningKey) -> Result<Self, JwsError> {
+        let signature = key.sign(&self)?;
+        self.signatures.push(signature);
+        Ok(self)
+    }
+}
+
+impl JwkSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_key(mut self, key: Jwk) -> Self {
+        self.keys.push(key);
+        self
+    }
+
+    /// Fetches the key set published at `url`.
+    pub async fn fetch(url: &str) -> Result<Self, JwsError> {
+        let fetch = async {
+            reqwest::get(url)
+                .await?
+                .error_for_status()?
+                .json::<JwkSet>()
+                .await
+        };
+        fetch.await.map_err(|source| JwsError::JwksFetch {
+            url: url.to_string(),
+            source,
+        })
+    }
+}
+
+impl CardVerifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Trusts the public key, pinning it for the agent.
+    pub fn with_key(mut self, key: Jwk) -> Self {
+        self.keys.push(key);
+        self
+    }
+
+    /// Trusts every key of the set.
+    pub fn with_jwks(mut self, jwks: JwkSet) -> Self {
+        self.keys.extend(jwks.keys);
+        self
+    }
+
+    /// Accepts cards without signatures. Signed cards must still verify.
+    pub fn with_allow_unsigned(mut self, allow_unsigned: bool) -> Self {
+        self.allow_unsigned = allow_unsigned;
+        self
+    }
+
+    /// Verifies `card` as this crate serializes it. Cards received from other agents
+    /// should be verified as received, with [`CardVerifier::verify_json`].
+    pub fn verify(&self, card: &AgentCard) -> Result<(), JwsError> {
+        self.verify_json(&serde_json::to_value(card)?)
+    }
+
+    /// Verifies the JSON of a card, the signatures covering every member but `signatures`,
+    /// including the ones [`AgentCard`] does not model.
+    pub fn verify_json(&self, card: &Value) -> Result<(), JwsError> {
+        let signatures: Vec<AgentCardSignature> = match card.get("signatures") {
+            Some(signatures) => serde_json::from_value(signatures.clone())?,
+            None => vec![],
+        };
+        if signatures.is_empty() {
+            return match self.allow_unsigned {
+                true => Ok(()),
+                false => Err(JwsError::Unsigned),
+            };
+        }
+        for signature in &signatures {
+            match self.verify_signature(signature, card) {
+                Ok(true) => return Ok(()),
+                Ok(false) => {}
+                Err(e) => tracing::debug!(error = %e, "skipping malformed agent card signature"),
+            }
+        }
+        Err(JwsError::InvalidSignature)
+    }
+
+    fn verify_signature(
+        &self,
+        signature: &AgentCardSignature,
+        card: &Value,
+    ) -> Result<bool, JwsError> {
+        let header: ProtectedHeader =
+            serde_json::from_slice(&URL_SAFE_NO_PAD.decode(&signature.protected)?)?;
+        let algorithm = Algorithm::parse(&header.alg)
+            .ok_or_else(|| JwsError::UnsupportedAlgorithm(header.alg.clone()))?;
+        let input = signing_input(&signature.protected, card)?;
+        let bytes = URL_SAFE_NO_PAD.decode(&signature.signature)?;
+        let verified = self
+            .keys
+            .iter()
+            .filter(|key| match (&header.kid, &key.kid) {
+                (Some(wanted), Some(kid)) => wanted == kid,
+                _ => true,
+            })
+            .filter(|key| key.alg.is_none_or(|alg| alg == algorithm))
+            .any(|key| verify(key, algorithm, &input, &bytes));
+        Ok(verified)
+    }
+}
+
+/// Verifies `signature` of `input` with `key`, `false` when the key does not fit the
+/// algorithm.
+pub(crate) fn verify(key: &Jwk, algorithm: Algorithm, input: &[u8], signature: &[u8]) -> bool {
+    let decode = |v: &Option<String>| v.as_ref().and_then(|v| URL_SAFE_NO_PAD.decode(v).ok());
+    match (algorithm, key.kty.as_str(), key.crv.as_deref()) {
+        (Algorithm::ES256, "EC", Some("P-256")) => {
+            let (Some(x), Some(y)) = (decode(&key.x), decode(&key.y)) else {
+                return false;
+            };
+            let point = [&[0x04], x.as_slice(), y.as_slice()].concat();
+            UnparsedPublicKey::new(&ECDSA_P256_SHA256_FIXED, point)
+                .verify(input, signature)
+                .is_ok()
+        }
+        (Algorithm::RS256, "RSA", _) => {
+            let (Some(n), Some(e)) = (decode(&key.n), decode(&key.e)) else {
+                return false;
+            };
+            PublicKeyComponents { n, e }
+                .verify(&RSA_PKCS1_2048_8192_SHA256, input, signature)
+                .is_ok()
+        }
+        (Algorithm::EdDSA, "OKP", Some("Ed25519")) => {
+            let Some(x) = decode(&key.x) else {
+                return false;
+            };
+            UnparsedPublicKey::new(&ED25519, x)
+                .verify(input, signature)
+                .is_ok()
+        }
+        _ => false,
+    }
+}
+
+/// `protected || '.' || base64url(payload)`, the payload being the canonical card.
+fn signing_input(protected: &str, card: &Value) -> Result<Vec<u8>, JwsError> {
+    let mut value = card.clone();
+    if let Value::Object(map) = &mut value {
+        map.remove("signatures");
+    }
+    let mut payload = String::new();
+    canonicalize(&value, &mut payload)?;
+    Ok(format!("{protected}.{}", URL_SAFE_NO_PAD.encode(payload)).into_bytes())
+}
+
+/// Writes `value` in the JSON Canonicalization Scheme of RFC 8785: no whitespace, object
+/// members sorted by the UTF-16 code units of their names and numbers formatted the way
+/// ECMAScript does.
+fn canonicalize(value: &Value, out: &mut String) -> Result<(), JwsError> {
+    match value {
+        Value::Object(map) => {
+            let mut members: Vec<_> = map.iter().collect();
+            members.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+            out.push('{');
+            for (i, (name, value)) in members.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&serde_json::to_string(name)?);
+                out.push(':');
+                canonicalize(value, out)?;
+            }
+            out.push('}');
+        }
+        Value::Array(values) => {
+            out.push('[');
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                canonicalize(value, out)?;
+            }
+            out.push(']');
+        }
+        Value::Number(number) => write_number(number, out),
+        value => out.push_str(&serde_json::to_string(value)?),
+    }
+    Ok(())
+}
+
+/// Largest integer up to which every integer has an exact double representation.
+const MAX_SAFE_INTEGER: u64 = 1 << 53;
+
+fn write_number(number: &Number, out: &mut String) {
+    // Integers beyond 2^53 are not exact doubles, so they serialize like any other number.
+    let exact = number
+        .as_i64()
+        .is_some_and(|n| n.unsigned_abs() <= MAX_SAFE_INTEGER)
+        || number.as_u64().is_some_and(|n| n <= MAX_SAFE_INTEGER);
+    if exact {
+        let _ = write!(out, "{number}");
+        return;
+    }
+    let n = number.as_f64().unwrap_or_default();
+    if n == 0.0 {
+        out.push('0');
+    } else if (1e-6..1e21).contains(&n.abs()) {
+        let _ = write!(out, "{n}");
+    } else {
+        let exponential = format!("{n:e}");
+        match exponential.split_once('e') {
+            Some((mantissa, exponent)) if !exponent.starts_with('-') => {
+                let _ = write!(out, "{mantissa}e+{exponent}");
+            }
+            _ => out.push_str(&exponential),
+        }
+    }
+}
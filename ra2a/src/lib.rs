@@ -2,12 +2,22 @@
 
 #[cfg(feature = "agent")]
 pub mod agent;
+#[cfg(feature = "agent")]
+pub mod auth;
 pub mod broker;
 pub mod client;
 pub mod core;
+pub mod egress;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod jws;
+#[cfg(feature = "agent")]
+pub mod limit;
+pub mod push;
 #[cfg(feature = "agent")]
 pub mod queue;
 #[cfg(feature = "agent")]
 pub mod server;
 #[cfg(feature = "agent")]
 pub mod store;
+pub mod tls;
@@ -0,0 +1,16 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum QuotaStoreError {
+    #[error("Quota store unavailable: {0}")]
+    Unavailable(String),
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum RateLimitError {
+    #[error("Rate limit burst must be at least 1")]
+    ZeroBurst,
+
+    #[error("Rate limit refill must be a positive number of requests per second, got {0}")]
+    InvalidRefill(f64),
+}
@@ -0,0 +1,62 @@
+use crate::limit::QuotaStore;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// What the requests counted together by a rate limit or quota have in common.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LimitKey {
+    /// The authenticated caller. Anonymous requests are not counted.
+    Principal,
+    /// The ip address requests come from.
+    RemoteAddr,
+    /// The card skill a message targets. Messages targeting no particular skill and
+    /// requests other than messages are not counted.
+    Skill,
+}
+
+/// Token bucket rate limit, allowing bursts of up to `burst` requests and refilled with
+/// `refill_per_second` requests every second.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimit {
+    pub(crate) key: LimitKey,
+    pub(crate) burst: u32,
+    pub(crate) refill_per_second: f64,
+}
+
+/// Maximum number of tasks created in each fixed `window`, such as tasks per day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quota {
+    pub key: LimitKey,
+    pub limit: u64,
+    pub window: Duration,
+}
+
+/// Enforces rate limits and quotas on the requests an agent serves.
+///
+/// Rate limits keyed by principal or remote address apply to every request and are
+/// checked by the transports right after authentication. Skill rate limits and quotas
+/// are checked once the skill a message targets is known. Buckets are kept in memory,
+/// per replica, while quota counters live in the [`QuotaStore`].
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    pub(crate) limits: Vec<RateLimit>,
+    pub(crate) quotas: Vec<Quota>,
+    pub(crate) store: Arc<dyn QuotaStore>,
+    // (limit index, key value) -> bucket
+    pub(crate) buckets: Arc<Mutex<HashMap<(usize, String), Bucket>>>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Bucket {
+    pub(crate) tokens: f64,
+    pub(crate) updated: Instant,
+}
+
+/// Keeps quota counters in memory, for agents running a single replica.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryQuotaStore {
+    // key -> (count, expiry)
+    pub(crate) counters: Arc<Mutex<HashMap<String, (u64, Instant)>>>,
+}
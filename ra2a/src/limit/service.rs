@@ -0,0 +1,217 @@
+use crate::agent::RequestContext;
+use crate::core::{A2AError, A2AProtocolError};
+use crate::limit::{
+    Bucket, InMemoryQuotaStore, LimitKey, Quota, QuotaStoreError, RateLimit, RateLimitError,
+    RateLimiter,
+};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::Mutex;
+
+/// Number of buckets above which full buckets are dropped, they behave like new ones.
+const MAX_IDLE_BUCKETS: usize = 1024;
+
+/// Counts quota usage. Backed by a shared database, quotas hold across every replica of
+/// an agent.
+#[async_trait]
+pub trait QuotaStore: Debug + Send + Sync {
+    /// Increments the counter `key` and returns its new value. Missing or expired counters
+    /// start from zero and expire after `ttl`.
+    async fn increment(&self, key: &str, ttl: Duration) -> Result<u64, QuotaStoreError>;
+}
+
+#[async_trait]
+impl QuotaStore for InMemoryQuotaStore {
+    async fn increment(&self, key: &str, ttl: Duration) -> Result<u64, QuotaStoreError> {
+        let now = Instant::now();
+        let mut counters = self.counters.lock().await;
+        counters.retain(|_, (_, expires_at)| *expires_at > now);
+        let (count, _) = counters.entry(key.to_string()).or_insert((0, now + ttl));
+        *count += 1;
+        Ok(*count)
+    }
+}
+
+impl RateLimit {
+    /// Fails when `burst` is zero, which would reject every request, or `refill_per_second`
+    /// is not positive, which would never let a drained bucket recover.
+    pub fn new(key: LimitKey, burst: u32, refill_per_second: f64) -> Result<Self, RateLimitError> {
+        if burst == 0 {
+            return Err(RateLimitError::ZeroBurst);
+        }
+        if !(refill_per_second > 0.0 && refill_per_second.is_finite()) {
+            return Err(RateLimitError::InvalidRefill(refill_per_second));
+        }
+        Ok(Self {
+            key,
+            burst,
+            refill_per_second,
+        })
+    }
+
+    pub fn key(&self) -> LimitKey {
+        self.key
+    }
+
+    pub fn burst(&self) -> u32 {
+        self.burst
+    }
+
+    pub fn refill_per_second(&self) -> f64 {
+        self.refill_per_second
+    }
+}
+
+impl Quota {
+    pub fn new(key: LimitKey, limit: u64, window: Duration) -> Self {
+        Self { key, limit, window }
+    }
+
+    pub fn per_day(key: LimitKey, limit: u64) -> Self {
+        Self::new(key, limit, Duration::from_secs(24 * 60 * 60))
+    }
+}
+
+impl Bucket {
+    fn refill(&mut self, limit: &RateLimit, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * limit.refill_per_second).min(limit.burst as f64);
+        self.updated = now;
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self {
+            limits: vec![],
+            quotas: vec![],
+            store: Arc::new(InMemoryQuotaStore::default()),
+            buckets: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+impl RateLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_limit(mut self, limit: RateLimit) -> Self {
+        self.limits.push(limit);
+        self
+    }
+
+    pub fn with_quota(mut self, quota: Quota) -> Self {
+        self.quotas.push(quota);
+        self
+    }
+
+    /// Where quota usage is counted, in memory by default.
+    pub fn with_quota_store(mut self, store: impl QuotaStore + 'static) -> Self {
+        self.store = Arc::new(store);
+        self
+    }
+
+    /// Takes a token from the principal and remote address buckets of the caller.
+    pub async fn check_request(&self, context: &RequestContext) -> Result<(), A2AError> {
+        self.take(context, None, |key| key != LimitKey::Skill).await
+    }
+
+    /// Takes a token from the bucket of the targeted skill and, for messages creating a
+    /// task, counts the task towards the quotas of the caller.
+    pub async fn check_message(
+        &self,
+        context: &RequestContext,
+        skill: Option<&str>,
+        is_new: bool,
+    ) -> Result<(), A2AError> {
+        self.take(context, skill, |key| key == LimitKey::Skill)
+            .await?;
+        if is_new {
+            self.count_task(context, skill).await?;
+        }
+        Ok(())
+    }
+
+    /// Takes a token from every matching bucket, or from none of them when one is empty.
+    async fn take(
+        &self,
+        context: &RequestContext,
+        skill: Option<&str>,
+        matches: impl Fn(LimitKey) -> bool,
+    ) -> Result<(), A2AError> {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().await;
+        let mut keys = vec![];
+        for (i, limit) in self.limits.iter().enumerate() {
+            if !matches(limit.key) {
+                continue;
+            }
+            let Some(value) = key_value(limit.key, context, skill) else {
+                continue;
+            };
+            let bucket = buckets.entry((i, value.clone())).or_insert_with(|| Bucket {
+                tokens: limit.burst as f64,
+                updated: now,
+            });
+            bucket.refill(limit, now);
+            if bucket.tokens < 1.0 {
+                let wait = (1.0 - bucket.tokens) / limit.refill_per_second;
+                let retry_after = (wait.ceil() as u64).max(1);
+                tracing::debug!(key = ?limit.key, retry_after, "rate limit exceeded");
+                return Err(A2AProtocolError::rate_limit_exceeded(retry_after).into());
+            }
+            keys.push((i, value));
+        }
+        for key in keys {
+            if let Some(bucket) = buckets.get_mut(&key) {
+                bucket.tokens -= 1.0;
+            }
+        }
+        if buckets.len() > MAX_IDLE_BUCKETS {
+            buckets.retain(|(i, _), bucket| {
+                bucket.refill(&self.limits[*i], now);
+                bucket.tokens < self.limits[*i].burst as f64
+            });
+        }
+        Ok(())
+    }
+
+    async fn count_task(
+        &self,
+        context: &RequestContext,
+        skill: Option<&str>,
+    ) -> Result<(), A2AError> {
+        let since_epoch = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        for (i, quota) in self.quotas.iter().enumerate() {
+            let Some(value) = key_value(quota.key, context, skill) else {
+                continue;
+            };
+            let window = quota.window.as_secs().max(1);
+            let index = since_epoch.as_secs() / window;
+            let ttl = Duration::from_secs((index + 1) * window) - since_epoch;
+            // quotas sharing a key and window still count apart
+            let key = format!("{i}/{:?}/{window}/{index}/{value}", quota.key);
+            let count = self.store.increment(&key, ttl).await?;
+            if count > quota.limit {
+                let retry_after = ttl.as_secs_f64().ceil() as u64;
+                tracing::debug!(key = ?quota.key, limit = quota.limit, "quota exceeded");
+                return Err(A2AProtocolError::quota_exceeded(quota.limit, retry_after).into());
+            }
+        }
+        Ok(())
+    }
+}
+
+fn key_value(key: LimitKey, context: &RequestContext, skill: Option<&str>) -> Option<String> {
+    match key {
+        LimitKey::Principal => context.principal.as_ref().map(|p| p.subject.clone()),
+        LimitKey::RemoteAddr => context.remote_addr.map(|addr| addr.ip().to_string()),
+        LimitKey::Skill => skill.map(str::to_string),
+    }
+}
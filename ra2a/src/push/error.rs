@@ -0,0 +1,39 @@
+use crate::egress::EgressError;
+use crate::jws::JwsError;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum PushError {
+    #[error(transparent)]
+    Egress(#[from] EgressError),
+
+    #[error(transparent)]
+    Jws(#[from] JwsError),
+
+    #[error("Failed to encode push notification")]
+    Encoding(#[from] serde_json::Error),
+
+    #[error("Push notification signature header is missing")]
+    MissingSignature,
+
+    #[error("Push notification signature is malformed")]
+    MalformedSignature,
+
+    #[error("Push notification signature does not verify against a trusted key")]
+    InvalidSignature,
+
+    #[error("Push notification was issued at {issued_at}, outside of the accepted window")]
+    Stale { issued_at: u64 },
+
+    #[error("Push notification {0} claim does not match")]
+    ClaimMismatch(&'static str),
+
+    #[error("Push notification token does not match")]
+    TokenMismatch,
+
+    #[error("Push notification is not bound to task {0}")]
+    TaskMismatch(String),
+
+    #[error("Push notification body does not match its signature")]
+    BodyMismatch,
+}
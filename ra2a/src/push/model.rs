@@ -0,0 +1,91 @@
+use crate::egress::EgressClient;
+use crate::jws::{Jwk, SigningKey};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+/// Keys push notifications are signed with.
+///
+/// Notifications are signed with the current key. Rotated out keys stay published in the
+/// key set for the retention period, so notifications signed just before a rotation still
+/// verify. With a rotation interval, a new key is generated once the current one is
+/// older than the interval.
+#[derive(Debug, Clone)]
+pub struct PushKeys {
+    pub(crate) ring: Arc<RwLock<KeyRing>>,
+    pub(crate) rotation: Option<Duration>,
+    pub(crate) retention: Duration,
+}
+
+#[derive(Debug)]
+pub(crate) struct KeyRing {
+    pub(crate) current: SigningKey,
+    pub(crate) created: Instant,
+    // (key, retired at)
+    pub(crate) retired: Vec<(SigningKey, Instant)>,
+}
+
+/// Delivers signed push notifications.
+///
+/// Each notification is the task as JSON, POSTed to the url of the push notification
+/// config through an [`EgressClient`]. It carries a JWT signed with the current key in
+/// the [`PUSH_SIGNATURE_HEADER`](crate::push::PUSH_SIGNATURE_HEADER), binding the
+/// notification to its task, token and body.
+#[derive(Debug, Clone)]
+pub struct PushNotifier {
+    pub(crate) client: EgressClient,
+    pub(crate) keys: PushKeys,
+    pub(crate) issuer: Option<String>,
+}
+
+/// Claims of the JWT push notifications are signed with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PushClaims {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iss: Option<String>,
+    /// Url the notification was sent to.
+    pub aud: String,
+    /// Seconds since the epoch the notification was signed at.
+    pub iat: u64,
+    /// Unique id of the notification, letting receivers drop replays.
+    pub jti: String,
+    pub task_id: String,
+    /// Token of the push notification config.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub token: String,
+    /// Base64url SHA-256 digest of the body.
+    pub body_sha256: String,
+}
+
+/// Checks the push notifications received by a webhook.
+///
+/// A notification is accepted when its signature verifies against a trusted key, it was
+/// issued within the maximum age, its body is the one signed and the task in the body is
+/// the one of the claims. Keys are pinned or fetched from the JWKS url of the agent, which
+/// is fetched again when a notification is signed with an unknown key, at most once per
+/// refetch interval. Fetched keys never replace pinned ones.
+#[derive(Debug, Clone)]
+pub struct PushVerifier {
+    pub(crate) pinned: Vec<Jwk>,
+    pub(crate) fetched: Arc<RwLock<FetchedKeys>>,
+    pub(crate) jwks_url: Option<String>,
+    pub(crate) refetch_interval: Duration,
+    pub(crate) issuer: Option<String>,
+    pub(crate) audience: Option<String>,
+    pub(crate) max_age: Duration,
+}
+
+/// The keys last fetched from the JWKS url of a [`PushVerifier`].
+#[derive(Debug, Default)]
+pub(crate) struct FetchedKeys {
+    pub(crate) keys: Vec<Jwk>,
+    pub(crate) fetched_at: Option<Instant>,
+}
+
+/// A push notification that passed [`PushVerifier::verify`](crate::push::PushVerifier).
+#[derive(Debug, Clone)]
+pub struct VerifiedPushNotification {
+    pub claims: PushClaims,
+    pub task: crate::core::task::Task,
+}
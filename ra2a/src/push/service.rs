@@ -0,0 +1,435 @@
+use crate::core::push_notification::PushNotificationConfig;
+use crate::core::task::Task;
+use crate::egress::{EgressClient, EgressError, UrlPolicy};
+use crate::jws::{Algorithm, Jwk, JwkSet, SigningKey};
+use crate::push::{
+    FetchedKeys, KeyRing, PushClaims, PushError, PushKeys, PushNotifier, PushVerifier,
+    VerifiedPushNotification,
+};
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use http::HeaderMap;
+use http::header::{AUTHORIZATION, CONTENT_TYPE};
+use ring::digest::{SHA256, digest};
+use serde::Deserialize;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+/// Header carrying the signed JWT of a push notification.
+pub const PUSH_SIGNATURE_HEADER: &str = "X-A2A-Notification-Signature";
+
+/// Header carrying the token of the push notification config.
+pub const PUSH_TOKEN_HEADER: &str = "X-A2A-Notification-Token";
+
+/// Path the json-rpc server publishes the push notification keys at.
+pub const PUSH_JWKS_PATH: &str = "/.well-known/jwks.json";
+
+/// `typ` of the protected header of push notification signatures.
+pub const PUSH_JWS_TYPE: &str = "JWT";
+
+/// Default time rotated out keys stay published.
+const DEFAULT_RETENTION: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Default maximum age of the notifications a [`PushVerifier`] accepts.
+const DEFAULT_MAX_AGE: Duration = Duration::from_secs(5 * 60);
+
+/// How far in the future notifications may be issued, for clock skew.
+const CLOCK_LEEWAY: u64 = 60;
+
+/// Default minimum time between two fetches of the JWKS of a verifier.
+const DEFAULT_JWKS_REFETCH_INTERVAL: Duration = Duration::from_secs(30);
+
+impl PushKeys {
+    /// Signs with `key`, given a random key id when it has none.
+    pub fn new(key: SigningKey) -> Self {
+        Self {
+            ring: Arc::new(RwLock::new(KeyRing {
+                current: with_key_id(key),
+                created: Instant::now(),
+                retired: vec![],
+            })),
+            rotation: None,
+            retention: DEFAULT_RETENTION,
+        }
+    }
+
+    /// Signs with a newly generated key.
+    pub fn generate(algorithm: Algorithm) -> Result<Self, PushError> {
+        Ok(Self::new(SigningKey::generate(algorithm)?))
+    }
+
+    /// Replaces the current key with a generated one of the same algorithm once it is
+    /// older than `interval`.
+    pub fn with_rotation(mut self, interval: Duration) -> Self {
+        self.rotation = Some(interval);
+        self
+    }
+
+    /// How long rotated out keys stay published, a day by default. It should exceed the
+    /// time notifications take to be delivered and verified.
+    pub fn with_retention(mut self, retention: Duration) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    /// Makes `key` the current key.
+    pub fn rotate(&self, key: SigningKey) {
+        let mut ring = self.ring.write().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        let previous = std::mem::replace(&mut ring.current, with_key_id(key));
+        ring.retired.push((previous, now));
+        ring.created = now;
+        let retention = self.retention;
+        ring.retired
+            .retain(|(_, retired)| now.saturating_duration_since(*retired) < retention);
+    }
+
+    /// Key notifications are signed with, rotated first when it is due.
+    pub fn current(&self) -> SigningKey {
+        let (current, due) = {
+            let ring = self.ring.read().unwrap_or_else(|e| e.into_inner());
+            let due = self
+                .rotation
+                .is_some_and(|interval| ring.created.elapsed() >= interval);
+            (ring.current.clone(), due)
+        };
+        if !due {
+            return current;
+        }
+        match SigningKey::generate(current.algorithm()) {
+            Ok(key) => {
+                self.rotate(key);
+                self.current()
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, "failed to rotate push notification key");
+                current
+            }
+        }
+    }
+
+    /// Public keys of the current and recently rotated out keys, as published at
+    /// [`PUSH_JWKS_PATH`].
+    pub fn jwks(&self) -> JwkSet {
+        let ring = self.ring.read().unwrap_or_else(|e| e.into_inner());
+        let retired = ring
+            .retired
+            .iter()
+            .filter(|(_, retired)| retired.elapsed() < self.retention)
+            .map(|(key, _)| key.public_jwk());
+        JwkSet {
+            keys: std::iter::once(ring.current.public_jwk())
+                .chain(retired)
+                .collect(),
+        }
+    }
+}
+
+fn with_key_id(key: SigningKey) -> SigningKey {
+    match key.key_id {
+        Some(_) => key,
+        None => key.with_key_id(Uuid::new_v4().to_string()),
+    }
+}
+
+impl PushNotifier {
+    /// Delivers notifications to the urls `policy` allows.
+    pub fn new(keys: PushKeys, policy: UrlPolicy) -> Result<Self, PushError> {
+        Ok(Self {
+            client: EgressClient::new(policy)?,
+            keys,
+            issuer: None,
+        })
+    }
+
+    /// Sets the `iss` claim, usually the url of the agent.
+    pub fn with_issuer(mut self, issuer: impl Into<String>) -> Self {
+        self.issuer = Some(issuer.into());
+        self
+    }
+
+    pub fn keys(&self) -> &PushKeys {
+        &self.keys
+    }
+
+    /// Checks the url of a push notification config against the policy, before any
+    /// notification is sent to it.
+    pub fn check(&self, config: &PushNotificationConfig) -> Result<(), PushError> {
+        self.client.policy().check_url(&config.url)?;
+        Ok(())
+    }
+
+    /// Sends the current state of `task` to the url of `config`.
+    pub async fn notify(
+        &self,
+        config: &PushNotificationConfig,
+        task: &Task,
+    ) -> Result<(), PushError> {
+        let body = serde_json::to_vec(task)?;
+        let claims = PushClaims {
+            iss: self.issuer.clone(),
+            aud: config.url.clone(),
+            iat: now(),
+            jti: Uuid::new_v4().to_string(),
+            task_id: task.id.clone(),
+            token: config.token.clone(),
+            body_sha256: body_digest(&body),
+        };
+        let signature = self
+            .keys
+            .current()
+            .sign_compact(PUSH_JWS_TYPE, &serde_json::to_vec(&claims)?)?;
+        let mut request = self
+            .client
+            .post(&config.url)?
+            .header(CONTENT_TYPE, "application/json")
+            .header(PUSH_SIGNATURE_HEADER, signature);
+        if !config.token.is_empty() {
+            request = request.header(PUSH_TOKEN_HEADER, &config.token);
+        }
+        if let Some(authorization) = authorization(config) {
+            request = request.header(AUTHORIZATION, authorization);
+        }
+        let res = request
+            .body(body)
+            .send()
+            .await
+            .map_err(|source| EgressError::Request {
+                url: config.url.clone(),
+                source,
+            })?;
+        if !res.status().is_success() {
+            return Err(EgressError::Status {
+                url: config.url.clone(),
+                status: res.status(),
+            }
+            .into());
+        }
+        tracing::debug!(
+            task_id = task.id,
+            url = config.url,
+            "push notification sent"
+        );
+        Ok(())
+    }
+}
+
+/// `Authorization` header for the credentials of the config, in the first scheme they
+/// can be sent with.
+fn authorization(config: &PushNotificationConfig) -> Option<String> {
+    let authentication = config.authentication.as_ref()?;
+    if authentication.credentials.is_empty() {
+        return None;
+    }
+    authentication
+        .schemes
+        .iter()
+        .find_map(|scheme| match scheme.to_ascii_lowercase().as_str() {
+            "bearer" => Some(format!("Bearer {}", authentication.credentials)),
+            "basic" => Some(format!("Basic {}", authentication.credentials)),
+            _ => None,
+        })
+}
+
+/// Protected header of a push notification signature.
+#[derive(Deserialize)]
+struct Header {
+    alg: String,
+    #[serde(default)]
+    kid: Option<String>,
+}
+
+impl Default for PushVerifier {
+    fn default() -> Self {
+        Self {
+            pinned: vec![],
+            fetched: Arc::new(RwLock::new(FetchedKeys::default())),
+            jwks_url: None,
+            refetch_interval: DEFAULT_JWKS_REFETCH_INTERVAL,
+            issuer: None,
+            audience: None,
+            max_age: DEFAULT_MAX_AGE,
+        }
+    }
+}
+
+impl PushVerifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Trusts the public key, pinning it for the agent.
+    pub fn with_key(mut self, key: Jwk) -> Self {
+        self.pinned.push(key);
+        self
+    }
+
+    /// Trusts every key of the set.
+    pub fn with_jwks(mut self, jwks: JwkSet) -> Self {
+        self.pinned.extend(jwks.keys);
+        self
+    }
+
+    /// Trusts the keys published at `url`, fetched when a notification is signed with a
+    /// key not known yet.
+    pub fn with_jwks_url(mut self, url: impl Into<String>) -> Self {
+        self.jwks_url = Some(url.into());
+        self
+    }
+
+    /// Minimum time between two fetches of the JWKS url, 30 seconds by default, so that
+    /// notifications naming unknown keys cannot make the verifier fetch it for each one.
+    pub fn with_jwks_refetch_interval(mut self, interval: Duration) -> Self {
+        self.refetch_interval = interval;
+        self
+    }
+
+    /// Requires the `iss` claim of notifications to be `issuer`.
+    pub fn with_issuer(mut self, issuer: impl Into<String>) -> Self {
+        self.issuer = Some(issuer.into());
+        self
+    }
+
+    /// Requires notifications to be addressed to `audience`, the url of the webhook.
+    pub fn with_audience(mut self, audience: impl Into<String>) -> Self {
+        self.audience = Some(audience.into());
+        self
+    }
+
+    /// Maximum age of accepted notifications, 5 minutes by default.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Verifies a received notification, given its headers and raw body. When `token`
+    /// is given, the notification must carry the token of the push notification config
+    /// it was sent for.
+    pub async fn verify(
+        &self,
+        headers: &HeaderMap,
+        body: &[u8],
+        token: Option<&str>,
+    ) -> Result<VerifiedPushNotification, PushError> {
+        let jwt = headers
+            .get(PUSH_SIGNATURE_HEADER)
+            .ok_or(PushError::MissingSignature)?
+            .to_str()
+            .map_err(|_| PushError::MalformedSignature)?;
+        let claims = self.verify_signature(jwt).await?;
+
+        let now = now();
+        if claims.iat > now + CLOCK_LEEWAY
+            || claims.iat.saturating_add(self.max_age.as_secs()) < now
+        {
+            return Err(PushError::Stale {
+                issued_at: claims.iat,
+            });
+        }
+        if self
+            .issuer
+            .as_ref()
+            .is_some_and(|iss| claims.iss.as_ref() != Some(iss))
+        {
+            return Err(PushError::ClaimMismatch("iss"));
+        }
+        if self.audience.as_ref().is_some_and(|aud| &claims.aud != aud) {
+            return Err(PushError::ClaimMismatch("aud"));
+        }
+        if let Some(token) = token {
+            let header = headers.get(PUSH_TOKEN_HEADER).map(|v| v.as_bytes());
+            if claims.token != token || header.is_some_and(|v| v != token.as_bytes()) {
+                return Err(PushError::TokenMismatch);
+            }
+        }
+        if body_digest(body) != claims.body_sha256 {
+            return Err(PushError::BodyMismatch);
+        }
+        let task: Task = serde_json::from_slice(body)?;
+        if task.id != claims.task_id {
+            return Err(PushError::TaskMismatch(claims.task_id));
+        }
+        Ok(VerifiedPushNotification { claims, task })
+    }
+
+    async fn verify_signature(&self, jwt: &str) -> Result<PushClaims, PushError> {
+        let mut parts = jwt.split('.');
+        let (Some(header), Some(payload), Some(signature), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(PushError::MalformedSignature);
+        };
+        let decode = |part: &str| {
+            URL_SAFE_NO_PAD
+                .decode(part)
+                .map_err(|_| PushError::MalformedSignature)
+        };
+        let header: Header =
+            serde_json::from_slice(&decode(header)?).map_err(|_| PushError::MalformedSignature)?;
+        let algorithm = Algorithm::parse(&header.alg).ok_or(PushError::InvalidSignature)?;
+        let input = &jwt[..jwt.len() - signature.len() - 1];
+        let signature = decode(signature)?;
+
+        if !self.has_key(header.kid.as_deref())
+            && let Some(url) = &self.jwks_url
+            && self.claim_refetch()
+        {
+            let jwks = JwkSet::fetch(url).await?;
+            self.fetched.write().unwrap_or_else(|e| e.into_inner()).keys = jwks.keys;
+        }
+        let fetched = self.fetched.read().unwrap_or_else(|e| e.into_inner());
+        let verified = self
+            .pinned
+            .iter()
+            .chain(&fetched.keys)
+            .filter(|key| key_matches(key, header.kid.as_deref()))
+            .filter(|key| key.alg.is_none_or(|alg| alg == algorithm))
+            .any(|key| crate::jws::verify(key, algorithm, input.as_bytes(), &signature));
+        drop(fetched);
+        if !verified {
+            return Err(PushError::InvalidSignature);
+        }
+        serde_json::from_slice(&decode(payload)?).map_err(|_| PushError::MalformedSignature)
+    }
+
+    fn has_key(&self, kid: Option<&str>) -> bool {
+        let fetched = self.fetched.read().unwrap_or_else(|e| e.into_inner());
+        self.pinned
+            .iter()
+            .chain(&fetched.keys)
+            .any(|key| key_matches(key, kid))
+    }
+
+    /// Whether the JWKS may be fetched now, recording the fetch if so.
+    fn claim_refetch(&self) -> bool {
+        let mut fetched = self.fetched.write().unwrap_or_else(|e| e.into_inner());
+        if fetched
+            .fetched_at
+            .is_some_and(|at| at.elapsed() < self.refetch_interval)
+        {
+            tracing::debug!("skipping JWKS refetch, fetched recently");
+            return false;
+        }
+        fetched.fetched_at = Some(Instant::now());
+        true
+    }
+}
+
+fn key_matches(key: &Jwk, kid: Option<&str>) -> bool {
+    match (kid, &key.kid) {
+        (Some(wanted), Some(kid)) => wanted == kid,
+        _ => true,
+    }
+}
+
+fn body_digest(body: &[u8]) -> String {
+    URL_SAFE_NO_PAD.encode(digest(&SHA256, body))
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
@@ -0,0 +1,226 @@
+use crate::agent::RemoteAddr;
+use crate::auth::PeerCertificates;
+use crate::server::A2AServerError;
+use crate::tls::ServerTlsConfig;
+use bytes::Bytes;
+use hyper::body::{Body, Incoming};
+use hyper_util::rt::{TokioExecutor, TokioIo, TokioTimer};
+use hyper_util::server::conn::auto;
+use hyper_util::service::TowerToHyperService;
+use jsonrpsee::server::{StopHandle, stop_channel};
+use socket2::{SockRef, TcpKeepalive};
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Notify;
+use tokio_rustls::TlsAcceptor;
+use tower::BoxError;
+
+/// How long a connection may take to complete its TLS handshake, and then to send its
+/// first request.
+const FIRST_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Pause after a failed accept, e.g. when out of file descriptors, before accepting again.
+const ACCEPT_ERROR_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Serves a connection over HTTP/1 or HTTP/2, whichever the client speaks.
+pub(crate) type ConnectionBuilder = auto::Builder<TokioExecutor>;
+
+/// A [`ConnectionBuilder`] with the defaults of hyper, its timeouts driven by tokio.
+pub(crate) fn connection_builder() -> ConnectionBuilder {
+    let mut builder = ConnectionBuilder::new(TokioExecutor::new());
+    builder.http1().timer(TokioTimer::new());
+    builder.http2().timer(TokioTimer::new());
+    builder
+}
+
+/// A plain or TLS client connection.
+trait Connection: AsyncRead + AsyncWrite + Send + Unpin {}
+
+impl<T: AsyncRead + AsyncWrite + Send + Unpin> Connection for T {}
+
+/// Accepts the connections of a server, over TCP or a Unix domain socket.
+pub(crate) trait Listener {
+    type Io: AsyncRead + AsyncWrite + Send + Unpin + 'static;
+
+    /// Accepts a connection, along with the address of the peer when it has one.
+    fn accept(&self) -> impl Future<Output = io::Result<(Self::Io, Option<SocketAddr>)>>;
+}
+
+impl Listener for TcpListener {
+    type Io = TcpStream;
+
+    async fn accept(&self) -> io::Result<(TcpStream, Option<SocketAddr>)> {
+        let (stream, remote_addr) = TcpListener::accept(self).await?;
+        Ok((stream, Some(remote_addr)))
+    }
+}
+
+#[cfg(unix)]
+impl Listener for tokio::net::UnixListener {
+    type Io = tokio::net::UnixStream;
+
+    async fn accept(&self) -> io::Result<(Self::Io, Option<SocketAddr>)> {
+        let (stream, _) = tokio::net::UnixListener::accept(self).await?;
+        Ok((stream, None))
+    }
+}
+
+/// A TCP listener enabling keepalive probes on the connections it accepts.
+pub(crate) struct KeepaliveListener {
+    listener: TcpListener,
+    keepalive: Option<TcpKeepalive>,
+}
+
+impl KeepaliveListener {
+    /// Probes idle connections every `keepalive`, none are sent when `None`.
+    pub(crate) fn new(listener: TcpListener, keepalive: Option<Duration>) -> Self {
+        Self {
+            listener,
+            keepalive: keepalive.map(|time| TcpKeepalive::new().with_time(time)),
+        }
+    }
+}
+
+impl Listener for KeepaliveListener {
+    type Io = TcpStream;
+
+    async fn accept(&self) -> io::Result<(TcpStream, Option<SocketAddr>)> {
+        let (stream, remote_addr) = self.listener.accept().await?;
+        if let Some(keepalive) = &self.keepalive
+            && let Err(e) = SockRef::from(&stream).set_tcp_keepalive(keepalive)
+        {
+            tracing::debug!(error = %e, "failed to enable tcp keepalive");
+        }
+        Ok((stream, Some(remote_addr)))
+    }
+}
+
+/// Accepts connections until `signal` resolves and serves each with `builder` and the
+/// service `make_service` builds, terminating TLS first when configured. The peer
+/// address, and the client certificate chain under mutual TLS, are added to every request
+/// of the connection.
+pub(crate) async fn serve_connections<L, F, M, S, B>(
+    listener: L,
+    tls: Option<&ServerTlsConfig>,
+    builder: ConnectionBuilder,
+    signal: F,
+    make_service: M,
+) -> Result<(), A2AServerError>
+where
+    L: Listener,
+    F: Future<Output = ()>,
+    M: Fn(StopHandle) -> S,
+    S: tower::Service<http::Request<Incoming>, Response = http::Response<B>>
+        + Clone
+        + Send
+        + 'static,
+    S::Future: Send,
+    S::Error: Into<BoxError>,
+    B: Body<Data = Bytes> + Send + 'static,
+    B::Error: Into<BoxError>,
+{
+    let acceptor = match tls {
+        Some(tls) => Some(TlsAcceptor::from(Arc::new(tls.rustls_config()?))),
+        None => None,
+    };
+    let (stop_handle, server_handle) = stop_channel();
+    tokio::pin!(signal);
+    loop {
+        let accepted = tokio::select! {
+            accepted = Listener::accept(&listener) => accepted,
+            _ = &mut signal => break,
+        };
+        let (stream, remote_addr) = match accepted {
+            Ok(accepted) => accepted,
+            Err(e) => {
+                tracing::warn!(error = %e, "failed to accept connection");
+                tokio::select! {
+                    _ = tokio::time::sleep(ACCEPT_ERROR_BACKOFF) => continue,
+                    _ = &mut signal => break,
+                }
+            }
+        };
+        let acceptor = acceptor.clone();
+        let builder = builder.clone();
+        let service = make_service(stop_handle.clone());
+        let stopped = stop_handle.clone().shutdown();
+        tokio::spawn(async move {
+            let mut extensions = http::Extensions::new();
+            if let Some(remote_addr) = remote_addr {
+                extensions.insert(RemoteAddr(remote_addr));
+            }
+            let io: Box<dyn Connection> = match acceptor {
+                Some(acceptor) => {
+                    let handshake =
+                        tokio::time::timeout(FIRST_REQUEST_TIMEOUT, acceptor.accept(stream));
+                    let stream = match handshake.await {
+                        Ok(Ok(stream)) => stream,
+                        Ok(Err(e)) => {
+                            tracing::debug!(error = %e, "tls handshake failed");
+                            return;
+                        }
+                        Err(_) => {
+                            tracing::debug!("tls handshake timed out");
+                            return;
+                        }
+                    };
+                    extensions.insert(PeerCertificates(
+                        stream
+                            .get_ref()
+                            .1
+                            .peer_certificates()
+                            .unwrap_or_default()
+                            .iter()
+                            .map(|c| c.to_vec())
+                            .collect(),
+                    ));
+                    Box::new(stream)
+                }
+                None => Box::new(stream),
+            };
+            let request_received = Arc::new(Notify::new());
+            let received = request_received.clone();
+            let service = tower::ServiceBuilder::new()
+                .map_request(move |mut request: http::Request<Incoming>| {
+                    received.notify_one();
+                    request.extensions_mut().extend(extensions.clone());
+                    request
+                })
+                .service(service);
+            let conn = builder.serve_connection_with_upgrades(
+                TokioIo::new(io),
+                TowerToHyperService::new(service),
+            );
+            tokio::pin!(conn, stopped);
+            let served = tokio::select! {
+                served = &mut conn => served,
+                _ = &mut stopped => {
+                    conn.as_mut().graceful_shutdown();
+                    conn.await
+                }
+                _ = no_request_within(&request_received) => {
+                    Err("no request received in time".into())
+                }
+            };
+            if let Err(e) = served {
+                tracing::debug!(error = %e, "connection closed with error");
+            }
+        });
+    }
+    let _ = server_handle.stop();
+    Ok(())
+}
+
+/// Resolves when no request arrives within [`FIRST_REQUEST_TIMEOUT`], never otherwise.
+async fn no_request_within(request_received: &Notify) {
+    if tokio::time::timeout(FIRST_REQUEST_TIMEOUT, request_received.notified())
+        .await
+        .is_ok()
+    {
+        std::future::pending::<()>().await;
+    }
+}
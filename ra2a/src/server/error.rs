@@ -13,6 +13,9 @@ pub enum A2AServerError {
     #[error("Register method")]
     RegisterMethod(#[from] RegisterMethodError),
 
+    #[error("Tls")]
+    Tls(#[from] crate::tls::TlsError),
+
     #[error("Task join error")]
     Join(#[from] tokio::task::JoinError),
 }
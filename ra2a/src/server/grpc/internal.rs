@@ -1,36 +1,65 @@
+use crate::core::agent::{AgentCard, GetAgentCardRequest};
 use crate::core::message::{SendMessageRequest, SendMessageResponse};
 use crate::core::{
-    A2A, A2AError, A2AProtocolError, GRPC_GET_TASK_PATH, GRPC_SEND_MESSAGE_PATH, GRPC_SERVICE_NAME,
+    A2AError, A2AProtocolError, GRPC_GET_AGENT_CARD_PATH, GRPC_GET_TASK_PATH,
+    GRPC_SEND_MESSAGE_PATH, GRPC_SERVICE_NAME,
 };
 use http::{Request as HttpRequest, Response as HttpResponse};
 use std::{
     convert::Infallible,
     future::Future,
+    net::SocketAddr,
     pin::Pin,
     task::{Context, Poll},
 };
 
-use crate::agent::A2ADelegate;
+use crate::agent::{A2ADelegate, RemoteAddr, RequestContext};
+use crate::auth::{AuthError, PeerCertificates};
 use crate::core::task::{GetTaskGrpcRequest, Task};
+use crate::grpc::GrpcConfig;
+use bytes::Bytes;
 use tonic::body::Body;
-use tonic::codec::CompressionEncoding;
 use tonic::codegen::Service;
+use tonic::transport::server::{TcpConnectInfo, TlsConnectInfo};
 use tonic::{
-    Request, Response, Status,
-    server::{Grpc, NamedService, UnaryService},
+    Code, Request, Response, Status,
+    server::{NamedService, UnaryService},
 };
 use tonic_prost::ProstCodec;
+use tower::BoxError;
 
+/// The gRPC transport as a tower service, to add to a tonic server or router of the caller
+/// next to other services and layers.
 #[derive(Debug, Clone)]
 pub struct A2AGrpc {
     pub(crate) delegate: A2ADelegate,
+    pub(crate) config: GrpcConfig,
+}
+
+impl A2AGrpc {
+    /// Serves the calls of `delegate`, compressing responses with gzip by default.
+    pub fn new(delegate: A2ADelegate) -> Self {
+        Self {
+            delegate,
+            config: GrpcConfig::default(),
+        }
+    }
+
+    pub fn with_config(mut self, config: GrpcConfig) -> Self {
+        self.config = config;
+        self
+    }
 }
 
 impl NamedService for A2AGrpc {
     const NAME: &'static str = GRPC_SERVICE_NAME;
 }
 
-impl Service<HttpRequest<Body>> for A2AGrpc {
+impl<B> Service<HttpRequest<B>> for A2AGrpc
+where
+    B: tonic::codegen::Body<Data = Bytes> + Send + 'static,
+    B::Error: Into<BoxError>,
+{
     type Response = HttpResponse<Body>;
     type Error = Infallible;
     type Future = BoxFut<Result<Self::Response, Self::Error>>;
@@ -39,32 +68,53 @@ impl Service<HttpRequest<Body>> for A2AGrpc {
         Poll::Ready(Ok(()))
     }
 
-    fn call(&mut self, req: HttpRequest<Body>) -> Self::Future {
+    fn call(&mut self, req: HttpRequest<B>) -> Self::Future {
         let delegate = self.delegate.clone();
+        let config = self.config.clone();
         Box::pin(async move {
+            // authenticate on the head only, the body is not shareable across the await
+            let (mut parts, body) = req.into_parts();
+            if let Some(certificates) = peer_certificates(&parts.extensions) {
+                parts.extensions.insert(certificates);
+            }
+            if let Some(addr) = remote_addr(&parts.extensions) {
+                parts.extensions.insert(RemoteAddr(addr));
+            }
+            let head = HttpRequest::from_parts(parts, ());
+            let context = match delegate.request_context(&head).await {
+                Ok(context) => context,
+                Err(e) => return Ok(auth_status(e).into_http()),
+            };
+            if let Err(e) = delegate.check_rate_limits(&context).await {
+                return Ok(status(e).into_http());
+            }
+            let (mut parts, ()) = head.into_parts();
+            parts.extensions.insert(context);
+            let req = HttpRequest::from_parts(parts, Body::new(body));
             match req.uri().path() {
                 GRPC_SEND_MESSAGE_PATH => {
-                    let mut grpc =
-                        Grpc::new(ProstCodec::<SendMessageResponse, SendMessageRequest>::default())
-                            .accept_compressed(CompressionEncoding::Gzip)
-                            .send_compressed(CompressionEncoding::Gzip)
-                            .max_decoding_message_size(4 * 1024 * 1024)
-                            .max_encoding_message_size(4 * 1024 * 1024);
+                    let mut grpc = config
+                        .server_codec(
+                            ProstCodec::<SendMessageResponse, SendMessageRequest>::default(),
+                        );
                     let svc = SendMessage { delegate };
                     let res = grpc.unary(svc, req).await;
                     Ok(res)
                 }
                 GRPC_GET_TASK_PATH => {
-                    // todo clean up this and expose tuning
-                    let mut grpc = Grpc::new(ProstCodec::<Task, GetTaskGrpcRequest>::default())
-                        .accept_compressed(CompressionEncoding::Gzip)
-                        .send_compressed(CompressionEncoding::Gzip)
-                        .max_decoding_message_size(4 * 1024 * 1024)
-                        .max_encoding_message_size(4 * 1024 * 1024);
+                    let mut grpc =
+                        config.server_codec(ProstCodec::<Task, GetTaskGrpcRequest>::default());
                     let svc = GetTask { delegate };
                     let res = grpc.unary(svc, req).await;
                     Ok(res)
                 }
+                GRPC_GET_AGENT_CARD_PATH => {
+                    let mut grpc = config
+                        .server_codec(ProstCodec::<AgentCard, GetAgentCardRequest>::default());
+                    let svc = GetAgentCard { delegate };
+                    let res = grpc.unary(svc, req).await;
+                    Ok(res)
+                }
                 _ => Ok(Status::unimplemented("unknown method").into_http()),
             }
         })
@@ -83,13 +133,18 @@ impl UnaryService<SendMessageRequest> for SendMessage {
     type Future = BoxFut<Result<Response<Self::Response>, Status>>;
 
     fn call(&mut self, request: Request<SendMessageRequest>) -> Self::Future {
+        let context = request
+            .extensions()
+            .get::<RequestContext>()
+            .cloned()
+            .unwrap_or_default();
         let req = request.into_inner();
         let delegate = self.delegate.clone();
         Box::pin(async move {
-            let res = delegate.send_message(req).await;
+            let res = delegate.send_message_with_context(&context, req).await;
             match res {
                 Ok(response) => Ok(Response::new(response)),
-                Err(e) => Err(Status::internal(e.to_string())),
+                Err(e) => Err(status(e)),
             }
         })
     }
@@ -105,19 +160,105 @@ impl UnaryService<GetTaskGrpcRequest> for GetTask {
     type Future = BoxFut<Result<Response<Self::Response>, Status>>;
 
     fn call(&mut self, request: Request<GetTaskGrpcRequest>) -> Self::Future {
+        let context = request
+            .extensions()
+            .get::<RequestContext>()
+            .cloned()
+            .unwrap_or_default();
         let req = request.into_inner();
         let delegate = self.delegate.clone();
         Box::pin(async move {
-            let res = delegate.get_task(req.into()).await;
+            let res = delegate.get_task_with_context(&context, req.into()).await;
             match res {
                 Ok(response) => Ok(Response::new(response)),
-                Err(A2AError::Protocol(A2AProtocolError::TaskNotFound { id, code })) => {
-                    let mut status = Status::not_found(format!("Task not found: {}", id));
-                    status.metadata_mut().insert("code", code.into());
-                    Err(status)
-                }
-                Err(e) => Err(Status::internal(e.to_string())),
+                Err(e) => Err(status(e)),
             }
         })
     }
 }
+
+/// Answers with the authenticated extended card.
+#[derive(Debug, Clone)]
+pub struct GetAgentCard {
+    delegate: A2ADelegate,
+}
+
+impl UnaryService<GetAgentCardRequest> for GetAgentCard {
+    type Response = AgentCard;
+    type Future = BoxFut<Result<Response<Self::Response>, Status>>;
+
+    fn call(&mut self, request: Request<GetAgentCardRequest>) -> Self::Future {
+        let context = request
+            .extensions()
+            .get::<RequestContext>()
+            .cloned()
+            .unwrap_or_default();
+        let delegate = self.delegate.clone();
+        Box::pin(async move {
+            delegate
+                .get_authenticated_extended_card_with_context(&context)
+                .await
+                .map(Response::new)
+                .map_err(status)
+        })
+    }
+}
+
+/// Client certificate chain of a mutual TLS connection, as recorded by tonic.
+fn peer_certificates(extensions: &http::Extensions) -> Option<PeerCertificates> {
+    let info = extensions.get::<TlsConnectInfo<TcpConnectInfo>>()?;
+    let certificates = info.peer_certs()?;
+    Some(PeerCertificates(
+        certificates.iter().map(|c| c.to_vec()).collect(),
+    ))
+}
+
+/// Address of the peer, as recorded by tonic with or without TLS.
+fn remote_addr(extensions: &http::Extensions) -> Option<SocketAddr> {
+    match extensions.get::<TlsConnectInfo<TcpConnectInfo>>() {
+        Some(info) => info.get_ref().remote_addr(),
+        None => extensions.get::<TcpConnectInfo>()?.remote_addr(),
+    }
+}
+
+fn auth_status(e: AuthError) -> Status {
+    match e {
+        AuthError::InsufficientScope { .. } | AuthError::UnknownSkill(_) => {
+            Status::permission_denied(e.to_string())
+        }
+        _ => Status::unauthenticated(e.to_string()),
+    }
+}
+
+/// Maps an error to a status. Protocol errors carry their A2A code in the `code` metadata
+/// and their structured data as JSON in the status details.
+fn status(e: A2AError) -> Status {
+    let e = match e {
+        A2AError::Protocol(e) => e,
+        A2AError::Auth(e) => return auth_status(e),
+        A2AError::Push(e) => return Status::invalid_argument(e.to_string()),
+        e => return Status::internal(e.to_string()),
+    };
+    let code = match &e {
+        A2AProtocolError::TaskNotFound { .. } => Code::NotFound,
+        A2AProtocolError::PushNotificationNotSupported { .. }
+        | A2AProtocolError::UnsupportedOperation { .. }
+        | A2AProtocolError::AuthenticatedExtendedCardNotConfigured { .. } => Code::Unimplemented,
+        A2AProtocolError::ContentTypeNotSupported { .. }
+        | A2AProtocolError::InvalidSkill { .. } => Code::InvalidArgument,
+        A2AProtocolError::InvalidAgentResponse { .. } => Code::Internal,
+        A2AProtocolError::TaskNotCancelable { .. }
+        | A2AProtocolError::DelegationLoop { .. }
+        | A2AProtocolError::DelegationDepthExceeded { .. } => Code::FailedPrecondition,
+        A2AProtocolError::RateLimitExceeded { .. } | A2AProtocolError::QuotaExceeded { .. } => {
+            Code::ResourceExhausted
+        }
+    };
+    let details = e
+        .data()
+        .map(|data| data.to_string().into())
+        .unwrap_or_default();
+    let mut status = Status::with_details(code, e.to_string(), details);
+    status.metadata_mut().insert("code", e.code().into());
+    status
+}
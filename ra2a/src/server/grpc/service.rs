@@ -1,6 +1,8 @@
 use crate::agent::A2ADelegate;
+use crate::grpc::GrpcConfig;
 use crate::server::A2AServerError;
 use crate::server::grpc::A2AGrpc;
+use crate::tls::ServerTlsConfig;
 use std::net::SocketAddr;
 use tokio::net::TcpListener;
 use tonic::codegen::tokio_stream::wrappers::TcpListenerStream;
@@ -10,6 +12,8 @@ use tonic::transport::Server;
 pub struct A2AGrpcServer {
     bind_addr: SocketAddr,
     delegate: A2ADelegate,
+    tls: Option<ServerTlsConfig>,
+    config: GrpcConfig,
 }
 
 impl A2AGrpcServer {
@@ -17,9 +21,22 @@ impl A2AGrpcServer {
         Self {
             bind_addr,
             delegate,
+            tls: None,
+            config: GrpcConfig::default(),
         }
     }
 
+    pub fn with_tls(mut self, tls: ServerTlsConfig) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+
+    /// Replaces the default config, which compresses responses with gzip.
+    pub fn with_config(mut self, config: GrpcConfig) -> Self {
+        self.config = config;
+        self
+    }
+
     pub async fn bind(&self) -> Result<TcpListener, A2AServerError> {
         TcpListener::bind(self.bind_addr)
             .await
@@ -31,10 +48,12 @@ impl A2AGrpcServer {
         signal: F,
         listener: TcpListener,
     ) -> Result<(), A2AServerError> {
-        Server::builder()
-            .add_service(A2AGrpc {
-                delegate: self.delegate.clone(),
-            })
+        let mut server = self.config.server(Server::builder());
+        if let Some(tls) = &self.tls {
+            server = server.tls_config(tls.grpc_config())?;
+        }
+        server
+            .add_service(A2AGrpc::new(self.delegate.clone()).with_config(self.config.clone()))
             .serve_with_incoming_shutdown(TcpListenerStream::new(listener), signal)
             .await?;
         Ok(())
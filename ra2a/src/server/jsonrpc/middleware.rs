@@ -0,0 +1,329 @@
+use crate::agent::{A2ADelegate, RequestContext};
+use crate::auth::AuthError;
+use crate::core::{
+    A2AError, A2AProtocolError, REST_GET_AUTHENTICATED_EXTENDED_CARD_PATH,
+    WELL_KNOWN_AGENT_CARD_PATH,
+};
+use crate::push::PUSH_JWKS_PATH;
+use futures::future::BoxFuture;
+use http::header::{CONTENT_TYPE, RETRY_AFTER, WWW_AUTHENTICATE};
+use http::{Method, StatusCode};
+use jsonrpsee::server::{HttpBody, HttpRequest, HttpResponse};
+use serde_json::{Value, json};
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+
+/// Http middleware that authenticates requests before they reach the json-rpc methods.
+/// Authenticated requests carry their [`crate::agent::RequestContext`] in the request
+/// extensions, rejected ones are answered with 401, or 403 for missing scopes. Callers
+/// over their rate limits are answered with 429.
+#[derive(Debug, Clone)]
+pub struct AuthLayer {
+    delegate: A2ADelegate,
+}
+
+#[derive(Debug, Clone)]
+pub struct AuthService<S> {
+    delegate: A2ADelegate,
+    inner: S,
+}
+
+/// Http middleware answering `GET /v1/card` with the authenticated extended card of the
+/// agent, next to the json-rpc methods. Sits behind [`AuthLayer`], which provides the
+/// request context.
+#[derive(Debug, Clone)]
+pub struct ExtendedCardLayer {
+    delegate: A2ADelegate,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExtendedCardService<S> {
+    delegate: A2ADelegate,
+    inner: S,
+}
+
+/// Http middleware publishing the push notification keys of the agent at
+/// `/.well-known/jwks.json`. Sits in front of [`AuthLayer`], webhook receivers fetch the
+/// keys without credentials.
+#[derive(Debug, Clone)]
+pub struct JwksLayer {
+    delegate: A2ADelegate,
+}
+
+#[derive(Debug, Clone)]
+pub struct JwksService<S> {
+    delegate: A2ADelegate,
+    inner: S,
+}
+
+/// Http middleware publishing the public card of the agent at
+/// `/.well-known/agent-card.json`. Sits in front of [`AuthLayer`], clients fetch the card
+/// to learn how to authenticate.
+#[derive(Debug, Clone)]
+pub struct AgentCardLayer {
+    delegate: A2ADelegate,
+}
+
+#[derive(Debug, Clone)]
+pub struct AgentCardService<S> {
+    delegate: A2ADelegate,
+    inner: S,
+}
+
+impl AuthLayer {
+    pub fn new(delegate: A2ADelegate) -> Self {
+        Self { delegate }
+    }
+}
+
+impl<S> Layer<S> for AuthLayer {
+    type Service = AuthService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AuthService {
+            delegate: self.delegate.clone(),
+            inner,
+        }
+    }
+}
+
+impl<S> Service<HttpRequest> for AuthService<S>
+where
+    S: Service<HttpRequest, Response = HttpResponse> + Clone + Send + 'static,
+    S::Future: Send,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: HttpRequest) -> Self::Future {
+        // the clone may not be ready, keep the service that was polled
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let delegate = self.delegate.clone();
+        Box::pin(async move {
+            // authenticate on the head only, the body is not shareable across the await
+            let (parts, body) = request.into_parts();
+            let head = http::Request::from_parts(parts, ());
+            match delegate.request_context(&head).await {
+                Ok(context) => {
+                    if let Err(e) = delegate.check_rate_limits(&context).await {
+                        return Ok(limited(e));
+                    }
+                    let (mut parts, ()) = head.into_parts();
+                    parts.extensions.insert(context);
+                    inner.call(HttpRequest::from_parts(parts, body)).await
+                }
+                Err(e) => Ok(rejection(&delegate, e)),
+            }
+        })
+    }
+}
+
+impl ExtendedCardLayer {
+    pub fn new(delegate: A2ADelegate) -> Self {
+        Self { delegate }
+    }
+}
+
+impl<S> Layer<S> for ExtendedCardLayer {
+    type Service = ExtendedCardService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ExtendedCardService {
+            delegate: self.delegate.clone(),
+            inner,
+        }
+    }
+}
+
+impl<S> Service<HttpRequest> for ExtendedCardService<S>
+where
+    S: Service<HttpRequest, Response = HttpResponse> + Send,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: HttpRequest) -> Self::Future {
+        if request.method() != Method::GET
+            || request.uri().path() != REST_GET_AUTHENTICATED_EXTENDED_CARD_PATH
+        {
+            return Box::pin(self.inner.call(request));
+        }
+        let context = request
+            .extensions()
+            .get::<RequestContext>()
+            .cloned()
+            .unwrap_or_default();
+        let delegate = self.delegate.clone();
+        Box::pin(async move {
+            let response = match delegate
+                .get_authenticated_extended_card_with_context(&context)
+                .await
+            {
+                Ok(card) => json_response(StatusCode::OK, &json!(card)),
+                Err(A2AError::Auth(e)) => rejection(&delegate, e),
+                Err(A2AError::Protocol(e)) => {
+                    let status = match e {
+                        A2AProtocolError::AuthenticatedExtendedCardNotConfigured { .. } => {
+                            StatusCode::NOT_FOUND
+                        }
+                        _ => StatusCode::INTERNAL_SERVER_ERROR,
+                    };
+                    let body = json!({ "code": e.code() as i32, "message": e.to_string() });
+                    json_response(status, &body)
+                }
+                Err(e) => json_response(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    &json!({ "message": e.to_string() }),
+                ),
+            };
+            Ok(response)
+        })
+    }
+}
+
+impl JwksLayer {
+    pub fn new(delegate: A2ADelegate) -> Self {
+        Self { delegate }
+    }
+}
+
+impl<S> Layer<S> for JwksLayer {
+    type Service = JwksService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        JwksService {
+            delegate: self.delegate.clone(),
+            inner,
+        }
+    }
+}
+
+impl<S> Service<HttpRequest> for JwksService<S>
+where
+    S: Service<HttpRequest, Response = HttpResponse> + Send,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: HttpRequest) -> Self::Future {
+        let notifier = self.delegate.push_notifier();
+        let Some(notifier) = notifier
+            .filter(|_| request.method() == Method::GET && request.uri().path() == PUSH_JWKS_PATH)
+        else {
+            return Box::pin(self.inner.call(request));
+        };
+        let response = json_response(StatusCode::OK, &json!(notifier.keys().jwks()));
+        Box::pin(async move { Ok(response) })
+    }
+}
+
+impl AgentCardLayer {
+    pub fn new(delegate: A2ADelegate) -> Self {
+        Self { delegate }
+    }
+}
+
+impl<S> Layer<S> for AgentCardLayer {
+    type Service = AgentCardService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AgentCardService {
+            delegate: self.delegate.clone(),
+            inner,
+        }
+    }
+}
+
+impl<S> Service<HttpRequest> for AgentCardService<S>
+where
+    S: Service<HttpRequest, Response = HttpResponse> + Send,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: HttpRequest) -> Self::Future {
+        let Some(card) = self.delegate.card().filter(|_| {
+            request.method() == Method::GET && request.uri().path() == WELL_KNOWN_AGENT_CARD_PATH
+        }) else {
+            return Box::pin(self.inner.call(request));
+        };
+        let response = json_response(StatusCode::OK, &json!(card));
+        Box::pin(async move { Ok(response) })
+    }
+}
+
+fn rejection(delegate: &A2ADelegate, e: AuthError) -> HttpResponse {
+    let status = match e {
+        AuthError::InsufficientScope { .. } | AuthError::UnknownSkill(_) => StatusCode::FORBIDDEN,
+        _ => StatusCode::UNAUTHORIZED,
+    };
+    let body = json!({
+        "jsonrpc": "2.0",
+        "error": { "code": -32000, "message": e.to_string() },
+        "id": null,
+    });
+    let mut response = json_response(status, &body);
+    let challenge = delegate.authentication().and_then(|a| a.challenge());
+    if let Some(value) = challenge.and_then(|c| http::HeaderValue::from_str(&c).ok()) {
+        response.headers_mut().insert(WWW_AUTHENTICATE, value);
+    }
+    response
+}
+
+/// Answers a request over a rate limit with 429 and the json-rpc error of the limit.
+fn limited(e: A2AError) -> HttpResponse {
+    let A2AError::Protocol(e) = e else {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "error": { "code": -32000, "message": e.to_string() },
+            "id": null,
+        });
+        return json_response(StatusCode::INTERNAL_SERVER_ERROR, &body);
+    };
+    let body = json!({
+        "jsonrpc": "2.0",
+        "error": { "code": e.code() as i32, "message": e.to_string(), "data": e.data() },
+        "id": null,
+    });
+    let mut response = json_response(StatusCode::TOO_MANY_REQUESTS, &body);
+    if let Some(retry_after) = e.retry_after() {
+        response
+            .headers_mut()
+            .insert(RETRY_AFTER, http::HeaderValue::from(retry_after));
+    }
+    response
+}
+
+fn json_response(status: StatusCode, body: &Value) -> HttpResponse {
+    let mut response = HttpResponse::new(HttpBody::from(body.to_string()));
+    *response.status_mut() = status;
+    response.headers_mut().insert(
+        CONTENT_TYPE,
+        http::HeaderValue::from_static("application/json"),
+    );
+    response
+}
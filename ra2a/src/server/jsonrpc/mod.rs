@@ -1,3 +1,5 @@
+mod middleware;
 mod service;
 
+pub use middleware::*;
 pub use service::*;
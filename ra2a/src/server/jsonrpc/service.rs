@@ -1,19 +1,54 @@
-use crate::agent::A2ADelegate;
-use crate::core::task::Task;
+use crate::agent::{A2ADelegate, RequestContext};
 use crate::core::{
-    A2A, A2AError, A2AProtocolError, JSONRPC_GET_TASK_METHOD, JSONRPC_SEND_MESSAGE_METHOD,
+    A2AError, JSONRPC_GET_AUTHENTICATED_EXTENDED_CARD_METHOD, JSONRPC_GET_TASK_METHOD,
+    JSONRPC_SEND_MESSAGE_METHOD,
+};
+use crate::server::jsonrpc::{AgentCardLayer, AuthLayer, ExtendedCardLayer, JwksLayer};
+use crate::server::{A2AServerError, connection_builder, serve_connections};
+use crate::tls::ServerTlsConfig;
+use bytes::Bytes;
+use hyper::body::Body;
+use jsonrpsee::server::{
+    HttpBody, HttpResponse, Server, ServerHandle, StopHandle, TowerService, TowerServiceBuilder,
+    stop_channel,
 };
-use crate::server::A2AServerError;
-use jsonrpsee::RpcModule;
-use jsonrpsee::server::Server;
 use jsonrpsee::types::{ErrorObject, ErrorObjectOwned};
+use jsonrpsee::{Methods, RpcModule};
+use std::convert::Infallible;
+use std::fmt::{Debug, Formatter};
 use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
 use tokio::net::TcpListener;
+use tower::layer::util::{Identity, Stack};
+use tower::{BoxError, Service};
+
+type HttpMiddleware =
+    Stack<ExtendedCardLayer, Stack<AuthLayer, Stack<JwksLayer, Stack<AgentCardLayer, Identity>>>>;
+
+/// The JSON-RPC transport and its agent card and JWKS routes as a tower service, to mount
+/// next to other routes, e.g. nested under a path prefix of an axum router, and run on a
+/// server of the caller. Failures to serve a request are answered with a 500 response.
+#[derive(Clone)]
+pub struct A2AJsonRpcService {
+    service: TowerService<Identity, HttpMiddleware>,
+    // stops the service when the last clone is dropped
+    _server_handle: Option<Arc<ServerHandle>>,
+}
+
+/// Builds the services serving the connections of a server.
+#[derive(Clone)]
+pub(crate) struct JsonRpcStack {
+    builder: TowerServiceBuilder<Identity, HttpMiddleware>,
+    methods: Methods,
+}
 
 #[derive(Debug, Clone)]
 pub struct A2AJsonRpcServer {
     bind_addr: SocketAddr,
     delegate: A2ADelegate,
+    tls: Option<ServerTlsConfig>,
 }
 
 impl A2AJsonRpcServer {
@@ -21,50 +56,159 @@ impl A2AJsonRpcServer {
         Self {
             bind_addr,
             delegate,
+            tls: None,
         }
     }
 
+    pub fn with_tls(mut self, tls: ServerTlsConfig) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+
     pub async fn bind(&self) -> Result<TcpListener, A2AServerError> {
         TcpListener::bind(self.bind_addr)
             .await
             .map_err(A2AServerError::from)
     }
 
+    /// Serves connections with the jsonrpsee tower service until `signal` resolves.
     pub async fn serve<F: Future<Output = ()>>(
         &self,
         signal: F,
         listener: TcpListener,
     ) -> Result<(), A2AServerError> {
-        // hand off to jsonrpsee as std listener
-        let std_listener = listener.into_std()?;
-        // ensure non-blocking for hyper/jsonrpsee
-        std_listener.set_nonblocking(true)?;
-        let server = Server::builder().build_from_tcp(std_listener)?;
-
-        let mut module = RpcModule::new(self.delegate.clone());
-        module.register_async_method(JSONRPC_SEND_MESSAGE_METHOD, |params, ctx, _| async move {
-            let request = params.parse()?;
-            let res = ctx.send_message(request).await.unwrap(); // todo handle these errors!!!
-            Ok::<_, ErrorObjectOwned>(res)
-        })?;
-        module.register_async_method(JSONRPC_GET_TASK_METHOD, |params, ctx, _| async move {
-            let request = params.parse()?;
-            ctx.get_task(request).await.map_err(|e| match e {
-                A2AError::Protocol(A2AProtocolError::TaskNotFound { id, code }) => {
-                    ErrorObject::owned(code as i32, format!("Task not found: {id}"), None::<String>)
+        let stack = JsonRpcStack::new(&self.delegate)?;
+        serve_connections(
+            listener,
+            self.tls.as_ref(),
+            connection_builder(),
+            signal,
+            |stop_handle| stack.service(stop_handle),
+        )
+        .await
+    }
+}
+
+impl A2AJsonRpcService {
+    pub fn new(delegate: A2ADelegate) -> Result<Self, A2AServerError> {
+        let (stop_handle, server_handle) = stop_channel();
+        let mut service = JsonRpcStack::new(&delegate)?.service(stop_handle);
+        service._server_handle = Some(Arc::new(server_handle));
+        Ok(service)
+    }
+}
+
+impl Debug for A2AJsonRpcService {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("A2AJsonRpcService").finish_non_exhaustive()
+    }
+}
+
+impl<B> Service<http::Request<B>> for A2AJsonRpcService
+where
+    B: Body<Data = Bytes> + Send + 'static,
+    B::Error: Into<BoxError>,
+{
+    type Response = HttpResponse;
+    type Error = Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let future = self.service.call(request.map(HttpBody::new));
+        Box::pin(async move {
+            match future.await {
+                Ok(response) => Ok(response),
+                Err(e) => {
+                    tracing::warn!(error = %e, "failed to serve json-rpc request");
+                    Ok(jsonrpsee::server::http::response::internal_error())
                 }
-                _ => ErrorObject::owned(-32000, format!("{e:?}"), None::<String>), // todo clean this up
-            })
-        })?;
-        let handle = server.start(module);
-
-        tokio::select! {
-            _ = signal => {
-                let _ = handle.stop();
             }
-            _ = handle.clone().stopped() => {} // server finished on its own
+        })
+    }
+}
+
+impl JsonRpcStack {
+    /// The agent card and JWKS routes in front of the JSON-RPC methods of `delegate`.
+    pub(crate) fn new(delegate: &A2ADelegate) -> Result<Self, A2AServerError> {
+        let builder = Server::builder()
+            .set_http_middleware(
+                tower::ServiceBuilder::new()
+                    .layer(AgentCardLayer::new(delegate.clone()))
+                    .layer(JwksLayer::new(delegate.clone()))
+                    .layer(AuthLayer::new(delegate.clone()))
+                    .layer(ExtendedCardLayer::new(delegate.clone())),
+            )
+            .to_service_builder();
+        Ok(Self {
+            builder,
+            methods: Self::module(delegate)?.into(),
+        })
+    }
+
+    pub(crate) fn service(&self, stop_handle: StopHandle) -> A2AJsonRpcService {
+        A2AJsonRpcService {
+            service: self
+                .builder
+                .clone()
+                .build(self.methods.clone(), stop_handle),
+            _server_handle: None,
         }
+    }
+
+    pub(crate) fn module(delegate: &A2ADelegate) -> Result<RpcModule<A2ADelegate>, A2AServerError> {
+        let mut module = RpcModule::new(delegate.clone());
+        module.register_async_method(
+            JSONRPC_SEND_MESSAGE_METHOD,
+            |params, ctx, extensions| async move {
+                let request = params.parse()?;
+                let context = extensions
+                    .get::<RequestContext>()
+                    .cloned()
+                    .unwrap_or_default();
+                ctx.send_message_with_context(&context, request)
+                    .await
+                    .map_err(error_object)
+            },
+        )?;
+        module.register_async_method(
+            JSONRPC_GET_TASK_METHOD,
+            |params, ctx, extensions| async move {
+                let request = params.parse()?;
+                let context = extensions
+                    .get::<RequestContext>()
+                    .cloned()
+                    .unwrap_or_default();
+                ctx.get_task_with_context(&context, request)
+                    .await
+                    .map_err(error_object)
+            },
+        )?;
+        module.register_async_method(
+            JSONRPC_GET_AUTHENTICATED_EXTENDED_CARD_METHOD,
+            |_params, ctx, extensions| async move {
+                let context = extensions
+                    .get::<RequestContext>()
+                    .cloned()
+                    .unwrap_or_default();
+                ctx.get_authenticated_extended_card_with_context(&context)
+                    .await
+                    .map_err(error_object)
+            },
+        )?;
+        Ok(module)
+    }
+}
 
-        Ok(())
+/// Protocol errors keep their A2A code and structured data, anything else is a server error.
+pub(crate) fn error_object(e: A2AError) -> ErrorObjectOwned {
+    match e {
+        A2AError::Protocol(e) => ErrorObject::owned(e.code() as i32, e.to_string(), e.data()),
+        // push notification configs the agent refuses to send to
+        A2AError::Push(e) => ErrorObject::owned(-32602, e.to_string(), None::<()>),
+        e => ErrorObject::owned(-32000, e.to_string(), None::<()>),
     }
 }
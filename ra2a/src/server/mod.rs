@@ -1,10 +1,16 @@
 #[cfg(feature = "grpc")]
 pub mod grpc;
 pub mod jsonrpc;
+pub mod mux;
+pub mod stdio;
 pub mod task;
+#[cfg(unix)]
+pub mod unix;
 
+mod connection;
 mod error;
 mod service;
 
+pub(crate) use connection::*;
 pub use error::*;
 pub use service::*;
@@ -0,0 +1,270 @@
+use crate::agent::A2ADelegate;
+use crate::core::{AGENT_PATH_PREFIX, AGENT_ROUTING_HEADER};
+#[cfg(feature = "grpc")]
+use crate::grpc::GrpcConfig;
+#[cfg(feature = "grpc")]
+use crate::server::grpc::A2AGrpc;
+use crate::server::jsonrpc::{A2AJsonRpcService, JsonRpcStack};
+use crate::server::{A2AServerError, KeepaliveListener, connection_builder, serve_connections};
+use crate::tls::ServerTlsConfig;
+use http::StatusCode;
+use http::uri::PathAndQuery;
+use hyper::body::Incoming;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::net::TcpListener;
+use tower::limit::ConcurrencyLimitLayer;
+use tower::{BoxError, Service, ServiceBuilder};
+
+#[cfg(feature = "grpc")]
+type Body = tonic::body::Body;
+#[cfg(not(feature = "grpc"))]
+type Body = jsonrpsee::server::HttpBody;
+
+/// Serves JSON-RPC, its http routes and, with the `grpc` feature, gRPC on a single port,
+/// telling gRPC requests apart by their `application/grpc` content type.
+///
+/// Next to its own delegate, the server may host further agents, each with its own card,
+/// store and queue. Requests reach a hosted agent by, in order:
+/// - the `x-a2a-agent` header or gRPC metadata naming it,
+/// - a path under `/agents/{name}`, which is stripped before the request is served, so
+///   the card of the agent is at `/agents/{name}/.well-known/agent-card.json`,
+/// - a Host header mapped to it with [`with_agent_host`](Self::with_agent_host).
+///
+/// Requests naming an agent the server does not host are answered with not found, all
+/// others are served by the delegate of the server.
+///
+/// The HTTP/2, TCP keepalive and concurrency settings of the [`GrpcConfig`] apply to every
+/// connection, whichever transport it carries.
+#[derive(Debug, Clone)]
+pub struct A2AMuxServer {
+    bind_addr: SocketAddr,
+    delegate: A2ADelegate,
+    agents: HashMap<String, A2ADelegate>,
+    hosts: HashMap<String, String>,
+    tls: Option<ServerTlsConfig>,
+    #[cfg(feature = "grpc")]
+    grpc_config: GrpcConfig,
+}
+
+/// Dispatches the requests of a connection to the agent and transport they are for.
+#[derive(Clone)]
+struct MuxService {
+    default: AgentService,
+    agents: Arc<HashMap<String, AgentService>>,
+    hosts: Arc<HashMap<String, String>>,
+}
+
+/// The transports of one agent.
+#[derive(Clone)]
+struct AgentService {
+    jsonrpc: A2AJsonRpcService,
+    #[cfg(feature = "grpc")]
+    grpc: A2AGrpc,
+}
+
+/// Builds the transports of one agent for each connection.
+struct AgentStack {
+    jsonrpc: JsonRpcStack,
+    #[cfg(feature = "grpc")]
+    grpc: A2AGrpc,
+}
+
+impl A2AMuxServer {
+    pub fn new(bind_addr: SocketAddr, delegate: A2ADelegate) -> Self {
+        Self {
+            bind_addr,
+            delegate,
+            agents: HashMap::new(),
+            hosts: HashMap::new(),
+            tls: None,
+            #[cfg(feature = "grpc")]
+            grpc_config: GrpcConfig::default(),
+        }
+    }
+
+    /// Hosts `delegate` as the agent `name`, replacing any agent hosted under that name.
+    pub fn with_agent(mut self, name: impl Into<String>, delegate: A2ADelegate) -> Self {
+        self.agents.insert(name.into(), delegate);
+        self
+    }
+
+    /// Routes requests whose Host header is `host`, without port, to the hosted agent
+    /// `name`.
+    pub fn with_agent_host(mut self, host: impl Into<String>, name: impl Into<String>) -> Self {
+        self.hosts
+            .insert(host.into().to_ascii_lowercase(), name.into());
+        self
+    }
+
+    pub fn with_tls(mut self, tls: ServerTlsConfig) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+
+    #[cfg(feature = "grpc")]
+    pub fn with_grpc_config(mut self, config: GrpcConfig) -> Self {
+        self.grpc_config = config;
+        self
+    }
+
+    pub async fn bind(&self) -> Result<TcpListener, A2AServerError> {
+        TcpListener::bind(self.bind_addr)
+            .await
+            .map_err(A2AServerError::from)
+    }
+
+    pub async fn serve<F: Future<Output = ()>>(
+        &self,
+        signal: F,
+        listener: TcpListener,
+    ) -> Result<(), A2AServerError> {
+        let default = self.stack(&self.delegate)?;
+        let agents = self
+            .agents
+            .iter()
+            .map(|(name, delegate)| Ok((name.clone(), self.stack(delegate)?)))
+            .collect::<Result<HashMap<_, _>, A2AServerError>>()?;
+        let hosts = Arc::new(self.hosts.clone());
+        #[cfg(feature = "grpc")]
+        let (listener, builder, limit) = (
+            KeepaliveListener::new(listener, self.grpc_config.tcp_keepalive),
+            self.grpc_config.connection(connection_builder()),
+            self.grpc_config.concurrency_limit,
+        );
+        #[cfg(not(feature = "grpc"))]
+        let (listener, builder, limit) = (
+            KeepaliveListener::new(listener, None),
+            connection_builder(),
+            None,
+        );
+        serve_connections(
+            listener,
+            self.tls.as_ref(),
+            builder,
+            signal,
+            |stop_handle| {
+                let agents = agents
+                    .iter()
+                    .map(|(name, stack)| (name.clone(), stack.service(stop_handle.clone())))
+                    .collect();
+                let service = MuxService {
+                    default: default.service(stop_handle),
+                    agents: Arc::new(agents),
+                    hosts: hosts.clone(),
+                };
+                // a limit for each connection, like the gRPC server
+                ServiceBuilder::new()
+                    .option_layer(limit.map(ConcurrencyLimitLayer::new))
+                    .service(service)
+            },
+        )
+        .await
+    }
+
+    fn stack(&self, delegate: &A2ADelegate) -> Result<AgentStack, A2AServerError> {
+        Ok(AgentStack {
+            jsonrpc: JsonRpcStack::new(delegate)?,
+            #[cfg(feature = "grpc")]
+            grpc: A2AGrpc::new(delegate.clone()).with_config(self.grpc_config.clone()),
+        })
+    }
+}
+
+impl AgentStack {
+    fn service(&self, stop_handle: jsonrpsee::server::StopHandle) -> AgentService {
+        AgentService {
+            jsonrpc: self.jsonrpc.service(stop_handle),
+            #[cfg(feature = "grpc")]
+            grpc: self.grpc.clone(),
+        }
+    }
+}
+
+impl MuxService {
+    /// The agent `request` is for, `Err` with the name of an agent the server does not
+    /// host. Strips the `/agents/{name}` prefix of requests routed by path.
+    fn route(&self, request: &mut http::Request<Incoming>) -> Result<&AgentService, String> {
+        if let Some(name) = request.headers().get(AGENT_ROUTING_HEADER) {
+            let name = String::from_utf8_lossy(name.as_bytes());
+            return self.agents.get(name.as_ref()).ok_or(name.into_owned());
+        }
+        if let Some(rest) = request.uri().path().strip_prefix(AGENT_PATH_PREFIX) {
+            let (name, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+            let Some(agent) = self.agents.get(name) else {
+                return Err(name.to_string());
+            };
+            let path = match (path, request.uri().query()) {
+                ("", None) => "/".to_string(),
+                ("", Some(query)) => format!("/?{query}"),
+                (path, None) => path.to_string(),
+                (path, Some(query)) => format!("{path}?{query}"),
+            };
+            let mut parts = request.uri().clone().into_parts();
+            parts.path_and_query = PathAndQuery::try_from(path).ok();
+            if let Ok(uri) = http::Uri::from_parts(parts) {
+                *request.uri_mut() = uri;
+            }
+            return Ok(agent);
+        }
+        let host = request.uri().host().map(str::to_string).or_else(|| {
+            let host = request.headers().get(http::header::HOST)?.to_str().ok()?;
+            let authority = host.parse::<http::uri::Authority>().ok()?;
+            Some(authority.host().to_string())
+        });
+        let agent = host
+            .and_then(|host| self.hosts.get(&host.to_ascii_lowercase()))
+            .and_then(|name| self.agents.get(name));
+        Ok(agent.unwrap_or(&self.default))
+    }
+}
+
+impl Service<http::Request<Incoming>> for MuxService {
+    type Response = http::Response<Body>;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, mut request: http::Request<Incoming>) -> Self::Future {
+        #[cfg(feature = "grpc")]
+        let grpc = request
+            .headers()
+            .get(http::header::CONTENT_TYPE)
+            .is_some_and(|v| v.as_bytes().starts_with(b"application/grpc"));
+        let agent = match self.route(&mut request) {
+            Ok(agent) => agent.clone(),
+            Err(name) => {
+                let message = format!("agent {name} not found");
+                #[cfg(feature = "grpc")]
+                if grpc {
+                    let response = tonic::Status::not_found(message).into_http();
+                    return Box::pin(async move { Ok(response) });
+                }
+                let mut response = http::Response::new(Body::new(message));
+                *response.status_mut() = StatusCode::NOT_FOUND;
+                return Box::pin(async move { Ok(response) });
+            }
+        };
+        #[cfg(feature = "grpc")]
+        if grpc {
+            let mut service = agent.grpc;
+            return Box::pin(async move {
+                let Ok(response) = service.call(request).await;
+                Ok(response)
+            });
+        }
+        let mut service = agent.jsonrpc;
+        Box::pin(async move {
+            let Ok(response) = service.call(request).await;
+            #[cfg(feature = "grpc")]
+            let response = response.map(Body::new);
+            Ok(response)
+        })
+    }
+}
@@ -1,12 +1,22 @@
-use crate::agent::A2ADelegate;
-use crate::core::Transport;
+use crate::agent::{A2ADelegate, AgentServerHandle};
+use crate::core::{A2AError, Transport};
 use crate::server::A2AServerError;
+use crate::tls::ServerTlsConfig;
+use futures::future::BoxFuture;
 use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::net::TcpListener;
 use tokio::sync::Mutex;
 use tokio::sync::broadcast::Sender;
+use tokio::task::JoinHandle;
+use tokio_util::sync::{CancellationToken, WaitForCancellationFutureOwned};
+
+/// The transports a multiplexed server serves.
+#[cfg(feature = "grpc")]
+const MUX_TRANSPORTS: [Transport; 2] = [Transport::Grpc, Transport::JsonRpc];
+#[cfg(not(feature = "grpc"))]
+const MUX_TRANSPORTS: [Transport; 1] = [Transport::JsonRpc];
 
 #[derive(Debug, Clone)]
 pub struct A2AServer {
@@ -14,9 +24,26 @@ pub struct A2AServer {
     #[cfg(feature = "grpc")]
     grpc: Option<crate::server::grpc::A2AGrpcServer>,
     jsonrpc: Option<crate::server::jsonrpc::A2AJsonRpcServer>,
+    mux: Option<crate::server::mux::A2AMuxServer>,
+    agents: Vec<(String, A2ADelegate)>,
+    agent_hosts: Vec<(String, String)>,
+    #[cfg(unix)]
+    unix: Vec<crate::server::unix::A2AUnixServer>,
+    stdio: Option<crate::server::stdio::A2AStdioServer>,
+    tls: Option<ServerTlsConfig>,
+    #[cfg(feature = "grpc")]
+    grpc_config: Option<crate::grpc::GrpcConfig>,
     local_addrs: Arc<Mutex<HashMap<Transport, SocketAddr>>>,
 }
 
+/// The listeners of the transports without a network address, bound before the server
+/// starts so that failures surface to the caller.
+#[derive(Default)]
+struct LocalListeners {
+    #[cfg(unix)]
+    unix: Vec<(crate::server::unix::A2AUnixServer, tokio::net::UnixListener)>,
+}
+
 impl A2AServer {
     pub fn new(delegate: A2ADelegate) -> Self {
         A2AServer {
@@ -24,29 +51,154 @@ impl A2AServer {
             #[cfg(feature = "grpc")]
             grpc: None,
             jsonrpc: None,
+            mux: None,
+            agents: vec![],
+            agent_hosts: vec![],
+            #[cfg(unix)]
+            unix: vec![],
+            stdio: None,
+            tls: None,
+            #[cfg(feature = "grpc")]
+            grpc_config: None,
             local_addrs: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
     pub fn with_jsonrpc(mut self, addr: SocketAddr) -> Self {
-        self.jsonrpc = Some(crate::server::jsonrpc::A2AJsonRpcServer::new(
-            addr,
-            self.delegate.clone(),
-        ));
+        let mut jsonrpc =
+            crate::server::jsonrpc::A2AJsonRpcServer::new(addr, self.delegate.clone());
+        if let Some(tls) = &self.tls {
+            jsonrpc = jsonrpc.with_tls(tls.clone());
+        }
+        self.jsonrpc = Some(jsonrpc);
         self
     }
 
     #[cfg(feature = "grpc")]
     pub fn with_grpc(mut self, addr: SocketAddr) -> Self {
-        self.grpc = Some(crate::server::grpc::A2AGrpcServer::new(
-            addr,
+        let mut grpc = crate::server::grpc::A2AGrpcServer::new(addr, self.delegate.clone());
+        if let Some(tls) = &self.tls {
+            grpc = grpc.with_tls(tls.clone());
+        }
+        if let Some(config) = &self.grpc_config {
+            grpc = grpc.with_config(config.clone());
+        }
+        self.grpc = Some(grpc);
+        self
+    }
+
+    /// Serves every transport on `addr` instead of a port each, see
+    /// [`A2AMuxServer`](crate::server::mux::A2AMuxServer).
+    pub fn with_multiplexed(mut self, addr: SocketAddr) -> Self {
+        let mut mux = crate::server::mux::A2AMuxServer::new(addr, self.delegate.clone());
+        if let Some(tls) = &self.tls {
+            mux = mux.with_tls(tls.clone());
+        }
+        #[cfg(feature = "grpc")]
+        if let Some(config) = &self.grpc_config {
+            mux = mux.with_grpc_config(config.clone());
+        }
+        for (name, delegate) in &self.agents {
+            mux = mux.with_agent(name.clone(), delegate.clone());
+        }
+        for (host, name) in &self.agent_hosts {
+            mux = mux.with_agent_host(host.clone(), name.clone());
+        }
+        self.mux = Some(mux);
+        self
+    }
+
+    /// Hosts `delegate` as the agent `name` next to the delegate of the server. Hosted
+    /// agents are only served on the multiplexed port, see
+    /// [`A2AMuxServer`](crate::server::mux::A2AMuxServer) for how requests reach them.
+    pub fn with_agent(mut self, name: impl Into<String>, delegate: A2ADelegate) -> Self {
+        let name = name.into();
+        self.mux = self
+            .mux
+            .map(|mux| mux.with_agent(name.clone(), delegate.clone()));
+        self.agents.push((name, delegate));
+        self
+    }
+
+    /// Routes requests whose Host header is `host` to the hosted agent `name`.
+    pub fn with_agent_host(mut self, host: impl Into<String>, name: impl Into<String>) -> Self {
+        let (host, name) = (host.into(), name.into());
+        self.mux = self
+            .mux
+            .map(|mux| mux.with_agent_host(host.clone(), name.clone()));
+        self.agent_hosts.push((host, name));
+        self
+    }
+
+    /// Serves json-rpc on the Unix domain socket at `path`, next to the other transports.
+    #[cfg(unix)]
+    pub fn with_jsonrpc_unix(mut self, path: impl Into<std::path::PathBuf>) -> Self {
+        let unix = crate::server::unix::A2AUnixServer::new(
+            path,
+            Transport::JsonRpc,
+            self.delegate.clone(),
+        );
+        self.unix.push(unix);
+        self
+    }
+
+    /// Serves grpc on the Unix domain socket at `path`, next to the other transports.
+    #[cfg(all(unix, feature = "grpc"))]
+    pub fn with_grpc_unix(mut self, path: impl Into<std::path::PathBuf>) -> Self {
+        let mut unix =
+            crate::server::unix::A2AUnixServer::new(path, Transport::Grpc, self.delegate.clone());
+        if let Some(config) = &self.grpc_config {
+            unix = unix.with_grpc_config(config.clone());
+        }
+        self.unix.push(unix);
+        self
+    }
+
+    /// Serves newline-delimited json-rpc over stdin and stdout, for agents run as a
+    /// subprocess of their client, see [`A2AStdioServer`](crate::server::stdio::A2AStdioServer).
+    /// The server stops once stdin is closed.
+    pub fn with_stdio(mut self) -> Self {
+        self.stdio = Some(crate::server::stdio::A2AStdioServer::new(
             self.delegate.clone(),
         ));
         self
     }
 
+    #[cfg(feature = "grpc")]
+    pub fn with_grpc_config(mut self, config: crate::grpc::GrpcConfig) -> Self {
+        self.grpc = self.grpc.map(|grpc| grpc.with_config(config.clone()));
+        self.mux = self.mux.map(|mux| mux.with_grpc_config(config.clone()));
+        #[cfg(unix)]
+        {
+            self.unix = std::mem::take(&mut self.unix)
+                .into_iter()
+                .map(|unix| match unix.transport() {
+                    Transport::Grpc => unix.with_grpc_config(config.clone()),
+                    Transport::JsonRpc => unix,
+                })
+                .collect();
+        }
+        self.grpc_config = Some(config);
+        self
+    }
+
+    /// Serves every transport over TLS.
+    pub fn with_tls(mut self, tls: ServerTlsConfig) -> Self {
+        self.jsonrpc = self.jsonrpc.map(|jsonrpc| jsonrpc.with_tls(tls.clone()));
+        #[cfg(feature = "grpc")]
+        {
+            self.grpc = self.grpc.map(|grpc| grpc.with_tls(tls.clone()));
+        }
+        self.mux = self.mux.map(|mux| mux.with_tls(tls.clone()));
+        self.tls = Some(tls);
+        self
+    }
+
     pub fn enabled_transports(&self) -> Vec<Transport> {
         let mut transports = Vec::new();
+        if self.mux.is_some() {
+            return MUX_TRANSPORTS.to_vec();
+        }
         #[cfg(feature = "grpc")]
         if self.grpc.is_some() {
             transports.push(Transport::Grpc);
@@ -54,6 +206,16 @@ impl A2AServer {
         if self.jsonrpc.is_some() {
             transports.push(Transport::JsonRpc);
         }
+        #[cfg(unix)]
+        let local = self.unix.iter().map(|unix| unix.transport());
+        #[cfg(not(unix))]
+        let local = std::iter::empty();
+        let stdio = self.stdio.as_ref().map(|_| Transport::JsonRpc);
+        for transport in local.chain(stdio) {
+            if !transports.contains(&transport) {
+                transports.push(transport);
+            }
+        }
         transports
     }
 
@@ -61,6 +223,133 @@ impl A2AServer {
         self.local_addrs.lock().await.get(&transport).cloned()
     }
 
+    /// Binds all configured transports and serves them in the background until the
+    /// returned handle is shut down or a ctrl-c signal is received.
+    pub async fn start(&self) -> Result<AgentServerHandle, A2AError> {
+        if let Some(mux) = &self.mux {
+            return self.start_multiplexed(mux.clone()).await;
+        }
+        let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+
+        let mut local_addrs = HashMap::new();
+        let (grpc_listener, jrpc_listener) = self.bind_all().await?;
+        let mut transports = vec![];
+        if let Some(listener) = grpc_listener {
+            local_addrs.insert(
+                Transport::Grpc,
+                listener.local_addr().map_err(A2AServerError::from)?,
+            );
+            transports.push((Transport::Grpc, listener));
+        }
+        if let Some(listener) = jrpc_listener {
+            local_addrs.insert(
+                Transport::JsonRpc,
+                listener.local_addr().map_err(A2AServerError::from)?,
+            );
+            transports.push((Transport::JsonRpc, listener));
+        }
+        let local = self.bind_local().await?;
+
+        let handle = self.spawn(rx, local, |server, shutdown| async move {
+            server.serve_with_shutdown(transports, shutdown).await
+        });
+
+        Ok(AgentServerHandle::new(tx, handle, local_addrs))
+    }
+
+    /// Like [`start`](Self::start), every transport reporting the address of the single
+    /// listener.
+    async fn start_multiplexed(
+        &self,
+        mux: crate::server::mux::A2AMuxServer,
+    ) -> Result<AgentServerHandle, A2AError> {
+        let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+        let listener = mux.bind().await?;
+        let addr = listener.local_addr().map_err(A2AServerError::from)?;
+        let local_addrs: HashMap<_, _> = MUX_TRANSPORTS
+            .into_iter()
+            .map(|transport| (transport, addr))
+            .collect();
+        self.local_addrs.lock().await.extend(local_addrs.clone());
+        let local = self.bind_local().await?;
+
+        let handle = self.spawn(rx, local, |_, shutdown| async move {
+            mux.serve(shutdown, listener).await
+        });
+
+        Ok(AgentServerHandle::new(tx, handle, local_addrs))
+    }
+
+    /// Serves the network transports with `serve` and the local ones in the background,
+    /// until `rx` fires, a ctrl-c signal is received or the client closes stdio.
+    fn spawn<S, F>(
+        &self,
+        rx: tokio::sync::oneshot::Receiver<()>,
+        local: LocalListeners,
+        serve: S,
+    ) -> JoinHandle<Result<(), A2AError>>
+    where
+        S: FnOnce(A2AServer, WaitForCancellationFutureOwned) -> F + Send + 'static,
+        F: Future<Output = Result<(), A2AServerError>> + Send,
+    {
+        let server = self.clone();
+        tokio::spawn(async move {
+            let shutdown = CancellationToken::new();
+            let serving = futures::future::try_join(
+                serve(server.clone(), shutdown.clone().cancelled_owned()),
+                server.serve_local(local, shutdown.clone()),
+            );
+            tokio::pin!(serving);
+            let served = tokio::select! {
+                served = &mut serving => served,
+                // Treat either "sent ()" or "sender dropped" as a shutdown signal
+                _ = async { tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = rx => {}
+                } } => {
+                    shutdown.cancel();
+                    serving.await
+                }
+            };
+            served.map(|_| ()).map_err(A2AError::from)
+        })
+    }
+
+    async fn bind_local(&self) -> Result<LocalListeners, A2AServerError> {
+        #[allow(unused_mut)]
+        let mut local = LocalListeners::default();
+        #[cfg(unix)]
+        for unix in &self.unix {
+            local.unix.push((unix.clone(), unix.bind().await?));
+        }
+        Ok(local)
+    }
+
+    /// Serves the Unix domain sockets and stdio until `shutdown` is cancelled. The client
+    /// closing stdio cancels it, stopping every transport.
+    async fn serve_local(
+        &self,
+        local: LocalListeners,
+        shutdown: CancellationToken,
+    ) -> Result<(), A2AServerError> {
+        #[allow(unused_mut)]
+        let mut served: Vec<BoxFuture<'_, Result<(), A2AServerError>>> = vec![];
+        #[cfg(unix)]
+        for (unix, listener) in local.unix {
+            let signal = shutdown.clone().cancelled_owned();
+            served.push(Box::pin(async move { unix.serve(signal, listener).await }));
+        }
+        if let Some(stdio) = &self.stdio {
+            let shutdown = shutdown.clone();
+            served.push(Box::pin(async move {
+                let served = stdio.serve(shutdown.cancelled()).await;
+                shutdown.cancel();
+                served
+            }));
+        }
+        futures::future::try_join_all(served).await.map(|_| ())
+    }
+
     pub async fn bind_all(
         &self,
     ) -> Result<(Option<TcpListener>, Option<TcpListener>), A2AServerError> {
@@ -0,0 +1,97 @@
+use crate::agent::A2ADelegate;
+use crate::server::A2AServerError;
+use crate::server::jsonrpc::JsonRpcStack;
+use jsonrpsee::Methods;
+use serde_json::json;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::mpsc;
+use tokio::task::JoinSet;
+
+/// Serves JSON-RPC over a pair of byte streams, one message per line, usually the stdin
+/// and stdout of an agent run as a subprocess of its client.
+///
+/// Requests carry no headers and are not authenticated nor rate limited, the client that
+/// owns the process is trusted. Requests are handled concurrently, responses are written
+/// as they complete.
+#[derive(Debug, Clone)]
+pub struct A2AStdioServer {
+    delegate: A2ADelegate,
+}
+
+impl A2AStdioServer {
+    pub fn new(delegate: A2ADelegate) -> Self {
+        Self { delegate }
+    }
+
+    /// Serves the stdin and stdout of the process until stdin is closed or `signal`
+    /// resolves. Nothing else may write to stdout, log to stderr instead.
+    pub async fn serve<F: Future<Output = ()>>(&self, signal: F) -> Result<(), A2AServerError> {
+        self.serve_io(signal, tokio::io::stdin(), tokio::io::stdout())
+            .await
+    }
+
+    /// Serves the requests read from `reader` until it is closed or `signal` resolves,
+    /// waiting for the requests in flight to be answered on `writer`.
+    pub async fn serve_io<F, R, W>(
+        &self,
+        signal: F,
+        reader: R,
+        mut writer: W,
+    ) -> Result<(), A2AServerError>
+    where
+        F: Future<Output = ()>,
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let methods: Methods = JsonRpcStack::module(&self.delegate)?.into();
+        let (tx, mut rx) = mpsc::unbounded_channel::<String>();
+        let read = async move {
+            let mut lines = BufReader::new(reader).lines();
+            let mut calls = JoinSet::new();
+            tokio::pin!(signal);
+            loop {
+                let line = tokio::select! {
+                    line = lines.next_line() => line?,
+                    _ = &mut signal => break,
+                };
+                let Some(line) = line else {
+                    break;
+                };
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let (methods, tx) = (methods.clone(), tx.clone());
+                calls.spawn(async move {
+                    let _ = tx.send(respond(&methods, &line).await);
+                });
+            }
+            while calls.join_next().await.is_some() {}
+            Ok::<_, A2AServerError>(())
+        };
+        let write = async move {
+            while let Some(response) = rx.recv().await {
+                writer.write_all(response.as_bytes()).await?;
+                writer.write_all(b"\n").await?;
+                writer.flush().await?;
+            }
+            Ok::<_, A2AServerError>(())
+        };
+        tokio::try_join!(read, write).map(|_| ())
+    }
+}
+
+/// The response to a line, a parse error when it is not a JSON-RPC request.
+async fn respond(methods: &Methods, request: &str) -> String {
+    match methods.raw_json_request(request, 1).await {
+        Ok((response, _)) => response.get().to_string(),
+        Err(e) => {
+            tracing::debug!(error = %e, "failed to parse json-rpc request");
+            json!({
+                "jsonrpc": "2.0",
+                "error": { "code": -32700, "message": "Parse error" },
+                "id": null,
+            })
+            .to_string()
+        }
+    }
+}
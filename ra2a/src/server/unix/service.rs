@@ -0,0 +1,106 @@
+use crate::agent::A2ADelegate;
+use crate::core::Transport;
+use crate::server::jsonrpc::JsonRpcStack;
+use crate::server::{A2AServerError, connection_builder, serve_connections};
+use std::os::unix::fs::FileTypeExt;
+use std::path::{Path, PathBuf};
+use tokio::net::UnixListener;
+
+/// Serves one transport on a Unix domain socket, for sidecar agents and clients on the
+/// same host. Connections are served without TLS, access is controlled by the permissions
+/// of the socket file.
+#[derive(Debug, Clone)]
+pub struct A2AUnixServer {
+    path: PathBuf,
+    transport: Transport,
+    delegate: A2ADelegate,
+    #[cfg(feature = "grpc")]
+    grpc_config: crate::grpc::GrpcConfig,
+}
+
+impl A2AUnixServer {
+    pub fn new(path: impl Into<PathBuf>, transport: Transport, delegate: A2ADelegate) -> Self {
+        Self {
+            path: path.into(),
+            transport,
+            delegate,
+            #[cfg(feature = "grpc")]
+            grpc_config: crate::grpc::GrpcConfig::default(),
+        }
+    }
+
+    #[cfg(feature = "grpc")]
+    pub fn with_grpc_config(mut self, config: crate::grpc::GrpcConfig) -> Self {
+        self.grpc_config = config;
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn transport(&self) -> Transport {
+        self.transport
+    }
+
+    /// Binds the socket, replacing the socket file a previous run left behind.
+    pub async fn bind(&self) -> Result<UnixListener, A2AServerError> {
+        let stale = tokio::fs::symlink_metadata(&self.path)
+            .await
+            .is_ok_and(|metadata| metadata.file_type().is_socket());
+        if stale {
+            tokio::fs::remove_file(&self.path).await?;
+        }
+        UnixListener::bind(&self.path).map_err(A2AServerError::from)
+    }
+
+    /// Serves connections until `signal` resolves, then removes the socket file.
+    pub async fn serve<F: Future<Output = ()>>(
+        &self,
+        signal: F,
+        listener: UnixListener,
+    ) -> Result<(), A2AServerError> {
+        let served = match self.transport {
+            Transport::JsonRpc => self.serve_jsonrpc(signal, listener).await,
+            #[cfg(feature = "grpc")]
+            Transport::Grpc => self.serve_grpc(signal, listener).await,
+        };
+        let _ = tokio::fs::remove_file(&self.path).await;
+        served
+    }
+
+    async fn serve_jsonrpc<F: Future<Output = ()>>(
+        &self,
+        signal: F,
+        listener: UnixListener,
+    ) -> Result<(), A2AServerError> {
+        let stack = JsonRpcStack::new(&self.delegate)?;
+        serve_connections(
+            listener,
+            None,
+            connection_builder(),
+            signal,
+            |stop_handle| stack.service(stop_handle),
+        )
+        .await
+    }
+
+    #[cfg(feature = "grpc")]
+    async fn serve_grpc<F: Future<Output = ()>>(
+        &self,
+        signal: F,
+        listener: UnixListener,
+    ) -> Result<(), A2AServerError> {
+        let grpc = crate::server::grpc::A2AGrpc::new(self.delegate.clone())
+            .with_config(self.grpc_config.clone());
+        self.grpc_config
+            .server(tonic::transport::Server::builder())
+            .add_service(grpc)
+            .serve_with_incoming_shutdown(
+                tonic::codegen::tokio_stream::wrappers::UnixListenerStream::new(listener),
+                signal,
+            )
+            .await?;
+        Ok(())
+    }
+}
@@ -7,6 +7,7 @@ use tokio::sync::Mutex;
 #[derive(Debug, Clone, Default)]
 pub struct InMemoryTaskStore {
     store: Arc<Mutex<HashMap<String, Task>>>,
+    owners: Arc<Mutex<HashMap<String, String>>>,
 }
 
 #[async_trait::async_trait]
@@ -24,6 +25,18 @@ impl TaskStore for InMemoryTaskStore {
 
     async fn delete(&self, task_id: &str) -> Result<Option<Task>, TaskStoreError> {
         let mut store = self.store.lock().await;
+        self.owners.lock().await.remove(task_id);
         Ok(store.remove(task_id))
     }
+
+    async fn set_owner(&self, task_id: &str, owner: &str) -> Result<(), TaskStoreError> {
+        let mut owners = self.owners.lock().await;
+        owners.insert(task_id.to_string(), owner.to_string());
+        Ok(())
+    }
+
+    async fn owner(&self, task_id: &str) -> Result<Option<String>, TaskStoreError> {
+        let owners = self.owners.lock().await;
+        Ok(owners.get(task_id).cloned())
+    }
 }
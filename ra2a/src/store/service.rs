@@ -7,4 +7,7 @@ pub trait TaskStore: Debug + Send + Sync {
     async fn fetch(&self, task_id: &str) -> Result<Option<Task>, TaskStoreError>;
     async fn upsert(&self, task: Task) -> Result<Task, TaskStoreError>;
     async fn delete(&self, task_id: &str) -> Result<Option<Task>, TaskStoreError>;
+    /// Records the subject of the principal that created the task.
+    async fn set_owner(&self, task_id: &str, owner: &str) -> Result<(), TaskStoreError>;
+    async fn owner(&self, task_id: &str) -> Result<Option<String>, TaskStoreError>;
 }
@@ -0,0 +1,24 @@
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum TlsError {
+    #[error("Failed to read {path}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Invalid PEM")]
+    Pem(#[from] rustls::pki_types::pem::Error),
+
+    #[error("No certificates found in PEM")]
+    NoCertificates,
+
+    #[error("Rustls")]
+    Rustls(#[from] rustls::Error),
+
+    #[error("Invalid client certificate verifier")]
+    Verifier(#[from] rustls::server::VerifierBuilderError),
+}
@@ -0,0 +1,6 @@
+mod error;
+mod model;
+mod service;
+
+pub use error::*;
+pub use model::*;
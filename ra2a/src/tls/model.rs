@@ -0,0 +1,43 @@
+use std::fmt::{Debug, Formatter};
+
+/// TLS settings of a server: its certificate chain and private key and, for mutual TLS,
+/// the CA bundle client certificates are verified against.
+#[derive(Clone)]
+pub struct ServerTlsConfig {
+    pub(crate) cert_pem: Vec<u8>,
+    pub(crate) key_pem: Vec<u8>,
+    pub(crate) client_ca_pem: Option<Vec<u8>>,
+    pub(crate) client_auth_optional: bool,
+}
+
+/// TLS settings of a client: the CA bundle servers are verified against, the platform
+/// roots by default, and for mutual TLS the certificate presented to servers.
+#[derive(Clone, Default)]
+pub struct ClientTlsConfig {
+    pub(crate) ca_pem: Option<Vec<u8>>,
+    pub(crate) identity: Option<Identity>,
+}
+
+#[derive(Clone)]
+pub(crate) struct Identity {
+    pub(crate) cert_pem: Vec<u8>,
+    pub(crate) key_pem: Vec<u8>,
+}
+
+impl Debug for ServerTlsConfig {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ServerTlsConfig")
+            .field("mutual", &self.client_ca_pem.is_some())
+            .field("client_auth_optional", &self.client_auth_optional)
+            .finish()
+    }
+}
+
+impl Debug for ClientTlsConfig {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClientTlsConfig")
+            .field("custom_ca", &self.ca_pem.is_some())
+            .field("mutual", &self.identity.is_some())
+            .finish()
+    }
+}
@@ -0,0 +1,186 @@
+use crate::tls::{ClientTlsConfig, Identity, ServerTlsConfig, TlsError};
+use rustls::RootCertStore;
+use rustls::crypto::CryptoProvider;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::server::WebPkiClientVerifier;
+use std::path::Path;
+use std::sync::Arc;
+
+impl ServerTlsConfig {
+    /// Serves with the PEM encoded certificate chain, leaf first, and private key.
+    pub fn from_pem(
+        cert_pem: impl Into<Vec<u8>>,
+        key_pem: impl Into<Vec<u8>>,
+    ) -> Result<Self, TlsError> {
+        let cert_pem = cert_pem.into();
+        let key_pem = key_pem.into();
+        certificates(&cert_pem)?;
+        PrivateKeyDer::from_pem_slice(&key_pem)?;
+        Ok(Self {
+            cert_pem,
+            key_pem,
+            client_ca_pem: None,
+            client_auth_optional: false,
+        })
+    }
+
+    pub fn from_pem_files(
+        cert_path: impl AsRef<Path>,
+        key_path: impl AsRef<Path>,
+    ) -> Result<Self, TlsError> {
+        Self::from_pem(read(cert_path.as_ref())?, read(key_path.as_ref())?)
+    }
+
+    /// Enables mutual TLS: clients must present a certificate issued by one of the CAs
+    /// in the PEM bundle. Their certificate chain is made available to the
+    /// authenticator of `mutualTLS` card schemes.
+    pub fn with_client_ca_pem(mut self, ca_pem: impl Into<Vec<u8>>) -> Result<Self, TlsError> {
+        let ca_pem = ca_pem.into();
+        certificates(&ca_pem)?;
+        self.client_ca_pem = Some(ca_pem);
+        Ok(self)
+    }
+
+    /// Also accepts clients without a certificate, leaving them to the other security
+    /// schemes of the card.
+    pub fn with_client_auth_optional(mut self, optional: bool) -> Self {
+        self.client_auth_optional = optional;
+        self
+    }
+
+    pub(crate) fn rustls_config(&self) -> Result<rustls::ServerConfig, TlsError> {
+        let builder = rustls::ServerConfig::builder_with_provider(provider())
+            .with_safe_default_protocol_versions()?;
+        let builder = match &self.client_ca_pem {
+            Some(ca_pem) => {
+                let verifier =
+                    WebPkiClientVerifier::builder_with_provider(roots(ca_pem)?.into(), provider());
+                let verifier = match self.client_auth_optional {
+                    true => verifier.allow_unauthenticated().build()?,
+                    false => verifier.build()?,
+                };
+                builder.with_client_cert_verifier(verifier)
+            }
+            None => builder.with_no_client_auth(),
+        };
+        let mut config = builder.with_single_cert(
+            certificates(&self.cert_pem)?,
+            PrivateKeyDer::from_pem_slice(&self.key_pem)?,
+        )?;
+        config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+        Ok(config)
+    }
+
+    #[cfg(feature = "grpc")]
+    pub(crate) fn grpc_config(&self) -> tonic::transport::ServerTlsConfig {
+        use tonic::transport::{Certificate, Identity};
+        let config = tonic::transport::ServerTlsConfig::new()
+            .identity(Identity::from_pem(&self.cert_pem, &self.key_pem));
+        match &self.client_ca_pem {
+            Some(ca_pem) => config
+                .client_ca_root(Certificate::from_pem(ca_pem))
+                .client_auth_optional(self.client_auth_optional),
+            None => config,
+        }
+    }
+}
+
+impl ClientTlsConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Verifies servers against the CAs in the PEM bundle instead of the platform roots.
+    pub fn with_ca_pem(mut self, ca_pem: impl Into<Vec<u8>>) -> Result<Self, TlsError> {
+        let ca_pem = ca_pem.into();
+        certificates(&ca_pem)?;
+        self.ca_pem = Some(ca_pem);
+        Ok(self)
+    }
+
+    /// Presents the PEM encoded certificate chain and private key to servers that
+    /// require mutual TLS.
+    pub fn with_identity_pem(
+        mut self,
+        cert_pem: impl Into<Vec<u8>>,
+        key_pem: impl Into<Vec<u8>>,
+    ) -> Result<Self, TlsError> {
+        let cert_pem = cert_pem.into();
+        let key_pem = key_pem.into();
+        certificates(&cert_pem)?;
+        PrivateKeyDer::from_pem_slice(&key_pem)?;
+        self.identity = Some(Identity { cert_pem, key_pem });
+        Ok(self)
+    }
+
+    pub(crate) fn rustls_config(&self) -> Result<rustls::ClientConfig, TlsError> {
+        let roots = match &self.ca_pem {
+            Some(ca_pem) => roots(ca_pem)?,
+            None => {
+                let mut roots = RootCertStore::empty();
+                let native = rustls_native_certs::load_native_certs();
+                for error in native.errors {
+                    tracing::debug!(error = %error, "failed to load a platform root certificate");
+                }
+                roots.add_parsable_certificates(native.certs);
+                roots
+            }
+        };
+        let builder = rustls::ClientConfig::builder_with_provider(provider())
+            .with_safe_default_protocol_versions()?
+            .with_root_certificates(roots);
+        let config = match &self.identity {
+            Some(identity) => builder.with_client_auth_cert(
+                certificates(&identity.cert_pem)?,
+                PrivateKeyDer::from_pem_slice(&identity.key_pem)?,
+            )?,
+            None => builder.with_no_client_auth(),
+        };
+        Ok(config)
+    }
+
+    #[cfg(feature = "grpc")]
+    pub(crate) fn grpc_config(&self) -> tonic::transport::ClientTlsConfig {
+        use tonic::transport::{Certificate, Identity};
+        let config = tonic::transport::ClientTlsConfig::new();
+        let config = match &self.ca_pem {
+            Some(ca_pem) => config.ca_certificate(Certificate::from_pem(ca_pem)),
+            None => config.with_native_roots(),
+        };
+        match &self.identity {
+            Some(identity) => {
+                config.identity(Identity::from_pem(&identity.cert_pem, &identity.key_pem))
+            }
+            None => config,
+        }
+    }
+}
+
+/// Every transport uses ring, whichever providers other dependencies enable in rustls.
+fn provider() -> Arc<CryptoProvider> {
+    Arc::new(rustls::crypto::ring::default_provider())
+}
+
+fn certificates(pem: &[u8]) -> Result<Vec<CertificateDer<'static>>, TlsError> {
+    let certificates = CertificateDer::pem_slice_iter(pem).collect::<Result<Vec<_>, _>>()?;
+    match certificates.is_empty() {
+        true => Err(TlsError::NoCertificates),
+        false => Ok(certificates),
+    }
+}
+
+fn roots(pem: &[u8]) -> Result<RootCertStore, TlsError> {
+    let mut roots = RootCertStore::empty();
+    for certificate in certificates(pem)? {
+        roots.add(certificate)?;
+    }
+    Ok(roots)
+}
+
+fn read(path: &Path) -> Result<Vec<u8>, TlsError> {
+    std::fs::read(path).map_err(|source| TlsError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
@@ -0,0 +1,124 @@
+mod common;
+
+#[cfg(test)]
+#[cfg(feature = "agent")]
+mod authentication {
+    use crate::common;
+
+    use ra2a::agent::AgentBuilder;
+    use ra2a::auth::{Principal, StaticAuthenticator};
+    use ra2a::client::A2AClient;
+    use ra2a::core::agent::AgentCard;
+    use ra2a::core::message::{Message, SendMessageRequest};
+
+    use ra2a::core::{A2A, A2AError, A2ATransportError, Transport};
+    use serde_json::{Value, json};
+
+    fn card() -> AgentCard {
+        common::card("secure")
+            .with_scheme(
+                "apiKey",
+                json!({ "type": "apiKey", "in": "header", "name": "X-API-Key" }),
+            )
+            .with_scheme("bearer", json!({ "type": "http", "scheme": "bearer" }))
+            .with_security(json!([{ "apiKey": [] }, { "bearer": ["agent:write"] }]))
+            .build()
+    }
+
+    fn body() -> Value {
+        json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "message/send",
+            "params": {
+                "message": {
+                    "role": "user",
+                    "parts": [{ "kind": "text", "text": "who am i?" }],
+                    "messageId": uuid::Uuid::new_v4().to_string()
+                }
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn should_enforce_card_security() {
+        let authenticator = StaticAuthenticator::new()
+            .with_token("key-1", Principal::new("alice"))
+            .with_token(
+                "token-1",
+                Principal::new("bob").with_scopes(["agent:write"]),
+            )
+            .with_token(
+                "token-2",
+                Principal::new("carol").with_scopes(["agent:read"]),
+            );
+        let agent_builder = AgentBuilder::new(common::WhoAmIHandler)
+            .with_name("secure")
+            .with_card(card())
+            .with_authenticator(authenticator)
+            .with_json_rpc_server("[::]:0".parse().unwrap());
+        #[cfg(feature = "grpc")]
+        let agent_builder = agent_builder.with_grpc_server("[::]:0".parse().unwrap());
+        let agent = agent_builder.build().expect("failed to build agent");
+        let handle = agent.start_server().await.expect("failed to start server");
+        let url = format!(
+            "http://localhost:{}",
+            handle.local_addr(Transport::JsonRpc).unwrap().port()
+        );
+
+        let http = reqwest::Client::new();
+        let cases = [
+            (None, 401, None),
+            (Some(("X-API-Key", "wrong")), 401, None),
+            (Some(("X-API-Key", "key-1")), 200, Some("alice")),
+            (Some(("Authorization", "Bearer token-1")), 200, Some("bob")),
+            (Some(("Authorization", "Bearer token-2")), 403, None),
+        ];
+        for (header, status, subject) in cases {
+            let mut request = http.post(&url).json(&body());
+            if let Some((name, value)) = header {
+                request = request.header(name, value);
+            }
+            let res = request.send().await.unwrap();
+            assert_eq!(res.status().as_u16(), status, "{header:?}");
+            if status == 401 {
+                assert_eq!(res.headers()["www-authenticate"], "Bearer");
+            }
+            let res: Value = res.json().await.unwrap();
+            match subject {
+                Some(subject) => {
+                    assert_eq!(
+                        res["result"]["payload"]["parts"][0]["text"], subject,
+                        "{res}"
+                    )
+                }
+                None => assert!(res["error"].is_object(), "{res}"),
+            }
+        }
+
+        // clients without credentials are rejected by every transport
+        for (transport, addr) in handle.local_addrs() {
+            let client = A2AClient::new(transport, format!("http://localhost:{}", addr.port()))
+                .await
+                .unwrap();
+            let err = client
+                .send_message(SendMessageRequest {
+                    message: Some(Message::new_simple("who am i?")),
+                    configuration: None,
+                    metadata: None,
+                })
+                .await
+                .unwrap_err();
+            match err {
+                #[cfg(feature = "grpc")]
+                A2AError::Transport(A2ATransportError::Grcp(status)) => {
+                    assert_eq!(status.code(), tonic::Code::Unauthenticated)
+                }
+                A2AError::Transport(A2ATransportError::JsonRpc(_)) => {}
+                e => panic!("expected transport error, got {e:?}"),
+            }
+        }
+
+        handle.shutdown().await.unwrap();
+    }
+}
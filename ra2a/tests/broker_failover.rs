@@ -0,0 +1,291 @@
+mod common;
+
+#[cfg(test)]
+#[cfg(feature = "agent")]
+mod broker_failover {
+    use crate::common;
+    use async_trait::async_trait;
+    use ra2a::agent::AgentServerHandle;
+    use ra2a::agent::{A2AAgentError, AgentBuilder, AgentHandler, RequestContext};
+    use ra2a::broker::{AgentBroker, AgentReplica, HealthCheckConfig};
+    use ra2a::core::message::{Message, SendMessageRequest, SendMessageResponsePayload};
+    use ra2a::core::part::PartBase;
+    use ra2a::core::task::{GetTaskRequest, Task, TaskState, TaskStatus};
+    use ra2a::core::util::Object;
+    use ra2a::core::{A2AError, A2AProtocolError, Transport};
+
+    #[derive(Debug, Default)]
+    struct TestHandler;
+
+    #[async_trait]
+    impl AgentHandler for TestHandler {
+        async fn handle_message(
+            &self,
+            _context: &RequestContext,
+            message: Message,
+            _metadata: Option<Object>,
+            mut task: Task,
+        ) -> Result<SendMessageResponsePayload, A2AAgentError> {
+            let done =
+                matches!(&message.parts[0].part, Some(PartBase::Text(text)) if text == "bye");
+            task.history.push(message);
+            task.status = Some(match done {
+                true => TaskStatus {
+                    state: TaskState::Completed.into(),
+                    message: None,
+                    timestamp: None,
+                },
+                false => TaskStatus::default_submitted(),
+            });
+            Ok(SendMessageResponsePayload::Task(task))
+        }
+    }
+
+    /// Starts a replica of the test agent, publishing its card.
+    async fn start() -> (AgentServerHandle, String) {
+        let agent = AgentBuilder::new(TestHandler)
+            .with_name("test")
+            .with_card(common::card("test").build())
+            .with_json_rpc_server("[::]:0".parse().unwrap())
+            .build()
+            .expect("failed to build agent");
+        let handle = agent.start_server().await.expect("failed to start server");
+        let url = format!(
+            "http://localhost:{}",
+            handle.local_addr(Transport::JsonRpc).unwrap().port()
+        );
+        (handle, url)
+    }
+
+    fn request(task_id: Option<String>) -> SendMessageRequest {
+        text_request("hello there!", task_id)
+    }
+
+    fn text_request(text: &str, task_id: Option<String>) -> SendMessageRequest {
+        let mut message = Message::new_simple(text);
+        message.task_id = task_id;
+        SendMessageRequest {
+            message: Some(message),
+            configuration: None,
+            metadata: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn should_route_task_to_owning_replica() {
+        let broker = AgentBroker::new();
+        let mut handles = vec![];
+        for _ in 0..2 {
+            let agent = AgentBuilder::new(TestHandler)
+                .with_name("test")
+                .with_json_rpc_server("[::]:0".parse().unwrap())
+                .build()
+                .expect("failed to build agent");
+            let handle = agent.start_server().await.expect("failed to start server");
+            let url = format!(
+                "http://localhost:{}",
+                handle.local_addr(Transport::JsonRpc).unwrap().port()
+            );
+            let replica = AgentReplica::connect(Transport::JsonRpc, url)
+                .await
+                .unwrap();
+            broker.register("test", replica).await;
+            handles.push(handle);
+        }
+
+        let mut owners = vec![];
+        for _ in 0..4 {
+            let res = broker.send_message("test", request(None)).await.unwrap();
+            let task = match res.payload.unwrap() {
+                SendMessageResponsePayload::Task(task) => task,
+                _ => panic!("expected task"),
+            };
+            let owner = broker.task_owner("test", &task.id).await.unwrap();
+            owners.push(owner.id().to_string());
+
+            // only the owning replica knows the task, so every lookup must be routed there
+            for _ in 0..3 {
+                let got = broker
+                    .get_task(
+                        "test",
+                        GetTaskRequest {
+                            id: task.id.clone(),
+                            history_length: None,
+                            metadata: None,
+                        },
+                    )
+                    .await
+                    .unwrap();
+                assert_eq!(got, task);
+            }
+
+            // follow-up messages stick to the owner as well
+            broker
+                .send_message("test", request(Some(task.id.clone())))
+                .await
+                .unwrap();
+        }
+        owners.sort();
+        owners.dedup();
+        assert!(
+            owners.len() > 1,
+            "expected work to be spread across replicas"
+        );
+
+        for handle in handles {
+            handle.shutdown().await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn should_mark_down_replica_unhealthy_and_fail_over() {
+        let broker = AgentBroker::new()
+            .with_health_check(HealthCheckConfig::default().with_unhealthy_threshold(1));
+
+        let (up, up_url) = start().await;
+        let (down, down_url) = start().await;
+        down.shutdown().await.unwrap();
+
+        broker
+            .register(
+                "test",
+                AgentReplica::connect(Transport::JsonRpc, &up_url)
+                    .await
+                    .unwrap(),
+            )
+            .await;
+        broker
+            .register(
+                "test",
+                AgentReplica::connect(Transport::JsonRpc, &down_url)
+                    .await
+                    .unwrap(),
+            )
+            .await;
+
+        broker.check_health().await;
+        let replicas = broker.replicas("test").await.unwrap();
+        for replica in &replicas {
+            assert_eq!(replica.is_healthy(), replica.id() == up_url);
+        }
+
+        for _ in 0..4 {
+            let res = broker.send_message("test", request(None)).await.unwrap();
+            let task = match res.payload.unwrap() {
+                SendMessageResponsePayload::Task(task) => task,
+                _ => panic!("expected task"),
+            };
+            let owner = broker.task_owner("test", &task.id).await.unwrap();
+            assert_eq!(owner.id(), up_url);
+        }
+
+        let err = broker
+            .send_message("unknown", request(None))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, A2AError::Broker(_)), "got {err:?}");
+
+        up.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_report_owner_failure_rather_than_task_not_found() {
+        let broker = AgentBroker::new();
+        let mut handles = vec![];
+        for _ in 0..2 {
+            let (handle, url) = start().await;
+            let replica = AgentReplica::connect(Transport::JsonRpc, &url)
+                .await
+                .unwrap();
+            broker.register("test", replica).await;
+            handles.push((handle, url));
+        }
+
+        let task = common::task(broker.send_message("test", request(None)).await.unwrap());
+        let owner = broker.task_owner("test", &task.id).await.unwrap();
+        let (handle, _) = handles.remove(
+            handles
+                .iter()
+                .position(|(_, url)| url == owner.id())
+                .unwrap(),
+        );
+        handle.shutdown().await.unwrap();
+
+        let err = broker
+            .get_task(
+                "test",
+                GetTaskRequest {
+                    id: task.id.clone(),
+                    history_length: None,
+                    metadata: None,
+                },
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, A2AError::Transport(_)), "got {err:?}");
+
+        for (handle, _) in handles {
+            handle.shutdown().await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn should_forget_routes_of_finished_and_old_tasks() {
+        let broker = AgentBroker::new().with_max_task_routes(2);
+        let (handle, url) = start().await;
+        let replica = AgentReplica::connect(Transport::JsonRpc, &url)
+            .await
+            .unwrap();
+        broker.register("test", replica).await;
+
+        let finished = common::task(broker.send_message("test", request(None)).await.unwrap());
+        assert!(broker.task_owner("test", &finished.id).await.is_some());
+        broker
+            .send_message("test", text_request("bye", Some(finished.id.clone())))
+            .await
+            .unwrap();
+        assert!(broker.task_owner("test", &finished.id).await.is_none());
+
+        let mut ids = vec![];
+        for _ in 0..3 {
+            ids.push(common::task(broker.send_message("test", request(None)).await.unwrap()).id);
+        }
+        assert!(broker.task_owner("test", &ids[0]).await.is_none());
+        assert!(broker.task_owner("test", &ids[1]).await.is_some());
+        assert!(broker.task_owner("test", &ids[2]).await.is_some());
+
+        // a forgotten task is still found on the replica holding it
+        let got = broker
+            .get_task(
+                "test",
+                GetTaskRequest {
+                    id: finished.id.clone(),
+                    history_length: None,
+                    metadata: None,
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(got.id, finished.id);
+        let err = broker
+            .get_task(
+                "test",
+                GetTaskRequest {
+                    id: "unknown".to_string(),
+                    history_length: None,
+                    metadata: None,
+                },
+            )
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                A2AError::Protocol(A2AProtocolError::TaskNotFound { .. })
+            ),
+            "got {err:?}"
+        );
+
+        handle.shutdown().await.unwrap();
+    }
+}
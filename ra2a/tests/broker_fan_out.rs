@@ -0,0 +1,193 @@
+#[cfg(test)]
+#[cfg(feature = "agent")]
+mod broker_fan_out {
+    use async_trait::async_trait;
+    use ra2a::agent::{
+        A2AAgentError, AgentBuilder, AgentHandler, AgentServerHandle, RequestContext,
+    };
+    use ra2a::broker::{AgentBroker, AgentReplica, FanOut, FanOutOutcome, MergeStrategy};
+    use ra2a::core::Transport;
+    use ra2a::core::artifact::Artifact;
+    use ra2a::core::message::{Message, SendMessageRequest, SendMessageResponsePayload};
+    use ra2a::core::part::{Part, PartBase};
+    use ra2a::core::task::{Task, TaskState, TaskStatus};
+    use ra2a::core::util::Object;
+    use std::time::Duration;
+
+    #[derive(Debug)]
+    struct SlowHandler {
+        name: &'static str,
+        delay: Duration,
+    }
+
+    #[async_trait]
+    impl AgentHandler for SlowHandler {
+        async fn handle_message(
+            &self,
+            _context: &RequestContext,
+            message: Message,
+            _metadata: Option<Object>,
+            mut task: Task,
+        ) -> Result<SendMessageResponsePayload, A2AAgentError> {
+            tokio::time::sleep(self.delay).await;
+            task.history.push(message);
+            task.artifacts.push(Artifact {
+                artifact_id: format!("{}-artifact", self.name),
+                name: Some(self.name.to_string()),
+                description: None,
+                parts: vec![Part {
+                    part: Some(PartBase::Text(self.name.to_string())),
+                }],
+                metadata: None,
+                extensions: vec![],
+            });
+            task.status = Some(TaskStatus {
+                state: TaskState::Completed.into(),
+                message: None,
+                timestamp: None,
+            });
+            Ok(SendMessageResponsePayload::Task(task))
+        }
+    }
+
+    async fn register(
+        broker: &AgentBroker,
+        name: &'static str,
+        delay: Duration,
+    ) -> AgentServerHandle {
+        let agent = AgentBuilder::new(SlowHandler { name, delay })
+            .with_name(name)
+            .with_json_rpc_server("[::]:0".parse().unwrap())
+            .build()
+            .expect("failed to build agent");
+        let handle = agent.start_server().await.expect("failed to start server");
+        let url = format!(
+            "http://localhost:{}",
+            handle.local_addr(Transport::JsonRpc).unwrap().port()
+        );
+        let replica = AgentReplica::connect(Transport::JsonRpc, url)
+            .await
+            .unwrap();
+        broker.register(name, replica).await;
+        handle
+    }
+
+    fn fan_out() -> FanOut {
+        FanOut::new(SendMessageRequest {
+            message: Some(Message::new_simple("research this")),
+            configuration: None,
+            metadata: None,
+        })
+        .with_agents(["fast", "medium", "slow"])
+        .with_timeout(Duration::from_secs(5))
+        .with_agent_timeout("slow", Duration::from_millis(200))
+    }
+
+    fn artifact_agents(task: &Task) -> Vec<String> {
+        let mut agents: Vec<String> = task
+            .artifacts
+            .iter()
+            .map(|a| {
+                a.metadata
+                    .as_ref()
+                    .and_then(|m| m.get_str("agent"))
+                    .unwrap()
+                    .to_string()
+            })
+            .collect();
+        agents.sort();
+        agents
+    }
+
+    #[tokio::test]
+    async fn should_merge_results_by_strategy() {
+        let broker = AgentBroker::new();
+        let handles = vec![
+            register(&broker, "fast", Duration::ZERO).await,
+            register(&broker, "medium", Duration::from_millis(50)).await,
+            register(&broker, "slow", Duration::from_secs(2)).await,
+        ];
+
+        let all = broker.fan_out(fan_out()).await;
+        assert_eq!(
+            all.task.status.as_ref().unwrap().state,
+            TaskState::Failed.into_i32()
+        );
+        assert_eq!(artifact_agents(&all.task), vec!["fast", "medium"]);
+        let slow = all.responses.iter().find(|r| r.agent == "slow").unwrap();
+        assert!(matches!(slow.outcome, FanOutOutcome::TimedOut));
+
+        let quorum = broker
+            .fan_out(fan_out().with_strategy(MergeStrategy::Quorum(2)))
+            .await;
+        assert_eq!(
+            quorum.task.status.as_ref().unwrap().state,
+            TaskState::Completed.into_i32()
+        );
+        assert_eq!(artifact_agents(&quorum.task), vec!["fast", "medium"]);
+
+        let first = broker
+            .fan_out(fan_out().with_strategy(MergeStrategy::FirstComplete))
+            .await;
+        assert_eq!(
+            first.task.status.as_ref().unwrap().state,
+            TaskState::Completed.into_i32()
+        );
+        assert_eq!(first.responses.len(), 1);
+        assert_eq!(artifact_agents(&first.task), vec!["fast"]);
+        let metadata = serde_json::to_value(first.task.metadata.as_ref().unwrap()).unwrap();
+        assert_eq!(metadata["fanOut"]["strategy"], "firstComplete");
+        assert_eq!(metadata["fanOut"]["agents"].as_array().unwrap().len(), 3);
+
+        for handle in handles {
+            handle.shutdown().await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn should_settle_fan_outs_without_sending() {
+        // none of the agents is registered, sending to them would fail
+        let broker = AgentBroker::new();
+        let quorum = broker
+            .fan_out(fan_out().with_strategy(MergeStrategy::Quorum(0)))
+            .await;
+        assert_eq!(
+            quorum.task.status.as_ref().unwrap().state,
+            TaskState::Completed.into_i32()
+        );
+        assert!(quorum.responses.is_empty());
+        let metadata = serde_json::to_value(quorum.task.metadata.as_ref().unwrap()).unwrap();
+        assert_eq!(metadata["fanOut"]["agents"][0]["outcome"], "abandoned");
+
+        for (strategy, state) in [
+            (MergeStrategy::FirstComplete, TaskState::Completed),
+            (MergeStrategy::All, TaskState::Completed),
+            // a quorum no agent is there to reach
+            (MergeStrategy::Quorum(2), TaskState::Failed),
+        ] {
+            let request = SendMessageRequest {
+                message: Some(Message::new_simple("research this")),
+                configuration: None,
+                metadata: None,
+            };
+            let none = broker
+                .fan_out(FanOut::new(request).with_strategy(strategy))
+                .await;
+            assert_eq!(
+                none.task.status.as_ref().unwrap().state,
+                state.into_i32(),
+                "{strategy:?}"
+            );
+            assert!(none.responses.is_empty());
+        }
+
+        let unreachable = broker
+            .fan_out(fan_out().with_strategy(MergeStrategy::Quorum(5)))
+            .await;
+        assert_eq!(
+            unreachable.task.status.as_ref().unwrap().state,
+            TaskState::Failed.into_i32()
+        );
+        assert!(unreachable.responses.is_empty());
+    }
+}
@@ -0,0 +1,196 @@
+mod common;
+
+#[cfg(test)]
+#[cfg(feature = "agent")]
+mod broker_registry {
+    use crate::common;
+    use async_trait::async_trait;
+    use ra2a::agent::{
+        A2AAgentError, AgentBuilder, AgentHandler, AgentServerHandle, RequestContext,
+    };
+    use ra2a::broker::AgentBroker;
+    use ra2a::core::Transport;
+    use ra2a::core::agent::AgentCard;
+    use ra2a::core::message::{Message, SendMessageRequest, SendMessageResponsePayload};
+    use ra2a::core::task::{Task, TaskStatus};
+    use ra2a::core::util::Object;
+    use std::path::PathBuf;
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    #[derive(Debug, Default)]
+    struct TestHandler;
+
+    #[async_trait]
+    impl AgentHandler for TestHandler {
+        async fn handle_message(
+            &self,
+            _context: &RequestContext,
+            message: Message,
+            _metadata: Option<Object>,
+            mut task: Task,
+        ) -> Result<SendMessageResponsePayload, A2AAgentError> {
+            task.history.push(message);
+            task.status = Some(TaskStatus::default_submitted());
+            Ok(SendMessageResponsePayload::Task(task))
+        }
+    }
+
+    async fn start_agent() -> (AgentServerHandle, String) {
+        let agent = AgentBuilder::new(TestHandler)
+            .with_name("test")
+            .with_json_rpc_server("[::]:0".parse().unwrap())
+            .build()
+            .expect("failed to build agent");
+        let handle = agent.start_server().await.expect("failed to start server");
+        let url = format!(
+            "http://localhost:{}",
+            handle.local_addr(Transport::JsonRpc).unwrap().port()
+        );
+        (handle, url)
+    }
+
+    fn card(url: &str) -> AgentCard {
+        common::card("test")
+            .with("url", url)
+            .with("preferredTransport", "JSONRPC")
+            .build()
+    }
+
+    /// Serves `card` as json to every request and returns its url.
+    async fn serve_card(card: AgentCard) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/card.json", listener.local_addr().unwrap());
+        let body = serde_json::to_string(&card).unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buf = [0u8; 4096];
+                let _ = stream.read(&mut buf).await;
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        url
+    }
+
+    fn registry_path(extension: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "ra2a-registry-{}.{extension}",
+            uuid::Uuid::new_v4()
+        ))
+    }
+
+    fn request() -> SendMessageRequest {
+        SendMessageRequest {
+            message: Some(Message::new_simple("hello there!")),
+            configuration: None,
+            metadata: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn should_load_agents_from_toml() {
+        let (handle, url) = start_agent().await;
+        let card_url = serve_card(card(&url)).await;
+        let path = registry_path("toml");
+        std::fs::write(
+            &path,
+            format!(
+                r#"
+                [[agents]]
+                name = "search"
+                cardUrl = "{card_url}"
+                preferredTransport = "JSONRPC"
+                weight = 3
+                tags = ["web", "research"]
+                "#
+            ),
+        )
+        .unwrap();
+
+        let broker = AgentBroker::new();
+        broker.load_registry_file(&path).await.unwrap();
+        assert_eq!(broker.agents().await, vec!["search"]);
+        assert_eq!(broker.agents_with_tag("web").await, vec!["search"]);
+        assert!(broker.agents_with_tag("maps").await.is_empty());
+        let replicas = broker.replicas("search").await.unwrap();
+        assert_eq!(replicas.len(), 1);
+        assert_eq!(replicas[0].id(), url);
+        assert_eq!(replicas[0].weight(), 3);
+        broker.send_message("search", request()).await.unwrap();
+
+        std::fs::remove_file(&path).unwrap();
+        handle.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_hot_reload_registry() {
+        let (first_handle, first) = start_agent().await;
+        let (second_handle, second) = start_agent().await;
+        let write = |path: &PathBuf, agents: serde_json::Value| {
+            std::fs::write(path, serde_json::json!({ "agents": agents }).to_string()).unwrap();
+        };
+        let path = registry_path("json");
+        write(
+            &path,
+            serde_json::json!([{ "name": "alpha", "card": card(&first) }]),
+        );
+
+        let broker = AgentBroker::new();
+        let _watch = broker
+            .watch_registry(&path, Duration::from_millis(20))
+            .await
+            .unwrap();
+        let res = broker.send_message("alpha", request()).await.unwrap();
+        let task = match res.payload.unwrap() {
+            SendMessageResponsePayload::Task(task) => task,
+            _ => panic!("expected task"),
+        };
+
+        // add a replica, the existing one keeps its task routes
+        write(
+            &path,
+            serde_json::json!([
+                { "name": "alpha", "card": card(&first) },
+                { "name": "alpha", "card": card(&second), "weight": 2 },
+            ]),
+        );
+        wait_until(|| async { broker.replicas("alpha").await.unwrap().len() == 2 }).await;
+        let owner = broker.task_owner("alpha", &task.id).await.unwrap();
+        assert_eq!(owner.id(), first);
+
+        // an invalid file is ignored
+        std::fs::write(&path, "{ not json").unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(broker.agents().await, vec!["alpha"]);
+
+        // agents removed from the file are deregistered
+        write(
+            &path,
+            serde_json::json!([{ "name": "beta", "card": card(&second) }]),
+        );
+        wait_until(|| async { broker.agents().await == vec!["beta"] }).await;
+
+        std::fs::remove_file(&path).unwrap();
+        first_handle.shutdown().await.unwrap();
+        second_handle.shutdown().await.unwrap();
+    }
+
+    async fn wait_until<F, Fut>(condition: F)
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = bool>,
+    {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !condition().await {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("condition not met in time");
+    }
+}
@@ -0,0 +1,211 @@
+mod common;
+
+#[cfg(test)]
+mod card_signature {
+    use crate::common;
+    use ra2a::client::{A2AClient, A2AClientConfig, A2AClientError};
+    use ra2a::core::agent::AgentCard;
+    use ra2a::jws::{Algorithm, CardVerifier, JwkSet, JwsError, SigningKey};
+    use serde_json::json;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    const ES256: &str = include_str!("keys/es256.pem");
+    const RS256: &str = include_str!("keys/rs256.pem");
+    const ED25519: &str = include_str!("keys/ed25519.pem");
+
+    fn card() -> AgentCard {
+        common::card("signed")
+            .with("description", "An agent with a signed card")
+            .with("url", "http://localhost:8080")
+            .with("defaultInputModes", json!(["text/plain"]))
+            .with("defaultOutputModes", json!(["text/plain"]))
+            .build()
+    }
+
+    fn keys() -> Vec<SigningKey> {
+        vec![
+            SigningKey::from_pkcs8_pem(Algorithm::ES256, ES256)
+                .unwrap()
+                .with_key_id("es256"),
+            SigningKey::from_pkcs8_pem(Algorithm::RS256, RS256)
+                .unwrap()
+                .with_key_id("rs256"),
+            SigningKey::from_pkcs8_pem(Algorithm::EdDSA, ED25519)
+                .unwrap()
+                .with_key_id("ed25519"),
+        ]
+    }
+
+    /// Serves `body` as JSON for every request and returns its url.
+    async fn serve_json(body: String) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!(
+            "http://{}/.well-known/agent-card.json",
+            listener.local_addr().unwrap()
+        );
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buf = [0u8; 4096];
+                let _ = stream.read(&mut buf).await;
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        url
+    }
+
+    #[test]
+    fn should_verify_signed_cards() {
+        for key in keys() {
+            let card = card().with_signature(&key).unwrap();
+            let verifier = CardVerifier::new().with_key(key.public_jwk());
+            verifier.verify(&card).unwrap();
+
+            // the signature survives a round trip through JSON
+            let json = serde_json::to_string(&card).unwrap();
+            let card: AgentCard = serde_json::from_str(&json).unwrap();
+            verifier.verify(&card).unwrap();
+
+            let mut tampered = card.clone();
+            tampered.url = "http://attacker.example".to_string();
+            assert!(
+                matches!(verifier.verify(&tampered), Err(JwsError::InvalidSignature)),
+                "{:?}",
+                key.algorithm()
+            );
+        }
+    }
+
+    #[test]
+    fn should_refuse_unsigned_and_untrusted_cards() {
+        let [es256, rs256, _] = <[SigningKey; 3]>::try_from(keys()).unwrap();
+        let verifier = CardVerifier::new().with_key(es256.public_jwk());
+        assert!(matches!(verifier.verify(&card()), Err(JwsError::Unsigned)));
+        verifier
+            .clone()
+            .with_allow_unsigned(true)
+            .verify(&card())
+            .unwrap();
+
+        let card = card().with_signature(&rs256).unwrap();
+        assert!(matches!(
+            verifier.verify(&card),
+            Err(JwsError::InvalidSignature)
+        ));
+
+        // any trusted signature is enough
+        let card = card.with_signature(&es256).unwrap();
+        verifier.verify(&card).unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_verify_fetched_cards_against_jwks() {
+        let keys = keys();
+        let jwks = keys
+            .iter()
+            .fold(JwkSet::new(), |jwks, key| jwks.with_key(key.public_jwk()));
+        let jwks_url = serve_json(serde_json::to_string(&jwks).unwrap()).await;
+        let verifier = CardVerifier::new().with_jwks(JwkSet::fetch(&jwks_url).await.unwrap());
+        let config = A2AClientConfig::new().with_card_verifier(verifier);
+
+        let signed = card().with_signature(&keys[2]).unwrap();
+        let url = serve_json(serde_json::to_string(&signed).unwrap()).await;
+        let card = A2AClient::fetch_agent_card(&url, &config).await.unwrap();
+        assert_eq!(card, signed);
+
+        let mut tampered = signed.clone();
+        tampered.name = "impostor".to_string();
+        let url = serve_json(serde_json::to_string(&tampered).unwrap()).await;
+        let res = A2AClient::fetch_agent_card(&url, &config).await;
+        assert!(matches!(
+            res,
+            Err(A2AClientError::CardVerification(JwsError::InvalidSignature))
+        ));
+
+        let url = serve_json(serde_json::to_string(&self::card()).unwrap()).await;
+        let res = A2AClient::fetch_agent_card(&url, &config).await;
+        assert!(matches!(
+            res,
+            Err(A2AClientError::CardVerification(JwsError::Unsigned))
+        ));
+
+        // without a verifier cards are accepted as is
+        let res = A2AClient::fetch_agent_card(&url, &A2AClientConfig::new()).await;
+        assert_eq!(res.unwrap(), self::card());
+    }
+
+    #[tokio::test]
+    async fn should_verify_fetched_cards_as_published() {
+        // published by another implementation: optional members left out and an
+        // extension member the card does not model
+        let mut published = json!({
+            "protocolVersion": "0.3.0",
+            "name": "foreign",
+            "description": "An agent with a foreign card",
+            "url": "http://localhost:8080",
+            "version": "1.0.0",
+            "securitySchemes": {},
+            "security": [],
+            "defaultInputModes": ["text/plain"],
+            "defaultOutputModes": ["text/plain"],
+            "skills": [],
+            "x-vendor": { "region": "eu" }
+        });
+        let key = &keys()[0];
+        let signature = key.sign_json(&published).unwrap();
+        published["signatures"] = json!([signature]);
+        let config = A2AClientConfig::new()
+            .with_card_verifier(CardVerifier::new().with_key(key.public_jwk()));
+
+        let url = serve_json(published.to_string()).await;
+        let card = A2AClient::fetch_agent_card(&url, &config).await.unwrap();
+        assert_eq!(card.name, "foreign");
+
+        // the unmodeled member is covered by the signature
+        published["x-vendor"]["region"] = json!("us");
+        let url = serve_json(published.to_string()).await;
+        let res = A2AClient::fetch_agent_card(&url, &config).await;
+        assert!(matches!(
+            res,
+            Err(A2AClientError::CardVerification(JwsError::InvalidSignature))
+        ));
+    }
+
+    #[test]
+    fn should_canonicalize_large_integers_as_doubles() {
+        // 2^53 + 1 has no exact double, so JCS serializes it as 2^53
+        let card = |quota: serde_json::Value| {
+            json!({
+                "name": "foreign",
+                "url": "http://localhost:8080",
+                "x-vendor": { "quota": quota, "limit": -9_007_199_254_740_993i64 }
+            })
+        };
+        let key = &keys()[0];
+        let verifier = CardVerifier::new().with_key(key.public_jwk());
+
+        let mut signed = card(json!(9_007_199_254_740_993u64));
+        signed["signatures"] = json!([key.sign_json(&signed).unwrap()]);
+        verifier.verify_json(&signed).unwrap();
+
+        // as signed by an implementation that parses numbers as doubles
+        let mut doubles = card(json!(9_007_199_254_740_992f64));
+        doubles["x-vendor"]["limit"] = json!(-9_007_199_254_740_992f64);
+        doubles["signatures"] = signed["signatures"].clone();
+        verifier.verify_json(&doubles).unwrap();
+
+        // integers within 2^53 are still exact
+        let mut exact = card(json!(9_007_199_254_740_991u64));
+        exact["signatures"] = json!([key.sign_json(&exact).unwrap()]);
+        let mut rounded = card(json!(9_007_199_254_740_990u64));
+        rounded["signatures"] = exact["signatures"].clone();
+        assert!(matches!(
+            verifier.verify_json(&rounded),
+            Err(JwsError::InvalidSignature)
+        ));
+    }
+}
@@ -0,0 +1,25 @@
+#!/bin/sh
+# Regenerates the certificates used by the tls tests: a CA, a server certificate for
+# localhost and a client certificate for "alice", all signed by the CA.
+set -e
+cd "$(dirname "$0")"
+
+openssl genpkey -algorithm EC -pkeyopt ec_paramgen_curve:P-256 -out ca.key
+openssl req -x509 -new -key ca.key -sha256 -days 36500 -subj "/CN=ra2a test ca" \
+  -addext "basicConstraints=critical,CA:TRUE" \
+  -addext "keyUsage=critical,keyCertSign,cRLSign" \
+  -out ca.pem
+
+openssl genpkey -algorithm EC -pkeyopt ec_paramgen_curve:P-256 -out server.key
+openssl req -new -key server.key -subj "/CN=localhost" -out server.csr
+printf "subjectAltName=DNS:localhost,IP:127.0.0.1,IP:::1\nextendedKeyUsage=serverAuth\nbasicConstraints=critical,CA:FALSE\n" > server.ext
+openssl x509 -req -in server.csr -CA ca.pem -CAkey ca.key -CAcreateserial -sha256 -days 36500 \
+  -extfile server.ext -out server.pem
+
+openssl genpkey -algorithm EC -pkeyopt ec_paramgen_curve:P-256 -out client.key
+openssl req -new -key client.key -subj "/CN=alice" -out client.csr
+printf "extendedKeyUsage=clientAuth\nbasicConstraints=critical,CA:FALSE\n" > client.ext
+openssl x509 -req -in client.csr -CA ca.pem -CAkey ca.key -CAcreateserial -sha256 -days 36500 \
+  -extfile client.ext -out client.pem
+
+rm -f ca.key ca.srl server.csr server.ext client.csr client.ext
@@ -0,0 +1,294 @@
+mod common;
+
+#[cfg(test)]
+#[cfg(feature = "agent")]
+mod client_credentials {
+    use crate::common;
+    use async_trait::async_trait;
+    use ra2a::agent::AgentBuilder;
+    use ra2a::auth::{Principal, StaticAuthenticator};
+    use ra2a::client::A2AClient;
+    use ra2a::client::auth::{
+        ClientAuthentication, ClientCredential, CredentialError, CredentialProvider,
+        StaticCredentialProvider,
+    };
+    use ra2a::core::A2A;
+    use ra2a::core::agent::AgentCard;
+    use ra2a::core::message::{Message, SendMessageRequest, SendMessageResponsePayload};
+    use ra2a::core::part::PartBase;
+
+    use ra2a::core::util::SecurityScheme;
+    use serde_json::{Value, json};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Presents the credentials of `client-a` or `client-b`, as switched.
+    #[derive(Debug, Default)]
+    struct SwitchingProvider(AtomicBool);
+
+    #[async_trait]
+    impl CredentialProvider for SwitchingProvider {
+        async fn credential(
+            &self,
+            _scheme_name: &str,
+            _scheme: &SecurityScheme,
+        ) -> Option<ClientCredential> {
+            let client_id = match self.0.load(Ordering::SeqCst) {
+                true => "client-b",
+                false => "client-a",
+            };
+            Some(ClientCredential::ClientCredentials {
+                client_id: client_id.to_string(),
+                client_secret: "secret".to_string(),
+            })
+        }
+    }
+
+    fn card(security: Value, token_url: &str) -> AgentCard {
+        common::card("secure")
+            .with_scheme(
+                "apiKey",
+                json!({ "type": "apiKey", "in": "header", "name": "X-API-Key" }),
+            )
+            .with_scheme(
+                "oauth",
+                json!({
+                    "type": "oauth2",
+                    "flows": {
+                        "clientCredentials": {
+                            "tokenUrl": token_url,
+                            "scopes": { "agent:write": "send messages" }
+                        }
+                    }
+                }),
+            )
+            .with_security(security)
+            .build()
+    }
+
+    /// Issues `token-1`, `token-2`, ... valid for `expires_in` seconds and returns the
+    /// token url along with the number of tokens issued.
+    async fn serve_tokens(expires_in: u64) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/token", listener.local_addr().unwrap());
+        let issued = Arc::new(AtomicUsize::new(0));
+        let counter = issued.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buf = [0u8; 4096];
+                let n = stream.read(&mut buf).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&buf[..n]).to_lowercase();
+                let response = match request.contains("authorization: basic ")
+                    && request.contains("grant_type=client_credentials")
+                {
+                    true => {
+                        let n = counter.fetch_add(1, Ordering::SeqCst) + 1;
+                        let body = json!({
+                            "access_token": format!("token-{n}"),
+                            "token_type": "Bearer",
+                            "expires_in": expires_in
+                        })
+                        .to_string();
+                        format!(
+                            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                            body.len()
+                        )
+                    }
+                    false => "HTTP/1.1 401 Unauthorized\r\ncontent-length: 0\r\nconnection: close\r\n\r\n"
+                        .to_string(),
+                };
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        (url, issued)
+    }
+
+    fn authenticator() -> StaticAuthenticator {
+        (1..=10).fold(
+            StaticAuthenticator::new().with_token("key-1", Principal::new("alice")),
+            |authenticator, n| {
+                authenticator.with_token(
+                    format!("token-{n}"),
+                    Principal::new("service").with_scopes(["agent:write"]),
+                )
+            },
+        )
+    }
+
+    async fn who_am_i(client: &A2AClient) -> String {
+        let res = client
+            .send_message(SendMessageRequest {
+                message: Some(Message::new_simple("who am i?")),
+                configuration: None,
+                metadata: None,
+            })
+            .await
+            .unwrap();
+        let Some(SendMessageResponsePayload::Message(message)) = res.payload else {
+            panic!("expected a message");
+        };
+        match &message.parts[0].part {
+            Some(PartBase::Text(text)) => text.clone(),
+            part => panic!("unexpected part {part:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn should_fetch_and_cache_client_credentials_tokens() {
+        let (token_url, issued) = serve_tokens(3600).await;
+        let card = card(json!([{ "oauth": ["agent:write"] }]), &token_url);
+        let agent_builder = AgentBuilder::new(common::WhoAmIHandler)
+            .with_name("secure")
+            .with_card(card.clone())
+            .with_authenticator(authenticator())
+            .with_json_rpc_server("[::]:0".parse().unwrap());
+        #[cfg(feature = "grpc")]
+        let agent_builder = agent_builder.with_grpc_server("[::]:0".parse().unwrap());
+        let agent = agent_builder.build().expect("failed to build agent");
+        let handle = agent.start_server().await.expect("failed to start server");
+
+        let provider = StaticCredentialProvider::new().with_credential(
+            "oauth",
+            ClientCredential::ClientCredentials {
+                client_id: "client".to_string(),
+                client_secret: "secret".to_string(),
+            },
+        );
+        let authentication = ClientAuthentication::new(&card, Arc::new(provider)).unwrap();
+        for (transport, addr) in handle.local_addrs() {
+            let client = A2AClient::new_with_authentication(
+                transport,
+                format!("http://localhost:{}", addr.port()),
+                Some(authentication.clone()),
+            )
+            .await
+            .unwrap();
+            assert_eq!(who_am_i(&client).await, "service");
+            assert_eq!(who_am_i(&client).await, "service");
+        }
+        assert_eq!(issued.load(Ordering::SeqCst), 1);
+
+        // tokens about to expire are refreshed before use
+        let authentication = authentication.with_refresh_skew(Duration::from_secs(7200));
+        for (transport, addr) in handle.local_addrs() {
+            let client = A2AClient::new_with_authentication(
+                transport,
+                format!("http://localhost:{}", addr.port()),
+                Some(authentication.clone()),
+            )
+            .await
+            .unwrap();
+            let before = issued.load(Ordering::SeqCst);
+            assert_eq!(who_am_i(&client).await, "service");
+            assert_eq!(issued.load(Ordering::SeqCst), before + 1);
+        }
+        handle.shutdown().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn should_present_first_satisfiable_requirement() {
+        let card = card(
+            json!([{ "oauth": ["agent:write"] }, { "apiKey": [] }]),
+            "http://127.0.0.1:9/token",
+        );
+        let agent_builder = AgentBuilder::new(common::WhoAmIHandler)
+            .with_name("secure")
+            .with_card(card.clone())
+            .with_authenticator(authenticator())
+            .with_json_rpc_server("[::]:0".parse().unwrap());
+        #[cfg(feature = "grpc")]
+        let agent_builder = agent_builder.with_grpc_server("[::]:0".parse().unwrap());
+        let agent = agent_builder.build().expect("failed to build agent");
+        let handle = agent.start_server().await.expect("failed to start server");
+
+        let provider = StaticCredentialProvider::new()
+            .with_credential("apiKey", ClientCredential::ApiKey("key-1".to_string()));
+        let authentication = ClientAuthentication::new(&card, Arc::new(provider)).unwrap();
+        for (transport, addr) in handle.local_addrs() {
+            let client = A2AClient::new_with_authentication(
+                transport,
+                format!("http://localhost:{}", addr.port()),
+                Some(authentication.clone()),
+            )
+            .await
+            .unwrap();
+            assert_eq!(who_am_i(&client).await, "alice");
+        }
+        handle.shutdown().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn should_cache_tokens_per_client() {
+        let (token_url, issued) = serve_tokens(3600).await;
+        let card = card(json!([{ "oauth": ["agent:write"] }]), &token_url);
+        let provider = Arc::new(SwitchingProvider::default());
+        let authentication = ClientAuthentication::new(&card, provider.clone()).unwrap();
+
+        let a = authentication.credentials().await.unwrap();
+        provider.0.store(true, Ordering::SeqCst);
+        let b = authentication.credentials().await.unwrap();
+        assert_ne!(a.headers, b.headers);
+        assert_eq!(issued.load(Ordering::SeqCst), 2);
+
+        provider.0.store(false, Ordering::SeqCst);
+        assert_eq!(
+            authentication.credentials().await.unwrap().headers,
+            a.headers
+        );
+        assert_eq!(issued.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn should_keep_tokens_with_unbounded_lifetimes() {
+        let (token_url, issued) = serve_tokens(u64::MAX).await;
+        let card = card(json!([{ "oauth": ["agent:write"] }]), &token_url);
+        let provider = StaticCredentialProvider::new().with_credential(
+            "oauth",
+            ClientCredential::ClientCredentials {
+                client_id: "client".to_string(),
+                client_secret: "secret".to_string(),
+            },
+        );
+        let authentication = ClientAuthentication::new(&card, Arc::new(provider)).unwrap();
+
+        let first = authentication.credentials().await.unwrap();
+        assert_eq!(
+            authentication.credentials().await.unwrap().headers,
+            first.headers
+        );
+        assert_eq!(issued.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn should_only_send_secrets_to_pinned_token_urls() {
+        let (token_url, issued) = serve_tokens(3600).await;
+        let (spoofed_url, spoofed) = serve_tokens(3600).await;
+        let provider = StaticCredentialProvider::new()
+            .with_credential(
+                "oauth",
+                ClientCredential::ClientCredentials {
+                    client_id: "client".to_string(),
+                    client_secret: "secret".to_string(),
+                },
+            )
+            .with_token_url("oauth", &token_url);
+        let provider = Arc::new(provider);
+
+        let spoofed_card = card(json!([{ "oauth": ["agent:write"] }]), &spoofed_url);
+        let authentication = ClientAuthentication::new(&spoofed_card, provider.clone()).unwrap();
+        let res = authentication.credentials().await;
+        assert!(
+            matches!(&res, Err(CredentialError::UntrustedTokenUrl(url)) if url == &spoofed_url),
+            "{res:?}"
+        );
+        assert_eq!(spoofed.load(Ordering::SeqCst), 0);
+
+        let card = card(json!([{ "oauth": ["agent:write"] }]), &token_url);
+        let authentication = ClientAuthentication::new(&card, provider).unwrap();
+        authentication.credentials().await.unwrap();
+        assert_eq!(issued.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,171 @@
+#[cfg(test)]
+#[cfg(feature = "agent")]
+mod client_interceptor {
+    use async_trait::async_trait;
+    use ra2a::agent::{
+        AgentBuilder, AgentServerHandle, Middleware, Next, NoopAgentHandler, RequestContext,
+    };
+    use ra2a::client::{A2AClient, A2AClientConfig, ClientCall, ClientInterceptor, ClientNext};
+    use ra2a::core::message::{Message, SendMessageRequest};
+    use ra2a::core::task::{GetTaskRequest, Task};
+    use ra2a::core::util::Object;
+    use ra2a::core::{A2A, A2AError, A2ARequest, A2AResponse};
+    use serde_json::json;
+    use std::sync::{Arc, Mutex};
+
+    const EXTENSIONS_HEADER: &str = "x-a2a-extensions";
+
+    /// Extensions header and message metadata of a call.
+    type Captured = (Option<String>, Option<Object>);
+
+    /// Records the calls the agent serves.
+    #[derive(Debug, Default)]
+    struct Capture {
+        calls: Arc<Mutex<Vec<Captured>>>,
+    }
+
+    #[async_trait]
+    impl Middleware for Capture {
+        async fn handle(
+            &self,
+            context: &RequestContext,
+            request: A2ARequest,
+            next: Next<'_>,
+        ) -> Result<A2AResponse, A2AError> {
+            let header = context
+                .headers
+                .get(EXTENSIONS_HEADER)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string);
+            let metadata = match &request {
+                A2ARequest::SendMessage(request) => request.metadata.clone(),
+                _ => None,
+            };
+            self.calls.lock().unwrap().push((header, metadata));
+            next.run(context, request).await
+        }
+    }
+
+    /// Activates an extension and tags outgoing messages.
+    #[derive(Debug)]
+    struct Extensions;
+
+    #[async_trait]
+    impl ClientInterceptor for Extensions {
+        async fn intercept(
+            &self,
+            mut call: ClientCall,
+            next: ClientNext<'_>,
+        ) -> Result<A2AResponse, A2AError> {
+            call.headers.insert(
+                EXTENSIONS_HEADER,
+                "https://example.com/ext/tracing/v1".parse().unwrap(),
+            );
+            if let A2ARequest::SendMessage(request) = &mut call.request {
+                request
+                    .metadata
+                    .get_or_insert_with(Object::empty)
+                    .insert("tag", json!(call.transport.to_string()));
+            }
+            next.run(call).await
+        }
+    }
+
+    /// Logs calls in a shared log and answers task lookups from a local cache.
+    #[derive(Debug)]
+    struct Logging {
+        log: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl ClientInterceptor for Logging {
+        async fn intercept(
+            &self,
+            call: ClientCall,
+            next: ClientNext<'_>,
+        ) -> Result<A2AResponse, A2AError> {
+            let entry = format!("{} {}", call.transport, call.request.method());
+            self.log.lock().unwrap().push(entry);
+            if let A2ARequest::GetTask(request) = &call.request {
+                let mut task = Task::new();
+                task.id = request.id.clone();
+                return Ok(A2AResponse::GetTask(task));
+            }
+            next.run(call).await
+        }
+    }
+
+    async fn start(capture: Capture) -> AgentServerHandle {
+        let agent_builder = AgentBuilder::new(NoopAgentHandler)
+            .with_name("intercepted")
+            .with_middleware(capture)
+            .with_json_rpc_server("[::]:0".parse().unwrap());
+        #[cfg(feature = "grpc")]
+        let agent_builder = agent_builder.with_grpc_server("[::]:0".parse().unwrap());
+        let agent = agent_builder.build().expect("failed to build agent");
+        agent.start_server().await.expect("failed to start server")
+    }
+
+    #[tokio::test]
+    async fn should_intercept_calls_on_every_transport() {
+        let capture = Capture::default();
+        let calls = capture.calls.clone();
+        let handle = start(capture).await;
+        let log = Arc::new(Mutex::new(vec![]));
+
+        for (transport, addr) in handle.local_addrs() {
+            let config = A2AClientConfig::new()
+                .with_interceptor(Logging { log: log.clone() })
+                .with_interceptor(Extensions);
+            let client = A2AClient::new_with_config(
+                transport,
+                format!("http://localhost:{}", addr.port()),
+                config,
+            )
+            .await
+            .unwrap();
+
+            client
+                .send_message(SendMessageRequest {
+                    message: Some(Message::new_simple("hello")),
+                    configuration: None,
+                    metadata: None,
+                })
+                .await
+                .unwrap();
+            let (header, metadata) = calls.lock().unwrap().pop().unwrap();
+            assert_eq!(
+                header.as_deref(),
+                Some("https://example.com/ext/tracing/v1"),
+                "{transport}"
+            );
+            assert_eq!(
+                metadata.unwrap().get_str("tag"),
+                Some(transport.to_string().as_str()),
+                "{transport}"
+            );
+
+            // answered by the interceptor without reaching the agent
+            let task = client
+                .get_task(GetTaskRequest {
+                    id: "cached".to_string(),
+                    history_length: None,
+                    metadata: None,
+                })
+                .await
+                .unwrap();
+            assert_eq!(task.id, "cached");
+            assert!(calls.lock().unwrap().is_empty(), "{transport}");
+
+            assert_eq!(
+                std::mem::take(&mut *log.lock().unwrap()),
+                [
+                    format!("{transport} message/send"),
+                    format!("{transport} tasks/get"),
+                ]
+            );
+        }
+
+        handle.shutdown().await.unwrap();
+    }
+}
@@ -0,0 +1,235 @@
+#[cfg(test)]
+#[cfg(feature = "agent")]
+mod client_resilience {
+    use async_trait::async_trait;
+    use ra2a::agent::{
+        A2AAgentError, AgentBuilder, AgentHandler, AgentServerHandle, Middleware, Next,
+        RequestContext,
+    };
+    use ra2a::client::A2AClient;
+    use ra2a::client::resilience::{CircuitBreaker, CircuitState, RetryPolicy};
+    use ra2a::core::message::{Message, SendMessageRequest, SendMessageResponsePayload};
+    use ra2a::core::task::{GetTaskRequest, Task, TaskStatus};
+    use ra2a::core::util::Object;
+    use ra2a::core::{A2A, A2AError, A2ARequest, A2AResponse, A2ATransportError, Transport};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    const TIMEOUT: Duration = Duration::from_millis(200);
+
+    /// Stalls the first `slow` calls past the client timeout, then answers task lookups.
+    #[derive(Debug)]
+    struct Flaky {
+        slow: usize,
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Middleware for Flaky {
+        async fn handle(
+            &self,
+            context: &RequestContext,
+            request: A2ARequest,
+            next: Next<'_>,
+        ) -> Result<A2AResponse, A2AError> {
+            if self.calls.fetch_add(1, Ordering::SeqCst) < self.slow {
+                tokio::time::sleep(TIMEOUT * 5).await;
+            }
+            if let A2ARequest::GetTask(request) = &request {
+                let mut task = Task::new();
+                task.id = request.id.clone();
+                return Ok(A2AResponse::GetTask(task));
+            }
+            next.run(context, request).await
+        }
+    }
+
+    /// Creates a task for every message it handles, counting them.
+    #[derive(Debug, Default)]
+    struct CountingHandler(Arc<AtomicUsize>);
+
+    #[async_trait]
+    impl AgentHandler for CountingHandler {
+        async fn handle_message(
+            &self,
+            _context: &RequestContext,
+            message: Message,
+            _metadata: Option<Object>,
+            mut task: Task,
+        ) -> Result<SendMessageResponsePayload, A2AAgentError> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            task.history.push(message);
+            task.status = Some(TaskStatus::default_submitted());
+            Ok(SendMessageResponsePayload::Task(task))
+        }
+    }
+
+    async fn start(slow: usize) -> (AgentServerHandle, Arc<AtomicUsize>) {
+        start_counting(slow, Arc::default()).await
+    }
+
+    async fn start_counting(
+        slow: usize,
+        handled: Arc<AtomicUsize>,
+    ) -> (AgentServerHandle, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let agent_builder = AgentBuilder::new(CountingHandler(handled))
+            .with_name("flaky")
+            .with_middleware(Flaky {
+                slow,
+                calls: calls.clone(),
+            })
+            .with_json_rpc_server("[::]:0".parse().unwrap());
+        #[cfg(feature = "grpc")]
+        let agent_builder = agent_builder.with_grpc_server("[::]:0".parse().unwrap());
+        let agent = agent_builder.build().expect("failed to build agent");
+        let handle = agent.start_server().await.expect("failed to start server");
+        (handle, calls)
+    }
+
+    fn url(handle: &AgentServerHandle, transport: Transport) -> String {
+        let (_, addr) = handle
+            .local_addrs()
+            .into_iter()
+            .find(|(t, _)| *t == transport)
+            .expect("transport not served");
+        format!("http://localhost:{}", addr.port())
+    }
+
+    fn get_task(id: &str) -> GetTaskRequest {
+        GetTaskRequest {
+            id: id.to_string(),
+            history_length: None,
+            metadata: None,
+        }
+    }
+
+    fn retry() -> RetryPolicy {
+        RetryPolicy::new(3).with_base_delay(Duration::from_millis(10))
+    }
+
+    fn is_timeout(e: &A2AError) -> bool {
+        matches!(e, A2AError::Transport(A2ATransportError::Timeout(_)))
+    }
+
+    #[tokio::test]
+    async fn should_retry_idempotent_calls_that_time_out() {
+        for transport in [
+            Transport::JsonRpc,
+            #[cfg(feature = "grpc")]
+            Transport::Grpc,
+        ] {
+            let (handle, calls) = start(2).await;
+            let client = A2AClient::builder(transport, url(&handle, transport))
+                .with_timeout(TIMEOUT)
+                .with_retry(retry())
+                .build()
+                .await
+                .expect("failed to create client");
+
+            let task = client.get_task(get_task("t1")).await.unwrap();
+            assert_eq!(task.id, "t1", "{transport}");
+            assert_eq!(calls.load(Ordering::SeqCst), 3, "{transport}");
+            handle.shutdown().await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn should_only_retry_messages_with_an_id() {
+        let handled = Arc::new(AtomicUsize::new(0));
+        let (handle, calls) = start_counting(2, handled.clone()).await;
+        let url = url(&handle, Transport::JsonRpc);
+        let client = A2AClient::builder(Transport::JsonRpc, &url)
+            .with_timeout(TIMEOUT)
+            .with_retry(retry())
+            .build()
+            .await
+            .expect("failed to create client");
+
+        let mut request = SendMessageRequest {
+            message: Some(Message::new_simple("hello")),
+            configuration: None,
+            metadata: None,
+        };
+        let e = client.send_message(request.clone()).await.unwrap_err();
+        assert!(is_timeout(&e), "{e:?}");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        if let Some(message) = &mut request.message {
+            message.message_id = "m1".to_string();
+        }
+        let first = client.send_message(request.clone()).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        // a resend is answered without handling the message again
+        tokio::time::sleep(TIMEOUT * 6).await;
+        let before = handled.load(Ordering::SeqCst);
+        let again = client.send_message(request).await.unwrap();
+        assert_eq!(again, first);
+        assert_eq!(handled.load(Ordering::SeqCst), before);
+        handle.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_fail_fast_while_circuit_is_open() {
+        let (handle, calls) = start(2).await;
+        let url = url(&handle, Transport::JsonRpc);
+        let breaker = CircuitBreaker::new(2, Duration::from_millis(500));
+        let client = A2AClient::builder(Transport::JsonRpc, &url)
+            .with_timeout(TIMEOUT)
+            .with_circuit_breaker(breaker.clone())
+            .build()
+            .await
+            .expect("failed to create client");
+
+        for _ in 0..2 {
+            let e = client.get_task(get_task("t1")).await.unwrap_err();
+            assert!(is_timeout(&e), "{e:?}");
+        }
+        assert_eq!(breaker.state(&url), CircuitState::Open);
+
+        let e = client.get_task(get_task("t1")).await.unwrap_err();
+        assert!(
+            matches!(e, A2AError::Transport(A2ATransportError::CircuitOpen(_))),
+            "{e:?}"
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        tokio::time::sleep(Duration::from_millis(600)).await;
+        assert_eq!(breaker.state(&url), CircuitState::HalfOpen);
+        let task = client.get_task(get_task("t1")).await.unwrap();
+        assert_eq!(task.id, "t1");
+        assert_eq!(breaker.state(&url), CircuitState::Closed);
+        handle.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_reopen_circuit_when_trial_call_is_cancelled() {
+        let (handle, calls) = start(3).await;
+        let url = url(&handle, Transport::JsonRpc);
+        let breaker = CircuitBreaker::new(2, Duration::from_millis(500));
+        let client = A2AClient::builder(Transport::JsonRpc, &url)
+            .with_timeout(TIMEOUT)
+            .with_circuit_breaker(breaker.clone())
+            .build()
+            .await
+            .expect("failed to create client");
+
+        for _ in 0..2 {
+            let e = client.get_task(get_task("t1")).await.unwrap_err();
+            assert!(is_timeout(&e), "{e:?}");
+        }
+        tokio::time::sleep(Duration::from_millis(600)).await;
+        let trial = tokio::time::timeout(TIMEOUT / 4, client.get_task(get_task("t1"))).await;
+        assert!(trial.is_err());
+        assert_eq!(breaker.state(&url), CircuitState::Open);
+
+        tokio::time::sleep(Duration::from_millis(600)).await;
+        let task = client.get_task(get_task("t1")).await.unwrap();
+        assert_eq!(task.id, "t1");
+        assert_eq!(breaker.state(&url), CircuitState::Closed);
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+        handle.shutdown().await.unwrap();
+    }
+}
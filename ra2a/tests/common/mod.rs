@@ -0,0 +1,100 @@
+//! Fixtures shared by the integration tests, each test crate uses a part of them.
+#![allow(dead_code)]
+
+#[cfg(feature = "agent")]
+use ra2a::agent::{A2AAgentError, AgentHandler, RequestContext};
+use ra2a::core::agent::AgentCard;
+use ra2a::core::message::{Message, SendMessageResponse, SendMessageResponsePayload};
+use ra2a::core::task::Task;
+#[cfg(feature = "agent")]
+use ra2a::core::util::Object;
+use serde_json::{Value, json};
+
+/// An agent card under construction, as the JSON it is served as.
+#[derive(Debug, Clone)]
+pub struct CardFixture(Value);
+
+/// A card named `name` without security, skills or signatures.
+pub fn card(name: &str) -> CardFixture {
+    CardFixture(json!({
+        "protocolVersion": "0.3.0",
+        "name": name,
+        "description": "",
+        "url": "",
+        "version": "1.0.0",
+        "securitySchemes": {},
+        "security": [],
+        "defaultInputModes": [],
+        "defaultOutputModes": [],
+        "skills": [],
+        "signatures": []
+    }))
+}
+
+/// A skill named after its id, open to every caller of the agent.
+pub fn skill(id: &str) -> Value {
+    json!({ "id": id, "name": id, "description": "", "tags": [] })
+}
+
+impl CardFixture {
+    /// Sets the card field `key`, as named in JSON.
+    pub fn with(mut self, key: &str, value: impl Into<Value>) -> Self {
+        self.0[key] = value.into();
+        self
+    }
+
+    /// Declares the security scheme `name`.
+    pub fn with_scheme(mut self, name: &str, scheme: Value) -> Self {
+        self.0["securitySchemes"][name] = scheme;
+        self
+    }
+
+    /// Replaces the security requirements of the agent.
+    pub fn with_security(self, security: Value) -> Self {
+        self.with("security", security)
+    }
+
+    /// Appends skills, see [`skill`].
+    pub fn with_skills(mut self, skills: impl IntoIterator<Item = Value>) -> Self {
+        let list = self.0["skills"].as_array_mut().unwrap();
+        list.extend(skills);
+        self
+    }
+
+    pub fn build(self) -> AgentCard {
+        serde_json::from_value(self.0).expect("invalid card fixture")
+    }
+}
+
+/// Replies with the subject of the authenticated caller.
+#[cfg(feature = "agent")]
+#[derive(Debug, Default)]
+pub struct WhoAmIHandler;
+
+#[cfg(feature = "agent")]
+#[async_trait::async_trait]
+impl AgentHandler for WhoAmIHandler {
+    async fn handle_message(
+        &self,
+        context: &RequestContext,
+        _message: Message,
+        _metadata: Option<Object>,
+        _task: Task,
+    ) -> Result<SendMessageResponsePayload, A2AAgentError> {
+        let subject = context
+            .principal
+            .as_ref()
+            .map_or("anonymous", |p| p.subject.as_str());
+        Ok(SendMessageResponsePayload::Message(Message::new_simple(
+            subject,
+        )))
+    }
+}
+
+/// The task a message was answered with.
+pub fn task(res: SendMessageResponse) -> Task {
+    match res.payload.unwrap() {
+        SendMessageResponsePayload::Task(task) => task,
+        _ => panic!("expected task"),
+    }
+}
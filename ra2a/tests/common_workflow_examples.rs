@@ -2,7 +2,7 @@
 #[cfg(feature = "agent")]
 mod basic_execution {
     use indoc::indoc;
-    use ra2a::agent::{A2AAgentError, AgentBuilder, AgentHandler};
+    use ra2a::agent::{A2AAgentError, AgentBuilder, AgentHandler, RequestContext};
     use ra2a::client::A2AClient;
     use ra2a::core::A2A;
     use ra2a::core::artifact::Artifact;
@@ -19,6 +19,7 @@ mod basic_execution {
     impl AgentHandler for TestHandler {
         async fn handle_message(
             &self,
+            _context: &RequestContext,
             mut message: Message,
             _metadata: Option<Object>,
             mut task: Task,
@@ -60,6 +61,7 @@ mod basic_execution {
     impl AgentHandler for TestNoTaskHandler {
         async fn handle_message(
             &self,
+            _context: &RequestContext,
             _message: Message,
             _metadata: Option<Object>,
             _task: Task,
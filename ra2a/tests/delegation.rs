@@ -0,0 +1,213 @@
+#[cfg(test)]
+#[cfg(feature = "agent")]
+mod delegation {
+    use async_trait::async_trait;
+    use ra2a::agent::{
+        A2AAgentError, A2ADelegate, AgentBuilder, AgentHandler, AgentServerHandle, RequestContext,
+    };
+    use ra2a::client::A2AClient;
+    use ra2a::core::agent::AgentCard;
+    use ra2a::core::delegation::{DELEGATION_METADATA_KEY, DelegationChain};
+    use ra2a::core::message::SendMessageResponse;
+    use ra2a::core::message::{Message, SendMessageRequest, SendMessageResponsePayload};
+    use ra2a::core::part::PartBase;
+    use ra2a::core::task::{GetTaskRequest, Task, TaskStatus};
+    use ra2a::core::util::Object;
+    use ra2a::core::{A2A, A2AError, A2AProtocolError, Transport};
+    use ra2a::server::A2AServer;
+    use std::sync::Arc;
+    use tokio::sync::OnceCell;
+
+    /// Forwards every message to the next agent and replies with what it answered.
+    #[derive(Debug, Clone, Default)]
+    struct ForwardingHandler {
+        next: Arc<OnceCell<String>>,
+    }
+
+    #[async_trait]
+    impl AgentHandler for ForwardingHandler {
+        async fn handle_message(
+            &self,
+            _context: &RequestContext,
+            message: Message,
+            _metadata: Option<Object>,
+            mut task: Task,
+        ) -> Result<SendMessageResponsePayload, A2AAgentError> {
+            let client = A2AClient::new(Transport::JsonRpc, self.next.get().unwrap())
+                .await
+                .unwrap();
+            let reply = match client.send_message(request(message_text(&message))).await {
+                Ok(res) => match res.payload.unwrap() {
+                    SendMessageResponsePayload::Task(task) => {
+                        message_text(task.history.last().unwrap())
+                    }
+                    SendMessageResponsePayload::Message(message) => message_text(&message),
+                },
+                Err(A2AError::Protocol(e)) => format!("{:?}", e.code()),
+                Err(e) => panic!("unexpected error {e:?}"),
+            };
+            task.history.push(message);
+            task.history.push(Message::new_simple(reply));
+            task.status = Some(TaskStatus::default_submitted());
+            Ok(SendMessageResponsePayload::Task(task))
+        }
+    }
+
+    /// Relays every call to the agent at a url, like a gateway would.
+    #[derive(Debug, Default)]
+    struct Relay {
+        next: OnceCell<A2AClient>,
+    }
+
+    #[async_trait]
+    impl A2A for Relay {
+        async fn send_message(
+            &self,
+            request: SendMessageRequest,
+        ) -> Result<SendMessageResponse, A2AError> {
+            self.next.get().unwrap().send_message(request).await
+        }
+
+        async fn get_task(&self, request: GetTaskRequest) -> Result<Task, A2AError> {
+            self.next.get().unwrap().get_task(request).await
+        }
+
+        async fn get_authenticated_extended_card(&self) -> Result<AgentCard, A2AError> {
+            self.next
+                .get()
+                .unwrap()
+                .get_authenticated_extended_card()
+                .await
+        }
+    }
+
+    fn request(text: String) -> SendMessageRequest {
+        SendMessageRequest {
+            message: Some(Message::new_simple(text)),
+            configuration: None,
+            metadata: None,
+        }
+    }
+
+    fn message_text(message: &Message) -> String {
+        match &message.parts[0].part {
+            Some(PartBase::Text(text)) => text.clone(),
+            _ => panic!("expected text part"),
+        }
+    }
+
+    async fn start(name: &str, handler: ForwardingHandler) -> (AgentServerHandle, String) {
+        let agent_builder = AgentBuilder::new(handler)
+            .with_name(name)
+            .with_max_delegation_depth(2)
+            .with_json_rpc_server("[::]:0".parse().unwrap());
+        #[cfg(feature = "grpc")]
+        let agent_builder = agent_builder.with_grpc_server("[::]:0".parse().unwrap());
+        let agent = agent_builder.build().expect("failed to build agent");
+        let handle = agent.start_server().await.expect("failed to start server");
+        let url = format!(
+            "http://localhost:{}",
+            handle.local_addr(Transport::JsonRpc).unwrap().port()
+        );
+        (handle, url)
+    }
+
+    #[tokio::test]
+    async fn should_reject_delegation_loop() {
+        let a = ForwardingHandler::default();
+        let b = ForwardingHandler::default();
+        let (a_handle, a_url) = start("a", a.clone()).await;
+        let (b_handle, b_url) = start("b", b.clone()).await;
+        a.next.set(b_url).unwrap();
+        b.next.set(a_url.clone()).unwrap();
+
+        // a -> b -> a is rejected by a, and b reports the rejection back
+        let client = A2AClient::new(Transport::JsonRpc, a_url).await.unwrap();
+        let res = client.send_message(request("ping".into())).await.unwrap();
+        let task = match res.payload.unwrap() {
+            SendMessageResponsePayload::Task(task) => task,
+            _ => panic!("expected task"),
+        };
+        assert_eq!(message_text(task.history.last().unwrap()), "DelegationLoop");
+
+        a_handle.shutdown().await.unwrap();
+        b_handle.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_reject_requests_past_max_depth() {
+        let (handle, _) = start("a", ForwardingHandler::default()).await;
+        let chain = DelegationChain::default()
+            .with_agent("x")
+            .with_agent("y")
+            .with_agent("z");
+
+        for (transport, addr) in handle.local_addrs() {
+            let url = format!("http://localhost:{}", addr.port());
+            let client = A2AClient::new(transport, url).await.unwrap();
+            let mut request = request("ping".into());
+            let metadata = request.metadata.get_or_insert_with(Object::empty);
+            metadata.insert(
+                DELEGATION_METADATA_KEY,
+                serde_json::to_value(&chain).unwrap(),
+            );
+            let err = client.send_message(request).await.unwrap_err();
+            match err {
+                A2AError::Protocol(A2AProtocolError::DelegationDepthExceeded {
+                    depth,
+                    max_depth,
+                    ..
+                }) => {
+                    assert_eq!(depth, 3);
+                    assert_eq!(max_depth, 2);
+                }
+                e => panic!("expected depth exceeded, got {e:?}"),
+            }
+        }
+
+        handle.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_count_hops_through_unnamed_agents() {
+        let chain = DelegationChain {
+            agents: vec!["x".to_string()],
+            depth: 3,
+        };
+        assert_eq!(chain.with_agent("y").depth, 4);
+        assert_eq!(chain.with_hop().with_agent("y").depth, 5);
+
+        // a relay forwarding to itself is stopped by the depth limit
+        let relay = Arc::new(Relay::default());
+        let delegate = A2ADelegate::forwarding(relay.clone()).with_max_delegation_depth(3);
+        let handle = A2AServer::new(delegate)
+            .with_jsonrpc("[::]:0".parse().unwrap())
+            .start()
+            .await
+            .expect("failed to start relay");
+        let url = format!(
+            "http://localhost:{}",
+            handle.local_addr(Transport::JsonRpc).unwrap().port()
+        );
+        let client = A2AClient::new(Transport::JsonRpc, url).await.unwrap();
+        relay.next.set(client.clone()).unwrap();
+
+        let err = client
+            .send_message(request("ping".into()))
+            .await
+            .unwrap_err();
+        match err {
+            A2AError::Protocol(A2AProtocolError::DelegationDepthExceeded {
+                depth,
+                max_depth,
+                ..
+            }) => {
+                assert_eq!(depth, 4);
+                assert_eq!(max_depth, 3);
+            }
+            e => panic!("expected depth exceeded, got {e:?}"),
+        }
+
+        handle.shutdown().await.unwrap();
+    }
+}
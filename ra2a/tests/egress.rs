@@ -0,0 +1,138 @@
+#[cfg(test)]
+mod egress {
+    use ra2a::core::part::File;
+    use ra2a::egress::{EgressClient, EgressError, FileResolver, UrlPolicy};
+    use std::error::Error;
+    use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Serves `/file` and redirects every other path to `location`.
+    async fn serve(location: &'static str) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buf = [0; 1024];
+                let n = stream.read(&mut buf).await.unwrap_or_default();
+                let request = String::from_utf8_lossy(&buf[..n]);
+                let response = if request.starts_with("GET /file ") {
+                    "HTTP/1.1 200 OK\r\ncontent-length: 5\r\nconnection: close\r\n\r\nhello"
+                        .to_string()
+                } else {
+                    format!(
+                        "HTTP/1.1 302 Found\r\nlocation: {location}\r\ncontent-length: 0\r\nconnection: close\r\n\r\n"
+                    )
+                };
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        addr
+    }
+
+    fn is_address_not_allowed(e: &EgressError) -> bool {
+        let mut source: Option<&dyn Error> = Some(e);
+        while let Some(e) = source {
+            if let Some(EgressError::AddressNotAllowed { .. }) = e.downcast_ref::<EgressError>() {
+                return true;
+            }
+            source = e.source();
+        }
+        false
+    }
+
+    #[tokio::test]
+    async fn should_check_schemes_hosts_and_addresses() {
+        let policy = UrlPolicy::new();
+        assert!(policy.check_url("https://example.com/hook").is_ok());
+        assert!(matches!(
+            policy.check_url("http://example.com/hook"),
+            Err(EgressError::SchemeNotAllowed(_))
+        ));
+        assert!(matches!(
+            policy.check_url("file:///etc/passwd"),
+            Err(EgressError::SchemeNotAllowed(_))
+        ));
+        for url in [
+            "https://127.0.0.1/",
+            "https://10.1.2.3/",
+            "https://169.254.169.254/latest/meta-data",
+            "https://[::1]/",
+            "https://[fe80::1]/",
+            "https://[fd00::1]/",
+            "https://[::ffff:192.168.0.1]/",
+            "https://0.0.0.0/",
+        ] {
+            let err = policy.check_url(url).unwrap_err();
+            assert!(is_address_not_allowed(&err), "{url}: {err:?}");
+        }
+        assert!(policy.check_url("https://93.184.215.14/").is_ok());
+
+        // host names are checked once resolved
+        assert!(policy.check_url("https://localhost/").is_ok());
+        let err = policy.check("https://localhost/").await.unwrap_err();
+        assert!(is_address_not_allowed(&err), "{err:?}");
+
+        let policy = UrlPolicy::new()
+            .with_allowed_host("*.example.com")
+            .with_denied_host("internal.example.com");
+        assert!(policy.check_url("https://hooks.example.com/").is_ok());
+        assert!(policy.check_url("https://HOOKS.example.com./").is_ok());
+        for url in [
+            "https://example.com/",
+            "https://internal.example.com/",
+            "https://badexample.com/",
+        ] {
+            assert!(
+                matches!(policy.check_url(url), Err(EgressError::HostNotAllowed(_))),
+                "{url}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn should_resolve_files_within_policy() {
+        let addr = serve("http://10.0.0.1/file").await;
+        let localhost = format!("http://localhost:{}", addr.port());
+        let policy = UrlPolicy::new().with_scheme("http");
+
+        let resolver = FileResolver::new(EgressClient::new(policy.clone()).unwrap());
+        let content = resolver
+            .resolve(&File::FileWithBytes(b"inline".to_vec()))
+            .await
+            .unwrap();
+        assert_eq!(content, b"inline");
+        let err = resolver
+            .resolve(&File::FileWithUri(format!("{localhost}/file")))
+            .await
+            .unwrap_err();
+        assert!(is_address_not_allowed(&err), "{err:?}");
+
+        let policy = policy
+            .with_allowed_addr(IpAddr::V4(Ipv4Addr::LOCALHOST))
+            .with_allowed_addr("::1".parse().unwrap());
+        let resolver = FileResolver::new(EgressClient::new(policy).unwrap());
+        let content = resolver
+            .resolve(&File::FileWithUri(format!("{localhost}/file")))
+            .await
+            .unwrap();
+        assert_eq!(content, b"hello");
+
+        // redirects are checked too
+        let err = resolver
+            .resolve(&File::FileWithUri(format!("{localhost}/redirect")))
+            .await
+            .unwrap_err();
+        assert!(is_address_not_allowed(&err), "{err:?}");
+
+        let err = resolver
+            .with_max_size(4)
+            .resolve(&File::FileWithUri(format!("{localhost}/file")))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, EgressError::TooLarge { limit: 4, .. }),
+            "{err:?}"
+        );
+    }
+}
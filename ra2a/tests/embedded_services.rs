@@ -0,0 +1,96 @@
+#[cfg(test)]
+#[cfg(feature = "agent")]
+mod embedded_services {
+    use hyper::body::Incoming;
+    use jsonrpsee::server::{HttpBody, HttpResponse};
+    use ra2a::agent::{A2ADelegate, NoopAgentHandler};
+    use ra2a::client::A2AClient;
+    use ra2a::core::message::{Message, SendMessageRequest, SendMessageResponsePayload};
+    use ra2a::core::{A2A, Transport};
+    use ra2a::server::jsonrpc::A2AJsonRpcService;
+    use std::convert::Infallible;
+    use std::sync::Arc;
+    use tokio::net::TcpListener;
+    use tower::ServiceExt;
+
+    const PREFIX: &str = "/agents/echo";
+
+    fn delegate() -> A2ADelegate {
+        A2ADelegate::new(Arc::new(NoopAgentHandler)).with_name("embedded")
+    }
+
+    async fn echo(client: &A2AClient) -> bool {
+        let res = client
+            .send_message(SendMessageRequest {
+                message: Some(Message::new_simple("hello")),
+                configuration: None,
+                metadata: None,
+            })
+            .await
+            .unwrap();
+        matches!(res.payload, Some(SendMessageResponsePayload::Message(_)))
+    }
+
+    #[tokio::test]
+    async fn should_mount_json_rpc_under_a_prefix() {
+        let a2a = A2AJsonRpcService::new(delegate()).unwrap();
+        // a router of the caller, serving other routes next to the agent
+        let router = tower::service_fn(move |request: http::Request<Incoming>| {
+            let a2a = a2a.clone();
+            async move {
+                match request.uri().path().starts_with(PREFIX) {
+                    true => a2a.oneshot(request).await,
+                    false => {
+                        let mut response = HttpResponse::new(HttpBody::from("not found"));
+                        *response.status_mut() = http::StatusCode::NOT_FOUND;
+                        Ok::<_, Infallible>(response)
+                    }
+                }
+            }
+        });
+        let listener = TcpListener::bind("[::]:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                tokio::spawn(jsonrpsee::server::serve(stream, router.clone()));
+            }
+        });
+
+        let url = format!("http://localhost:{}{PREFIX}", addr.port());
+        let client = A2AClient::new(Transport::JsonRpc, url).await.unwrap();
+        assert!(echo(&client).await);
+
+        let url = format!("http://localhost:{}/other", addr.port());
+        let client = A2AClient::new(Transport::JsonRpc, url).await.unwrap();
+        let res = client
+            .send_message(SendMessageRequest {
+                message: Some(Message::new_simple("hello")),
+                configuration: None,
+                metadata: None,
+            })
+            .await;
+        assert!(res.is_err());
+    }
+
+    #[cfg(feature = "grpc")]
+    #[tokio::test]
+    async fn should_add_grpc_to_a_tonic_server() {
+        use ra2a::server::grpc::A2AGrpc;
+        use tonic::codegen::tokio_stream::wrappers::TcpListenerStream;
+
+        let a2a = A2AGrpc::new(delegate());
+        let listener = TcpListener::bind("[::]:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .timeout(std::time::Duration::from_secs(5))
+                .add_service(a2a)
+                .serve_with_incoming(TcpListenerStream::new(listener)),
+        );
+
+        let url = format!("http://localhost:{}", addr.port());
+        let client = A2AClient::new(Transport::Grpc, url).await.unwrap();
+        assert!(echo(&client).await);
+    }
+}
@@ -0,0 +1,154 @@
+mod common;
+
+#[cfg(test)]
+#[cfg(feature = "agent")]
+mod extended_card {
+    use crate::common;
+    use ra2a::agent::{AgentBuilder, NoopAgentHandler};
+    use ra2a::auth::{Principal, StaticAuthenticator};
+    use ra2a::client::auth::{ClientAuthentication, ClientCredential, StaticCredentialProvider};
+    use ra2a::client::{A2AClient, A2AClientConfig};
+    use ra2a::core::agent::AgentCard;
+    use ra2a::core::{A2A, A2AError, A2AProtocolError, A2ATransportError, Transport};
+    use serde_json::{Value, json};
+    use std::sync::Arc;
+
+    /// Card declaring a single api key scheme.
+    fn card(security: Value, skills: Value) -> AgentCard {
+        common::card("extended")
+            .with_scheme(
+                "apiKey",
+                json!({ "type": "apiKey", "in": "header", "name": "X-API-Key" }),
+            )
+            .with_security(security)
+            .with("defaultInputModes", json!(["text/plain"]))
+            .with("defaultOutputModes", json!(["text/plain"]))
+            .with("skills", skills)
+            .with("supportsAuthenticatedExtendedCard", true)
+            .build()
+    }
+
+    fn extended_card() -> AgentCard {
+        card(
+            json!([]),
+            json!([{
+                "id": "admin",
+                "name": "Admin",
+                "description": "Only listed for authenticated callers",
+                "tags": []
+            }]),
+        )
+    }
+
+    async fn start(extended: Option<AgentCard>) -> ra2a::agent::AgentServerHandle {
+        let mut agent_builder = AgentBuilder::new(NoopAgentHandler)
+            .with_name("extended")
+            .with_card(card(json!([]), json!([])))
+            .with_authenticator(
+                StaticAuthenticator::new().with_token("key-1", Principal::new("alice")),
+            )
+            .with_json_rpc_server("[::]:0".parse().unwrap());
+        #[cfg(feature = "grpc")]
+        {
+            agent_builder = agent_builder.with_grpc_server("[::]:0".parse().unwrap());
+        }
+        if let Some(card) = extended {
+            agent_builder = agent_builder.with_extended_card(card);
+        }
+        let agent = agent_builder.build().expect("failed to build agent");
+        agent.start_server().await.expect("failed to start server")
+    }
+
+    async fn client(transport: Transport, port: u16, key: Option<&str>) -> A2AClient {
+        let mut config = A2AClientConfig::new();
+        if let Some(key) = key {
+            let provider = StaticCredentialProvider::new()
+                .with_credential("apiKey", ClientCredential::ApiKey(key.to_string()));
+            let card = card(json!([{ "apiKey": [] }]), json!([]));
+            config = config
+                .with_authentication(ClientAuthentication::new(&card, Arc::new(provider)).unwrap());
+        }
+        A2AClient::new_with_config(transport, format!("http://localhost:{port}"), config)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn should_serve_extended_card_to_authenticated_callers() {
+        let handle = start(Some(extended_card())).await;
+        let port = handle.local_addr(Transport::JsonRpc).unwrap().port();
+        let url = format!("http://localhost:{port}/v1/card");
+
+        let http = reqwest::Client::new();
+        let res = http.get(&url).send().await.unwrap();
+        assert_eq!(res.status().as_u16(), 401);
+        let res = http
+            .get(&url)
+            .header("X-API-Key", "key-1")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status().as_u16(), 200);
+        assert_eq!(res.json::<AgentCard>().await.unwrap(), extended_card());
+
+        for (transport, addr) in handle.local_addrs() {
+            let card = client(transport, addr.port(), Some("key-1"))
+                .await
+                .get_authenticated_extended_card()
+                .await
+                .unwrap();
+            assert_eq!(card, extended_card(), "{transport}");
+
+            let err = client(transport, addr.port(), None)
+                .await
+                .get_authenticated_extended_card()
+                .await
+                .unwrap_err();
+            match err {
+                #[cfg(feature = "grpc")]
+                A2AError::Transport(A2ATransportError::Grcp(status)) => {
+                    assert_eq!(status.code(), tonic::Code::Unauthenticated)
+                }
+                A2AError::Transport(A2ATransportError::JsonRpc(_)) => {}
+                e => panic!("expected transport error, got {e:?}"),
+            }
+        }
+
+        handle.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_report_missing_extended_card() {
+        let handle = start(None).await;
+        let port = handle.local_addr(Transport::JsonRpc).unwrap().port();
+
+        let res = reqwest::Client::new()
+            .get(format!("http://localhost:{port}/v1/card"))
+            .header("X-API-Key", "key-1")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status().as_u16(), 404);
+        let res: Value = res.json().await.unwrap();
+        assert_eq!(res["code"], -32007, "{res}");
+
+        for (transport, addr) in handle.local_addrs() {
+            let err = client(transport, addr.port(), Some("key-1"))
+                .await
+                .get_authenticated_extended_card()
+                .await
+                .unwrap_err();
+            assert!(
+                matches!(
+                    err,
+                    A2AError::Protocol(
+                        A2AProtocolError::AuthenticatedExtendedCardNotConfigured { .. }
+                    )
+                ),
+                "{transport}: {err:?}"
+            );
+        }
+
+        handle.shutdown().await.unwrap();
+    }
+}
@@ -0,0 +1,299 @@
+mod common;
+
+#[cfg(test)]
+#[cfg(feature = "agent")]
+mod gateway {
+    use crate::common;
+    use async_trait::async_trait;
+    use ra2a::agent::{
+        A2AAgentError, A2ADelegate, AgentBuilder, AgentHandler, AgentServerHandle, RequestContext,
+    };
+    use ra2a::broker::{A2AGateway, AgentBroker, AgentReplica, GATEWAY_AGENT_METADATA_KEY};
+    use ra2a::client::A2AClient;
+    use ra2a::core::agent::AgentCard;
+    use ra2a::core::message::{Message, SendMessageRequest, SendMessageResponsePayload};
+    use ra2a::core::part::PartBase;
+    use ra2a::core::task::{GetTaskRequest, Task, TaskState, TaskStatus};
+    use ra2a::core::util::Object;
+    use ra2a::core::{A2A, A2AError, A2AProtocolError, Transport};
+    use ra2a::server::A2AServer;
+    use std::sync::Arc;
+
+    #[derive(Debug)]
+    struct NamedHandler(&'static str);
+
+    #[async_trait]
+    impl AgentHandler for NamedHandler {
+        async fn handle_message(
+            &self,
+            _context: &RequestContext,
+            mut message: Message,
+            _metadata: Option<Object>,
+            mut task: Task,
+        ) -> Result<SendMessageResponsePayload, A2AAgentError> {
+            // new tasks join the context of the message
+            if let (None, Some(context_id)) = (&message.task_id, &message.context_id) {
+                task.context_id = context_id.clone();
+            }
+            message.context_id = Some(task.context_id.clone());
+            let done =
+                matches!(&message.parts[0].part, Some(PartBase::Text(text)) if text == "bye");
+            task.history.push(message);
+            task.history.push(Message::new_simple(self.0));
+            task.status = Some(match done {
+                true => TaskStatus {
+                    state: TaskState::Completed.into(),
+                    message: None,
+                    timestamp: None,
+                },
+                false => TaskStatus::default_submitted(),
+            });
+            Ok(SendMessageResponsePayload::Task(task))
+        }
+    }
+
+    fn card(name: &str, skills: &[&str]) -> AgentCard {
+        common::card(name)
+            .with_skills(skills.iter().map(|id| common::skill(id)))
+            .build()
+    }
+
+    async fn start_backend(name: &'static str) -> (AgentServerHandle, AgentReplica) {
+        let agent = AgentBuilder::new(NamedHandler(name))
+            .with_name(name)
+            .with_json_rpc_server("[::]:0".parse().unwrap())
+            .build()
+            .expect("failed to build agent");
+        let handle = agent.start_server().await.expect("failed to start server");
+        let url = format!(
+            "http://localhost:{}",
+            handle.local_addr(Transport::JsonRpc).unwrap().port()
+        );
+        let replica = AgentReplica::connect(Transport::JsonRpc, url)
+            .await
+            .unwrap();
+        (handle, replica)
+    }
+
+    fn request_for(agent: &str) -> SendMessageRequest {
+        let metadata = serde_json::from_value::<Object>(
+            serde_json::json!({ GATEWAY_AGENT_METADATA_KEY: agent }),
+        )
+        .unwrap();
+        SendMessageRequest {
+            message: Some(Message::new_simple("hello there!")),
+            configuration: None,
+            metadata: Some(metadata),
+        }
+    }
+
+    fn last_text(task: &Task) -> String {
+        match &task.history.last().unwrap().parts[0].part {
+            Some(PartBase::Text(text)) => text.clone(),
+            _ => panic!("expected text part"),
+        }
+    }
+
+    #[tokio::test]
+    async fn should_route_tasks_through_gateway() {
+        let broker = AgentBroker::new();
+        let (weather_handle, weather) = start_backend("weather").await;
+        let (maps_handle, maps) = start_backend("maps").await;
+        broker.register("weather", weather.clone()).await;
+        broker.register("maps", maps).await;
+
+        let gateway = A2AGateway::new(broker, card("gateway", &[]));
+        gateway
+            .register_backend("weather", card("weather", &["forecast"]))
+            .await;
+        gateway
+            .register_backend("maps", card("maps", &["route", "forecast"]))
+            .await;
+
+        let skills: Vec<String> = gateway
+            .agent_card()
+            .await
+            .skills
+            .into_iter()
+            .map(|s| s.id)
+            .collect();
+        assert_eq!(skills, vec!["route", "forecast"]);
+
+        let server = A2AServer::new(A2ADelegate::forwarding(Arc::new(gateway.clone())))
+            .with_jsonrpc("[::]:0".parse().unwrap());
+        let handle = server.start().await.expect("failed to start gateway");
+        let client = A2AClient::new(
+            Transport::JsonRpc,
+            format!(
+                "http://localhost:{}",
+                handle.local_addr(Transport::JsonRpc).unwrap().port()
+            ),
+        )
+        .await
+        .unwrap();
+
+        for agent in ["weather", "maps"] {
+            let res = client.send_message(request_for(agent)).await.unwrap();
+            let task = match res.payload.unwrap() {
+                SendMessageResponsePayload::Task(task) => task,
+                _ => panic!("expected task"),
+            };
+            assert_eq!(last_text(&task), agent);
+
+            let route = gateway.resolve(&task.id).await.unwrap();
+            assert_eq!(route.agent, agent);
+            assert_ne!(route.task_id, task.id);
+
+            let got = client
+                .get_task(GetTaskRequest {
+                    id: task.id.clone(),
+                    history_length: None,
+                    metadata: None,
+                })
+                .await
+                .unwrap();
+            assert_eq!(got, task);
+
+            // follow-ups without routing metadata still reach the owner
+            let mut follow_up = Message::new_simple("and again");
+            follow_up.task_id = Some(task.id.clone());
+            let res = client
+                .send_message(SendMessageRequest {
+                    message: Some(follow_up),
+                    configuration: None,
+                    metadata: None,
+                })
+                .await
+                .unwrap();
+            match res.payload.unwrap() {
+                SendMessageResponsePayload::Task(follow_up) => {
+                    assert_eq!(follow_up.id, task.id);
+                    assert_eq!(last_text(&follow_up), agent);
+                }
+                _ => panic!("expected task"),
+            }
+        }
+
+        // backend ids are not valid gateway ids
+        let backend_task = weather
+            .client()
+            .send_message(request_for("weather"))
+            .await
+            .unwrap();
+        let backend_id = match backend_task.payload.unwrap() {
+            SendMessageResponsePayload::Task(task) => task.id,
+            _ => panic!("expected task"),
+        };
+        let err = gateway
+            .get_task(GetTaskRequest {
+                id: backend_id,
+                history_length: None,
+                metadata: None,
+            })
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                A2AError::Protocol(A2AProtocolError::TaskNotFound { .. })
+            ),
+            "got {err:?}"
+        );
+
+        handle.shutdown().await.unwrap();
+        weather_handle.shutdown().await.unwrap();
+        maps_handle.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_map_context_ids() {
+        let broker = AgentBroker::new();
+        let (weather_handle, weather) = start_backend("weather").await;
+        let (maps_handle, maps) = start_backend("maps").await;
+        broker.register("weather", weather).await;
+        broker.register("maps", maps).await;
+        let gateway = A2AGateway::new(broker, card("gateway", &[]));
+        gateway
+            .register_backend("weather", card("weather", &[]))
+            .await;
+        gateway.register_backend("maps", card("maps", &[])).await;
+
+        let first = common::task(gateway.send_message(request_for("weather")).await.unwrap());
+        let route = gateway.resolve(&first.id).await.unwrap();
+        let backend = gateway
+            .broker()
+            .get_task(
+                "weather",
+                GetTaskRequest {
+                    id: route.task_id,
+                    history_length: None,
+                    metadata: None,
+                },
+            )
+            .await
+            .unwrap();
+        assert_ne!(first.context_id, backend.context_id);
+        assert_eq!(
+            first.history[0].context_id.as_ref(),
+            Some(&first.context_id)
+        );
+
+        // a new task in the context stays with its backend, whatever the metadata says
+        let mut request = request_for("maps");
+        request.message.as_mut().unwrap().context_id = Some(first.context_id.clone());
+        let second = common::task(gateway.send_message(request).await.unwrap());
+        assert_ne!(second.id, first.id);
+        assert_eq!(second.context_id, first.context_id);
+        assert_eq!(last_text(&second), "weather");
+
+        // context ids the gateway did not issue never reach a backend
+        let mut request = request_for("maps");
+        request.message.as_mut().unwrap().context_id = Some(backend.context_id.clone());
+        let third = common::task(gateway.send_message(request).await.unwrap());
+        assert_eq!(last_text(&third), "maps");
+        assert_ne!(third.context_id, backend.context_id);
+        assert_ne!(third.context_id, first.context_id);
+
+        weather_handle.shutdown().await.unwrap();
+        maps_handle.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_forget_ids_of_finished_and_old_tasks() {
+        let broker = AgentBroker::new();
+        let (weather_handle, weather) = start_backend("weather").await;
+        broker.register("weather", weather).await;
+        let gateway = A2AGateway::new(broker, card("gateway", &[]))
+            .with_default_agent("weather")
+            .with_max_routes(2);
+        gateway
+            .register_backend("weather", card("weather", &[]))
+            .await;
+
+        let finished = common::task(gateway.send_message(request_for("weather")).await.unwrap());
+        let mut bye = Message::new_simple("bye");
+        bye.task_id = Some(finished.id.clone());
+        let res = gateway
+            .send_message(SendMessageRequest {
+                message: Some(bye),
+                configuration: None,
+                metadata: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(common::task(res).id, finished.id);
+        assert!(gateway.resolve(&finished.id).await.is_none());
+
+        let mut tasks = vec![];
+        for _ in 0..3 {
+            tasks.push(common::task(
+                gateway.send_message(request_for("weather")).await.unwrap(),
+            ));
+        }
+        assert!(gateway.resolve(&tasks[0].id).await.is_none());
+        assert!(gateway.resolve(&tasks[1].id).await.is_some());
+        assert!(gateway.resolve(&tasks[2].id).await.is_some());
+
+        weather_handle.shutdown().await.unwrap();
+    }
+}
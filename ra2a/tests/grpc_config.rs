@@ -0,0 +1,136 @@
+#[cfg(test)]
+#[cfg(all(feature = "agent", feature = "grpc"))]
+mod grpc_config {
+    use ra2a::agent::{AgentBuilder, AgentServerHandle, NoopAgentHandler};
+    use ra2a::client::{A2AClient, A2AClientConfig};
+    use ra2a::core::message::{Message, SendMessageRequest, SendMessageResponsePayload};
+    use ra2a::core::{A2A, A2AError, A2ATransportError, Transport};
+    use ra2a::grpc::{CompressionEncoding, GrpcConfig};
+    use std::time::Duration;
+    use tonic::Code;
+
+    async fn start(config: GrpcConfig) -> (AgentServerHandle, String) {
+        let agent = AgentBuilder::new(NoopAgentHandler)
+            .with_name("tuned")
+            .with_grpc_server("[::]:0".parse().unwrap())
+            .with_grpc_config(config)
+            .build()
+            .expect("failed to build agent");
+        let handle = agent.start_server().await.expect("failed to start server");
+        let addr = handle.local_addr(Transport::Grpc).unwrap();
+        (handle, format!("http://localhost:{}", addr.port()))
+    }
+
+    async fn start_multiplexed(config: GrpcConfig) -> (AgentServerHandle, String) {
+        let agent = AgentBuilder::new(NoopAgentHandler)
+            .with_name("tuned")
+            .with_multiplexed_server("[::]:0".parse().unwrap())
+            .with_grpc_config(config)
+            .build()
+            .expect("failed to build agent");
+        let handle = agent.start_server().await.expect("failed to start server");
+        let addr = handle.local_addr(Transport::Grpc).unwrap();
+        (handle, format!("http://localhost:{}", addr.port()))
+    }
+
+    async fn connect(url: &str, config: GrpcConfig) -> A2AClient {
+        let config = A2AClientConfig::new().with_grpc(config);
+        A2AClient::new_with_config(Transport::Grpc, url, config)
+            .await
+            .expect("failed to create client")
+    }
+
+    async fn echo(client: &A2AClient, size: usize) -> Result<String, A2AError> {
+        let res = client
+            .send_message(SendMessageRequest {
+                message: Some(Message::new_simple("a".repeat(size))),
+                configuration: None,
+                metadata: None,
+            })
+            .await?;
+        match res.payload.unwrap() {
+            SendMessageResponsePayload::Message(message) => Ok(message.message_id),
+            _ => panic!("expected message"),
+        }
+    }
+
+    fn code(e: &A2AError) -> Option<Code> {
+        match e {
+            A2AError::Transport(A2ATransportError::Grcp(status)) => Some(status.code()),
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn should_enforce_message_size_limits() {
+        // uncompressed, as compressed messages are measured while decompressing
+        let uncompressed = || GrpcConfig::new().without_send_compression();
+        let (handle, url) = start(uncompressed().with_max_decoding_message_size(4096)).await;
+
+        let client = connect(&url, uncompressed()).await;
+        echo(&client, 1024).await.unwrap();
+        let e = echo(&client, 8192).await.unwrap_err();
+        assert_eq!(code(&e), Some(Code::OutOfRange), "{e:?}");
+
+        let client = connect(&url, uncompressed().with_max_decoding_message_size(2048)).await;
+        let e = echo(&client, 3072).await.unwrap_err();
+        assert_eq!(code(&e), Some(Code::OutOfRange), "{e:?}");
+
+        handle.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_negotiate_compression() {
+        let (handle, url) = start(
+            GrpcConfig::new()
+                .with_accept_compression([CompressionEncoding::Zstd])
+                .with_send_compression(CompressionEncoding::Zstd)
+                .with_keepalive(Duration::from_secs(10), Duration::from_secs(5))
+                .with_window_sizes(1024 * 1024, 4 * 1024 * 1024)
+                .with_concurrency_limit(8),
+        )
+        .await;
+
+        let zstd = GrpcConfig::new()
+            .with_accept_compression([CompressionEncoding::Zstd])
+            .with_send_compression(CompressionEncoding::Zstd)
+            .with_concurrency_limit(8);
+        echo(&connect(&url, zstd).await, 1024).await.unwrap();
+
+        // gzip is sent by default, like the server
+        let gzip = GrpcConfig::new();
+        assert_eq!(
+            gzip,
+            GrpcConfig::new().with_send_compression(CompressionEncoding::Gzip)
+        );
+        let e = echo(&connect(&url, gzip).await, 1024).await.unwrap_err();
+        assert_eq!(code(&e), Some(Code::Unimplemented), "{e:?}");
+
+        handle.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_apply_http2_settings_to_multiplexed_connections() {
+        let (handle, url) = start_multiplexed(
+            GrpcConfig::new()
+                .with_keepalive(Duration::from_millis(50), Duration::from_secs(1))
+                .with_tcp_keepalive(Duration::from_secs(30))
+                .with_window_sizes(1024 * 1024, 4 * 1024 * 1024)
+                .with_concurrency_limit(1),
+        )
+        .await;
+
+        // RPCs beyond the limit wait for the ones in flight
+        let client = connect(&url, GrpcConfig::new()).await;
+        let echoes = futures::future::join_all((0..4).map(|_| echo(&client, 1024))).await;
+        assert!(echoes.iter().all(Result::is_ok), "{echoes:?}");
+
+        // the connection survives the pings of the server
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        echo(&client, 1024).await.unwrap();
+        let client = A2AClient::new(Transport::JsonRpc, &url).await.unwrap();
+        echo(&client, 1024).await.unwrap();
+
+        handle.shutdown().await.unwrap();
+    }
+}
@@ -0,0 +1,110 @@
+mod common;
+
+#[cfg(test)]
+#[cfg(feature = "agent")]
+mod in_process {
+    use crate::common;
+    use async_trait::async_trait;
+    use ra2a::agent::{A2ADelegate, AgentBuilder};
+    use ra2a::auth::{Principal, StaticAuthenticator};
+    use ra2a::client::{A2AClient, A2AClientConfig, ClientCall, ClientInterceptor, ClientNext};
+    use ra2a::core::agent::AgentCard;
+    use ra2a::core::message::{Message, SendMessageRequest, SendMessageResponsePayload};
+    use ra2a::core::part::PartBase;
+    use ra2a::core::task::GetTaskRequest;
+
+    use ra2a::core::{A2A, A2AError, A2AProtocolError, A2AResponse};
+    use serde_json::json;
+
+    /// Presents an api key with every call.
+    #[derive(Debug)]
+    struct ApiKey(&'static str);
+
+    #[async_trait]
+    impl ClientInterceptor for ApiKey {
+        async fn intercept(
+            &self,
+            mut call: ClientCall,
+            next: ClientNext<'_>,
+        ) -> Result<A2AResponse, A2AError> {
+            call.headers.insert("X-API-Key", self.0.parse().unwrap());
+            next.run(call).await
+        }
+    }
+
+    fn card() -> AgentCard {
+        common::card("secure")
+            .with_scheme(
+                "apiKey",
+                json!({ "type": "apiKey", "in": "header", "name": "X-API-Key" }),
+            )
+            .with_security(json!([{ "apiKey": [] }]))
+            .build()
+    }
+
+    fn delegate() -> A2ADelegate {
+        AgentBuilder::new(common::WhoAmIHandler)
+            .with_name("secure")
+            .with_card(card())
+            .with_authenticator(
+                StaticAuthenticator::new().with_token("key-1", Principal::new("alice")),
+            )
+            .build_delegate()
+            .expect("failed to build delegate")
+    }
+
+    async fn who_am_i(client: &A2AClient) -> Result<String, A2AError> {
+        let res = client
+            .send_message(SendMessageRequest {
+                message: Some(Message::new_simple("who am i?")),
+                configuration: None,
+                metadata: None,
+            })
+            .await?;
+        let Some(SendMessageResponsePayload::Message(message)) = res.payload else {
+            panic!("expected message");
+        };
+        match &message.parts[0].part {
+            Some(PartBase::Text(text)) => Ok(text.clone()),
+            _ => panic!("expected text"),
+        }
+    }
+
+    #[tokio::test]
+    async fn should_authenticate_intercepted_calls() {
+        let client = A2AClient::in_process(delegate());
+        let e = who_am_i(&client).await.unwrap_err();
+        assert!(matches!(e, A2AError::Auth(_)), "{e:?}");
+
+        let config = A2AClientConfig::new().with_interceptor(ApiKey("key-1"));
+        let client = A2AClient::in_process_with_config(delegate(), config);
+        assert_eq!(who_am_i(&client).await.unwrap(), "alice");
+    }
+
+    #[tokio::test]
+    async fn should_round_trip_through_json_in_strict_mode() {
+        let config = A2AClientConfig::new().with_interceptor(ApiKey("key-1"));
+        for strict in [false, true] {
+            let client = ra2a::client::inprocess::A2AInProcessClient::new_with_config(
+                delegate(),
+                config.clone(),
+            )
+            .with_strict(strict);
+            let client = A2AClient::InProcess(client);
+            assert_eq!(who_am_i(&client).await.unwrap(), "alice");
+
+            let e = client
+                .get_task(GetTaskRequest {
+                    id: "bogus".to_string(),
+                    history_length: None,
+                    metadata: None,
+                })
+                .await
+                .unwrap_err();
+            assert!(
+                matches!(e, A2AError::Protocol(A2AProtocolError::TaskNotFound { .. })),
+                "{e:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,8 @@
+#!/bin/sh
+# Regenerates the PKCS#8 private keys used by the card_signature tests.
+set -e
+cd "$(dirname "$0")"
+
+openssl genpkey -algorithm EC -pkeyopt ec_paramgen_curve:P-256 -out es256.pem
+openssl genpkey -algorithm RSA -pkeyopt rsa_keygen_bits:2048 -out rs256.pem
+openssl genpkey -algorithm ED25519 -out ed25519.pem
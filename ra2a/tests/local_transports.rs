@@ -0,0 +1,134 @@
+#[cfg(test)]
+#[cfg(all(unix, feature = "agent"))]
+mod local_transports {
+    use ra2a::agent::{A2ADelegate, AgentBuilder, NoopAgentHandler};
+    use ra2a::client::A2AClient;
+    use ra2a::core::message::{Message, SendMessageRequest, SendMessageResponsePayload};
+    use ra2a::core::task::GetTaskRequest;
+    use ra2a::core::{A2A, A2AError, A2AProtocolError, Transport};
+    use ra2a::server::stdio::A2AStdioServer;
+    use serde_json::{Value, json};
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    fn socket_path(transport: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("ra2a-{transport}-{}.sock", uuid::Uuid::new_v4()))
+    }
+
+    async fn echo(client: &A2AClient) -> bool {
+        let res = client
+            .send_message(SendMessageRequest {
+                message: Some(Message::new_simple("hello")),
+                configuration: None,
+                metadata: None,
+            })
+            .await
+            .unwrap();
+        matches!(res.payload, Some(SendMessageResponsePayload::Message(_)))
+    }
+
+    #[tokio::test]
+    async fn should_serve_over_unix_domain_sockets() {
+        let jsonrpc = socket_path("jsonrpc");
+        let builder = AgentBuilder::new(NoopAgentHandler)
+            .with_name("sidecar")
+            .with_json_rpc_unix_socket(&jsonrpc);
+        #[cfg(feature = "grpc")]
+        let grpc = socket_path("grpc");
+        #[cfg(feature = "grpc")]
+        let builder = builder.with_grpc_unix_socket(&grpc);
+        let agent = builder.build().expect("failed to build agent");
+        let handle = agent.start_server().await.expect("failed to start server");
+
+        let client = A2AClient::new_unix(Transport::JsonRpc, &jsonrpc)
+            .await
+            .unwrap();
+        assert!(echo(&client).await);
+        #[cfg(feature = "grpc")]
+        {
+            let client = A2AClient::new_unix(Transport::Grpc, &grpc).await.unwrap();
+            assert!(echo(&client).await);
+        }
+
+        handle.shutdown().await.unwrap();
+        assert!(!jsonrpc.exists());
+        #[cfg(feature = "grpc")]
+        assert!(!grpc.exists());
+    }
+
+    #[tokio::test]
+    async fn should_serve_json_rpc_lines() {
+        let server = A2AStdioServer::new(A2ADelegate::new(Arc::new(NoopAgentHandler)));
+        let (client_io, server_io) = tokio::io::duplex(4096);
+        let (server_reader, server_writer) = tokio::io::split(server_io);
+        let serving = tokio::spawn(async move {
+            server
+                .serve_io(std::future::pending(), server_reader, server_writer)
+                .await
+        });
+
+        let (reader, mut writer) = tokio::io::split(client_io);
+        let requests = [
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "message/send",
+                "params": { "message": Message::new_simple("hello") },
+            })
+            .to_string(),
+            json!({
+                "jsonrpc": "2.0",
+                "id": 2,
+                "method": "tasks/get",
+                "params": { "id": "bogus" },
+            })
+            .to_string(),
+            "not json".to_string(),
+        ];
+        for request in requests {
+            writer.write_all(request.as_bytes()).await.unwrap();
+            writer.write_all(b"\n").await.unwrap();
+        }
+        writer.shutdown().await.unwrap();
+
+        let mut lines = BufReader::new(reader).lines();
+        let mut responses = vec![];
+        while let Some(line) = lines.next_line().await.unwrap() {
+            responses.push(serde_json::from_str::<Value>(&line).unwrap());
+        }
+        responses.sort_by_key(|response| response["id"].as_i64());
+        assert_eq!(responses.len(), 3, "{responses:?}");
+        assert_eq!(responses[0]["error"]["code"], -32700);
+        assert_eq!(responses[1]["result"]["payload"]["kind"], "message");
+        assert!(responses[2]["error"].is_object(), "{}", responses[2]);
+
+        serving.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_talk_to_a_spawned_agent_over_stdio() {
+        // answers every request with a task not found error, echoing its id
+        let mut command = tokio::process::Command::new("sed");
+        command.args([
+            "-u",
+            r#"s/.*"id":\([0-9]*\),"method".*/{"jsonrpc":"2.0","id":\1,"error":{"code":-32001,"message":"Task not found"}}/"#,
+        ]);
+        let client = A2AClient::spawn(command).unwrap();
+
+        for _ in 0..2 {
+            let e = client
+                .get_task(GetTaskRequest {
+                    id: "t1".to_string(),
+                    history_length: None,
+                    metadata: None,
+                })
+                .await
+                .unwrap_err();
+            assert!(
+                matches!(e, A2AError::Protocol(A2AProtocolError::TaskNotFound { .. })),
+                "{e:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,174 @@
+mod common;
+
+#[cfg(test)]
+#[cfg(feature = "agent")]
+mod middleware {
+    use crate::common;
+    use async_trait::async_trait;
+    use ra2a::agent::{
+        AgentBuilder, AgentServerHandle, Middleware, Next, NoopAgentHandler, RequestContext,
+    };
+    use ra2a::auth::{Principal, StaticAuthenticator};
+    use ra2a::client::auth::{ClientAuthentication, ClientCredential, StaticCredentialProvider};
+    use ra2a::client::{A2AClient, A2AClientConfig};
+    use ra2a::core::agent::{AgentCard, Security};
+    use ra2a::core::message::{Message, SendMessageRequest};
+    use ra2a::core::task::{GetTaskRequest, Task};
+    use ra2a::core::{A2A, A2AError, A2AProtocolError, A2ARequest, A2AResponse, Transport};
+    use serde_json::json;
+    use std::sync::{Arc, Mutex};
+
+    fn card() -> AgentCard {
+        common::card("wrapped")
+            .with_scheme("bearer", json!({ "type": "http", "scheme": "bearer" }))
+            .build()
+    }
+
+    /// Records the calls it wraps in a log shared with the other middlewares.
+    #[derive(Debug)]
+    struct Recording {
+        name: &'static str,
+        log: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl Middleware for Recording {
+        async fn handle(
+            &self,
+            context: &RequestContext,
+            request: A2ARequest,
+            next: Next<'_>,
+        ) -> Result<A2AResponse, A2AError> {
+            let principal = context.principal.as_ref().map(|p| p.subject.as_str());
+            let entry = format!("{} {} {principal:?}", self.name, request.method());
+            self.log.lock().unwrap().push(entry.clone());
+            let response = next.run(context, request).await;
+            self.log.lock().unwrap().push(format!("{entry} done"));
+            response
+        }
+    }
+
+    /// Refuses messages without metadata and answers task lookups itself.
+    #[derive(Debug)]
+    struct Validating;
+
+    #[async_trait]
+    impl Middleware for Validating {
+        async fn handle(
+            &self,
+            context: &RequestContext,
+            request: A2ARequest,
+            next: Next<'_>,
+        ) -> Result<A2AResponse, A2AError> {
+            match &request {
+                A2ARequest::SendMessage(request) if request.metadata.is_none() => {
+                    Err(A2AProtocolError::content_type_not_supported().into())
+                }
+                A2ARequest::GetTask(request) => {
+                    let mut task = Task::new();
+                    task.id = request.id.clone();
+                    Ok(A2AResponse::GetTask(task))
+                }
+                _ => next.run(context, request).await,
+            }
+        }
+    }
+
+    async fn start(log: &Arc<Mutex<Vec<String>>>) -> AgentServerHandle {
+        let agent_builder = AgentBuilder::new(NoopAgentHandler)
+            .with_name("wrapped")
+            .with_card(card())
+            .with_extended_card(card())
+            .with_authenticator(
+                StaticAuthenticator::new().with_token("token-1", Principal::new("alice")),
+            )
+            .with_middleware(Recording {
+                name: "outer",
+                log: log.clone(),
+            })
+            .with_middleware(Recording {
+                name: "inner",
+                log: log.clone(),
+            })
+            .with_middleware(Validating)
+            .with_json_rpc_server("[::]:0".parse().unwrap());
+        #[cfg(feature = "grpc")]
+        let agent_builder = agent_builder.with_grpc_server("[::]:0".parse().unwrap());
+        let agent = agent_builder.build().expect("failed to build agent");
+        agent.start_server().await.expect("failed to start server")
+    }
+
+    async fn client(transport: Transport, port: u16) -> A2AClient {
+        let provider = StaticCredentialProvider::new()
+            .with_credential("bearer", ClientCredential::Bearer("token-1".to_string()));
+        let mut card = card();
+        card.security = vec![Security::empty().with_scheme("bearer", Vec::<String>::new())];
+        let config = A2AClientConfig::new()
+            .with_authentication(ClientAuthentication::new(&card, Arc::new(provider)).unwrap());
+        A2AClient::new_with_config(transport, format!("http://localhost:{port}"), config)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn should_wrap_calls_on_every_transport() {
+        let log = Arc::new(Mutex::new(vec![]));
+        let handle = start(&log).await;
+
+        for (transport, addr) in handle.local_addrs() {
+            let client = client(transport, addr.port()).await;
+            client.get_authenticated_extended_card().await.unwrap();
+            let entries = std::mem::take(&mut *log.lock().unwrap());
+            assert_eq!(
+                entries,
+                [
+                    "outer agent/getAuthenticatedExtendedCard Some(\"alice\")",
+                    "inner agent/getAuthenticatedExtendedCard Some(\"alice\")",
+                    "inner agent/getAuthenticatedExtendedCard Some(\"alice\") done",
+                    "outer agent/getAuthenticatedExtendedCard Some(\"alice\") done",
+                ],
+                "{transport}"
+            );
+
+            let task = client
+                .get_task(GetTaskRequest {
+                    id: "answered-by-middleware".to_string(),
+                    history_length: None,
+                    metadata: None,
+                })
+                .await
+                .unwrap();
+            assert_eq!(task.id, "answered-by-middleware", "{transport}");
+
+            let err = client
+                .send_message(SendMessageRequest {
+                    message: Some(Message::new_simple("hello")),
+                    configuration: None,
+                    metadata: None,
+                })
+                .await
+                .unwrap_err();
+            assert!(
+                matches!(
+                    err,
+                    A2AError::Protocol(A2AProtocolError::ContentTypeNotSupported { .. })
+                ),
+                "{transport}: {err:?}"
+            );
+            log.lock().unwrap().clear();
+        }
+
+        // the rest endpoint goes through the same stack
+        let port = handle.local_addr(Transport::JsonRpc).unwrap().port();
+        let res = reqwest::Client::new()
+            .get(format!("http://localhost:{port}/v1/card"))
+            .bearer_auth("token-1")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), 200);
+        assert_eq!(log.lock().unwrap().len(), 4);
+
+        handle.shutdown().await.unwrap();
+    }
+}
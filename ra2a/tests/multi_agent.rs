@@ -0,0 +1,216 @@
+mod common;
+
+#[cfg(test)]
+#[cfg(feature = "agent")]
+mod multi_agent {
+    use crate::common;
+    use async_trait::async_trait;
+    use ra2a::agent::{
+        A2AAgentError, AgentBuilder, AgentBuilderError, AgentHandler, AgentServerHandle,
+        RequestContext,
+    };
+    use ra2a::client::A2AClient;
+    #[cfg(feature = "grpc")]
+    use ra2a::client::{A2AClientConfig, ClientCall, ClientInterceptor, ClientNext};
+    use ra2a::core::agent::AgentCard;
+    use ra2a::core::message::{Message, SendMessageRequest, SendMessageResponsePayload};
+    use ra2a::core::part::PartBase;
+    use ra2a::core::task::Task;
+    use ra2a::core::util::Object;
+    use ra2a::core::{A2A, A2AError, Transport};
+    #[cfg(feature = "grpc")]
+    use ra2a::core::{A2AResponse, A2ATransportError, AGENT_ROUTING_HEADER};
+    use serde_json::{Value, json};
+    #[cfg(feature = "grpc")]
+    use tonic::Code;
+
+    #[derive(Debug)]
+    struct NamedHandler(&'static str);
+
+    #[async_trait]
+    impl AgentHandler for NamedHandler {
+        async fn handle_message(
+            &self,
+            _context: &RequestContext,
+            _message: Message,
+            _metadata: Option<Object>,
+            _task: Task,
+        ) -> Result<SendMessageResponsePayload, A2AAgentError> {
+            Ok(SendMessageResponsePayload::Message(Message::new_simple(
+                self.0,
+            )))
+        }
+    }
+
+    /// Names the agent calls are for in their metadata.
+    #[cfg(feature = "grpc")]
+    #[derive(Debug)]
+    struct RouteTo(&'static str);
+
+    #[cfg(feature = "grpc")]
+    #[async_trait]
+    impl ClientInterceptor for RouteTo {
+        async fn intercept(
+            &self,
+            mut call: ClientCall,
+            next: ClientNext<'_>,
+        ) -> Result<A2AResponse, A2AError> {
+            call.headers
+                .insert(AGENT_ROUTING_HEADER, self.0.parse().unwrap());
+            next.run(call).await
+        }
+    }
+
+    async fn start() -> (AgentServerHandle, u16) {
+        let mut builder = AgentBuilder::new(NamedHandler("front"))
+            .with_name("front")
+            .with_card(common::card("front").build())
+            .with_multiplexed_server("[::]:0".parse().unwrap())
+            .with_agent_host("echo.localhost", "echo");
+        for name in ["echo", "summarizer"] {
+            let delegate = AgentBuilder::new(NamedHandler(name))
+                .with_name(name)
+                .with_card(common::card(name).build())
+                .build_delegate()
+                .expect("failed to build delegate");
+            builder = builder.with_hosted_agent(name, delegate);
+        }
+        let agent = builder.build().expect("failed to build agent");
+        let handle = agent.start_server().await.expect("failed to start server");
+        let port = handle.local_addr(Transport::JsonRpc).unwrap().port();
+        (handle, port)
+    }
+
+    async fn answer(client: &A2AClient) -> Result<String, A2AError> {
+        let res = client
+            .send_message(SendMessageRequest {
+                message: Some(Message::new_simple("hello")),
+                configuration: None,
+                metadata: None,
+            })
+            .await?;
+        let Some(SendMessageResponsePayload::Message(message)) = res.payload else {
+            panic!("expected message");
+        };
+        match &message.parts[0].part {
+            Some(PartBase::Text(text)) => Ok(text.clone()),
+            _ => panic!("expected text"),
+        }
+    }
+
+    #[tokio::test]
+    async fn should_route_json_rpc_by_path_and_host() {
+        let (handle, port) = start().await;
+
+        for (url, name) in [
+            (format!("http://localhost:{port}"), "front"),
+            (format!("http://localhost:{port}/agents/echo"), "echo"),
+            (
+                format!("http://localhost:{port}/agents/summarizer"),
+                "summarizer",
+            ),
+        ] {
+            let client = A2AClient::new(Transport::JsonRpc, &url).await.unwrap();
+            assert_eq!(answer(&client).await.unwrap(), name, "{url}");
+        }
+
+        let url = format!("http://localhost:{port}/agents/bogus");
+        let client = A2AClient::new(Transport::JsonRpc, &url).await.unwrap();
+        assert!(answer(&client).await.is_err());
+
+        let res: Value = reqwest::Client::new()
+            .post(format!("http://localhost:{port}"))
+            .header(reqwest::header::HOST, "echo.localhost")
+            .json(&json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "message/send",
+                "params": { "message": Message::new_simple("hello") },
+            }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(
+            res["result"]["payload"]["parts"][0]["text"], "echo",
+            "{res}"
+        );
+
+        handle.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_serve_the_card_of_each_agent() {
+        let (handle, port) = start().await;
+        let http = reqwest::Client::new();
+
+        for (path, name) in [
+            ("", "front"),
+            ("/agents/echo", "echo"),
+            ("/agents/summarizer", "summarizer"),
+        ] {
+            let url = format!("http://localhost:{port}{path}/.well-known/agent-card.json");
+            let card: AgentCard = http.get(&url).send().await.unwrap().json().await.unwrap();
+            assert_eq!(card.name, name);
+        }
+
+        let url = format!("http://localhost:{port}/agents/bogus/.well-known/agent-card.json");
+        let res = http.get(&url).send().await.unwrap();
+        assert_eq!(res.status(), reqwest::StatusCode::NOT_FOUND);
+
+        handle.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "grpc")]
+    async fn should_route_grpc_by_metadata() {
+        let (handle, port) = start().await;
+        let url = format!("http://localhost:{port}");
+
+        let client = A2AClient::new(Transport::Grpc, &url).await.unwrap();
+        assert_eq!(answer(&client).await.unwrap(), "front");
+
+        let config = A2AClientConfig::new().with_interceptor(RouteTo("summarizer"));
+        let client = A2AClient::new_with_config(Transport::Grpc, &url, config)
+            .await
+            .unwrap();
+        assert_eq!(answer(&client).await.unwrap(), "summarizer");
+
+        let config = A2AClientConfig::new().with_interceptor(RouteTo("bogus"));
+        let client = A2AClient::new_with_config(Transport::Grpc, &url, config)
+            .await
+            .unwrap();
+        let e = answer(&client).await.unwrap_err();
+        assert!(
+            matches!(&e, A2AError::Transport(A2ATransportError::Grcp(status)) if status.code() == Code::NotFound),
+            "{e:?}"
+        );
+
+        handle.shutdown().await.unwrap();
+    }
+
+    #[test]
+    fn should_require_multiplexed_server_to_host_agents() {
+        let front = || {
+            AgentBuilder::new(NamedHandler("front"))
+                .with_name("front")
+                .with_json_rpc_server("[::]:0".parse().unwrap())
+        };
+        let delegate = AgentBuilder::new(NamedHandler("echo"))
+            .with_name("echo")
+            .build_delegate()
+            .expect("failed to build delegate");
+        let res = front().with_hosted_agent("echo", delegate).build();
+        assert!(matches!(
+            res,
+            Err(AgentBuilderError::MissingMultiplexedServer)
+        ));
+        let res = front().with_agent_host("echo.localhost", "echo").build();
+        assert!(matches!(
+            res,
+            Err(AgentBuilderError::MissingMultiplexedServer)
+        ));
+    }
+}
@@ -0,0 +1,55 @@
+#[cfg(test)]
+#[cfg(all(feature = "agent", feature = "grpc"))]
+mod multiplexed_server {
+    use ra2a::agent::{AgentBuilder, NoopAgentHandler};
+    use ra2a::client::A2AClient;
+    use ra2a::core::message::{Message, SendMessageRequest, SendMessageResponsePayload};
+    use ra2a::core::task::GetTaskRequest;
+    use ra2a::core::{A2A, A2AError, A2AProtocolError, Transport};
+
+    #[tokio::test]
+    async fn should_serve_every_transport_on_one_port() {
+        let agent = AgentBuilder::new(NoopAgentHandler)
+            .with_name("multiplexed")
+            .with_multiplexed_server("[::]:0".parse().unwrap())
+            .build()
+            .expect("failed to build agent");
+        let handle = agent.start_server().await.expect("failed to start server");
+
+        let addrs = handle.local_addrs();
+        assert_eq!(addrs.len(), 2);
+        assert!(addrs.iter().all(|(_, addr)| *addr == addrs[0].1));
+        let url = format!("http://localhost:{}", addrs[0].1.port());
+
+        for transport in [Transport::JsonRpc, Transport::Grpc] {
+            let client = A2AClient::new(transport, &url).await.unwrap();
+            let res = client
+                .send_message(SendMessageRequest {
+                    message: Some(Message::new_simple("hello")),
+                    configuration: None,
+                    metadata: None,
+                })
+                .await
+                .unwrap();
+            assert!(
+                matches!(res.payload, Some(SendMessageResponsePayload::Message(_))),
+                "{transport}"
+            );
+
+            let e = client
+                .get_task(GetTaskRequest {
+                    id: "bogus".to_string(),
+                    history_length: None,
+                    metadata: None,
+                })
+                .await
+                .unwrap_err();
+            assert!(
+                matches!(e, A2AError::Protocol(A2AProtocolError::TaskNotFound { .. })),
+                "{transport}: {e:?}"
+            );
+        }
+
+        handle.shutdown().await.unwrap();
+    }
+}
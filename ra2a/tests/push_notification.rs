@@ -0,0 +1,317 @@
+#[cfg(test)]
+#[cfg(feature = "agent")]
+mod push_notification {
+    use base64::Engine;
+    use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+    use ra2a::agent::{AgentBuilder, AgentServerHandle, NoopAgentHandler};
+    use ra2a::client::A2AClient;
+    use ra2a::core::message::{
+        Message, SendMessageConfiguration, SendMessageRequest, SendMessageResponsePayload,
+    };
+    use ra2a::core::push_notification::PushNotificationConfig;
+    use ra2a::core::{A2A, A2AError, A2AProtocolError, Transport};
+    use ra2a::egress::UrlPolicy;
+    use ra2a::jws::{Algorithm, JwkSet, SigningKey};
+    use ra2a::push::{
+        PUSH_JWKS_PATH, PUSH_SIGNATURE_HEADER, PushError, PushKeys, PushNotifier, PushVerifier,
+    };
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio::sync::mpsc;
+
+    /// Webhook answering 200 and forwarding the headers and body of every request.
+    async fn webhook() -> (
+        SocketAddr,
+        mpsc::UnboundedReceiver<(http::HeaderMap, Vec<u8>)>,
+    ) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut request = vec![];
+                let mut buf = [0; 4096];
+                let (head, body) = loop {
+                    let n = stream.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                    let text = String::from_utf8_lossy(&request).to_string();
+                    let Some((head, _)) = text.split_once("\r\n\r\n") else {
+                        continue;
+                    };
+                    let body = request[head.len() + 4..].to_vec();
+                    let length: usize = head
+                        .lines()
+                        .find_map(|line| {
+                            line.to_lowercase()
+                                .strip_prefix("content-length: ")
+                                .map(str::to_string)
+                        })
+                        .and_then(|v| v.parse().ok())
+                        .unwrap_or_default();
+                    if body.len() >= length {
+                        break (head.to_string(), body);
+                    }
+                };
+                let mut headers = http::HeaderMap::new();
+                for line in head.lines().skip(1) {
+                    if let Some((name, value)) = line.split_once(": ") {
+                        headers.insert(
+                            http::HeaderName::from_bytes(name.as_bytes()).unwrap(),
+                            value.parse().unwrap(),
+                        );
+                    }
+                }
+                let _ = tx.send((headers, body));
+                let _ = stream
+                    .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\nconnection: close\r\n\r\n")
+                    .await;
+            }
+        });
+        (addr, rx)
+    }
+
+    /// Serves `jwks` for every request, counting them.
+    async fn serve_jwks(jwks: JwkSet) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}{PUSH_JWKS_PATH}", listener.local_addr().unwrap());
+        let body = serde_json::to_string(&jwks).unwrap();
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let counter = fetches.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buf = [0u8; 4096];
+                let _ = stream.read(&mut buf).await;
+                counter.fetch_add(1, Ordering::SeqCst);
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        (url, fetches)
+    }
+
+    fn policy() -> UrlPolicy {
+        UrlPolicy::new()
+            .with_scheme("http")
+            .with_allowed_addr(IpAddr::V4(Ipv4Addr::LOCALHOST))
+            .with_allowed_addr(IpAddr::V6(Ipv6Addr::LOCALHOST))
+    }
+
+    async fn start(notifier: Option<PushNotifier>) -> AgentServerHandle {
+        let mut agent_builder = AgentBuilder::new(NoopAgentHandler)
+            .with_name("pusher")
+            .with_json_rpc_server("[::]:0".parse().unwrap());
+        #[cfg(feature = "grpc")]
+        {
+            agent_builder = agent_builder.with_grpc_server("[::]:0".parse().unwrap());
+        }
+        if let Some(notifier) = notifier {
+            agent_builder = agent_builder.with_push_notifier(notifier);
+        }
+        let agent = agent_builder.build().expect("failed to build agent");
+        agent.start_server().await.expect("failed to start server")
+    }
+
+    fn request(url: &str, token: &str) -> SendMessageRequest {
+        SendMessageRequest {
+            message: Some(Message::new_simple("hello")),
+            configuration: Some(SendMessageConfiguration {
+                accepted_output_modes: vec!["text/plain".to_string()],
+                push_notification: Some(PushNotificationConfig {
+                    id: "push-1".to_string(),
+                    url: url.to_string(),
+                    token: token.to_string(),
+                    authentication: None,
+                }),
+                history_length: 0,
+                blocking: false,
+            }),
+            metadata: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn should_sign_push_notifications() {
+        let (addr, mut notifications) = webhook().await;
+        let hook = format!("http://localhost:{}/hook", addr.port());
+        let keys = PushKeys::generate(Algorithm::ES256).unwrap();
+        let notifier = PushNotifier::new(keys, policy())
+            .unwrap()
+            .with_issuer("pusher");
+        let handle = start(Some(notifier)).await;
+        let port = handle.local_addr(Transport::JsonRpc).unwrap().port();
+
+        // the keys are public
+        let jwks_url = format!("http://localhost:{port}{PUSH_JWKS_PATH}");
+        let jwks: JwkSet = reqwest::get(&jwks_url).await.unwrap().json().await.unwrap();
+        assert_eq!(jwks.keys.len(), 1);
+        let verifier = PushVerifier::new()
+            .with_jwks_url(&jwks_url)
+            .with_issuer("pusher")
+            .with_audience(&hook);
+
+        for (transport, addr) in handle.local_addrs() {
+            let client = A2AClient::new(transport, format!("http://localhost:{}", addr.port()))
+                .await
+                .unwrap();
+            let token = format!("token-{transport}");
+            let res = client.send_message(request(&hook, &token)).await.unwrap();
+            let Some(SendMessageResponsePayload::Task(task)) = res.payload else {
+                panic!("{transport}: expected a task");
+            };
+
+            let (headers, body) = notifications.recv().await.unwrap();
+            let notification = verifier
+                .verify(&headers, &body, Some(&token))
+                .await
+                .unwrap();
+            assert_eq!(notification.task.id, task.id, "{transport}");
+            assert_eq!(notification.claims.task_id, task.id, "{transport}");
+
+            let err = verifier
+                .verify(&headers, &body, Some("other-token"))
+                .await
+                .unwrap_err();
+            assert!(matches!(err, PushError::TokenMismatch), "{err:?}");
+            let mut tampered = body.clone();
+            tampered.extend_from_slice(b" ");
+            let err = verifier
+                .verify(&headers, &tampered, None)
+                .await
+                .unwrap_err();
+            assert!(matches!(err, PushError::BodyMismatch), "{err:?}");
+            let err = verifier
+                .verify(&http::HeaderMap::new(), &body, None)
+                .await
+                .unwrap_err();
+            assert!(matches!(err, PushError::MissingSignature), "{err:?}");
+        }
+
+        handle.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_reject_push_notification_configs() {
+        let keys = PushKeys::generate(Algorithm::EdDSA).unwrap();
+        let handle = start(Some(PushNotifier::new(keys, UrlPolicy::new()).unwrap())).await;
+        for (transport, addr) in handle.local_addrs() {
+            let client = A2AClient::new(transport, format!("http://localhost:{}", addr.port()))
+                .await
+                .unwrap();
+            for url in ["http://example.com/hook", "https://169.254.169.254/hook"] {
+                let err = client.send_message(request(url, "")).await;
+                assert!(err.is_err(), "{transport}: {url} accepted");
+            }
+        }
+        handle.shutdown().await.unwrap();
+
+        let handle = start(None).await;
+        for (transport, addr) in handle.local_addrs() {
+            let client = A2AClient::new(transport, format!("http://localhost:{}", addr.port()))
+                .await
+                .unwrap();
+            let err = client
+                .send_message(request("https://example.com/hook", ""))
+                .await
+                .unwrap_err();
+            assert!(
+                matches!(
+                    err,
+                    A2AError::Protocol(A2AProtocolError::PushNotificationNotSupported { .. })
+                ),
+                "{transport}: {err:?}"
+            );
+        }
+        handle.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_publish_rotated_keys() {
+        let (addr, mut notifications) = webhook().await;
+        let hook = format!("http://localhost:{}/hook", addr.port());
+        let keys = PushKeys::generate(Algorithm::ES256).unwrap();
+        let notifier = PushNotifier::new(keys.clone(), policy()).unwrap();
+        let config = request(&hook, "token")
+            .configuration
+            .unwrap()
+            .push_notification
+            .unwrap();
+        let task = ra2a::core::task::Task::new();
+
+        let pinned = PushVerifier::new().with_jwks(keys.jwks());
+        notifier.notify(&config, &task).await.unwrap();
+        let (old_headers, old_body) = notifications.recv().await.unwrap();
+
+        keys.rotate(SigningKey::generate(Algorithm::EdDSA).unwrap());
+        let jwks = keys.jwks();
+        assert_eq!(jwks.keys.len(), 2);
+        assert_ne!(jwks.keys[0].kid, jwks.keys[1].kid);
+
+        notifier.notify(&config, &task).await.unwrap();
+        let (headers, body) = notifications.recv().await.unwrap();
+        assert_ne!(
+            headers[PUSH_SIGNATURE_HEADER],
+            old_headers[PUSH_SIGNATURE_HEADER]
+        );
+        let err = pinned.verify(&headers, &body, None).await.unwrap_err();
+        assert!(matches!(err, PushError::InvalidSignature), "{err:?}");
+
+        // notifications signed before the rotation still verify against the new key set
+        let verifier = PushVerifier::new().with_jwks(jwks);
+        for (headers, body) in [(old_headers, old_body), (headers, body)] {
+            let notification = verifier
+                .verify(&headers, &body, Some("token"))
+                .await
+                .unwrap();
+            assert_eq!(notification.task.id, task.id);
+        }
+    }
+
+    #[tokio::test]
+    async fn should_keep_pinned_keys_and_throttle_jwks_fetches() {
+        let (addr, mut notifications) = webhook().await;
+        let hook = format!("http://localhost:{}/hook", addr.port());
+        let config = request(&hook, "token")
+            .configuration
+            .unwrap()
+            .push_notification
+            .unwrap();
+        let task = ra2a::core::task::Task::new();
+        let pinned = PushKeys::generate(Algorithm::ES256).unwrap();
+        let published = PushKeys::generate(Algorithm::EdDSA).unwrap();
+        let (jwks_url, fetches) = serve_jwks(published.jwks()).await;
+        let verifier = PushVerifier::new()
+            .with_jwks(pinned.jwks())
+            .with_jwks_url(jwks_url);
+
+        let mut received = vec![];
+        for keys in [&published, &pinned] {
+            let notifier = PushNotifier::new(keys.clone(), policy()).unwrap();
+            notifier.notify(&config, &task).await.unwrap();
+            received.push(notifications.recv().await.unwrap());
+        }
+        // the fetched key set adds to the pinned keys rather than replacing them
+        for (headers, body) in &received {
+            verifier.verify(headers, body, None).await.unwrap();
+        }
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+
+        // unknown key ids do not make the verifier fetch the key set again right away
+        let (mut headers, body) = received.pop().unwrap();
+        let jwt = headers[PUSH_SIGNATURE_HEADER].to_str().unwrap().to_string();
+        let (_, rest) = jwt.split_once('.').unwrap();
+        for kid in ["forged-1", "forged-2"] {
+            let header = URL_SAFE_NO_PAD.encode(format!(r#"{{"alg":"ES256","kid":"{kid}"}}"#));
+            let forged = format!("{header}.{rest}");
+            headers.insert(PUSH_SIGNATURE_HEADER, forged.parse().unwrap());
+            let err = verifier.verify(&headers, &body, None).await.unwrap_err();
+            assert!(matches!(err, PushError::InvalidSignature), "{err:?}");
+        }
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+    }
+}
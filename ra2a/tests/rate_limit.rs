@@ -0,0 +1,287 @@
+mod common;
+
+#[cfg(test)]
+#[cfg(feature = "agent")]
+mod rate_limit {
+    use crate::common;
+    use async_trait::async_trait;
+    use ra2a::agent::{AgentBuilder, AgentServerHandle, NoopAgentHandler};
+    use ra2a::auth::{Principal, StaticAuthenticator};
+    use ra2a::client::auth::{ClientAuthentication, ClientCredential, StaticCredentialProvider};
+    use ra2a::client::{A2AClient, A2AClientConfig};
+    use ra2a::core::agent::{AgentCard, Security};
+    use ra2a::core::message::{Message, SendMessageRequest};
+    use ra2a::core::util::Object;
+    use ra2a::core::{A2A, A2AError, A2AProtocolError, A2ATransportError, Transport};
+    use ra2a::limit::{
+        InMemoryQuotaStore, LimitKey, Quota, QuotaStore, QuotaStoreError, RateLimit,
+        RateLimitError, RateLimiter,
+    };
+    use serde_json::{Value, json};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    fn card() -> AgentCard {
+        let skills = [Transport::JsonRpc, Transport::Grpc]
+            .into_iter()
+            .flat_map(|transport| {
+                [
+                    format!("skill-{transport}"),
+                    format!("skill-{transport}-other"),
+                ]
+            })
+            .map(|id| common::skill(&id));
+        common::card("limited")
+            .with_scheme("bearer", json!({ "type": "http", "scheme": "bearer" }))
+            .with_skills(skills)
+            .build()
+    }
+
+    fn body() -> Value {
+        json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "message/send",
+            "params": {
+                "message": {
+                    "role": "user",
+                    "parts": [{ "kind": "text", "text": "hello" }],
+                    "messageId": uuid::Uuid::new_v4().to_string()
+                }
+            }
+        })
+    }
+
+    fn request(skill: &str) -> SendMessageRequest {
+        let mut metadata = Object::default();
+        metadata.insert("skillId", json!(skill));
+        SendMessageRequest {
+            message: Some(Message::new_simple("hello")),
+            configuration: None,
+            metadata: Some(metadata),
+        }
+    }
+
+    async fn start(rate_limiter: RateLimiter) -> AgentServerHandle {
+        let agent_builder = AgentBuilder::new(NoopAgentHandler)
+            .with_name("limited")
+            .with_card(card())
+            .with_authenticator(
+                StaticAuthenticator::new()
+                    .with_token("token-1", Principal::new("alice"))
+                    .with_token("token-2", Principal::new("bob")),
+            )
+            .with_rate_limiter(rate_limiter)
+            .with_json_rpc_server("[::]:0".parse().unwrap());
+        #[cfg(feature = "grpc")]
+        let agent_builder = agent_builder.with_grpc_server("[::]:0".parse().unwrap());
+        let agent = agent_builder.build().expect("failed to build agent");
+        agent.start_server().await.expect("failed to start server")
+    }
+
+    async fn send(url: &str, token: Option<&str>) -> reqwest::Response {
+        let mut request = reqwest::Client::new().post(url).json(&body());
+        if let Some(token) = token {
+            request = request.bearer_auth(token);
+        }
+        request.send().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn should_limit_requests_per_principal() {
+        let handle = start(
+            RateLimiter::new().with_limit(RateLimit::new(LimitKey::Principal, 2, 0.01).unwrap()),
+        )
+        .await;
+        let url = format!(
+            "http://localhost:{}",
+            handle.local_addr(Transport::JsonRpc).unwrap().port()
+        );
+
+        for _ in 0..2 {
+            assert_eq!(send(&url, Some("token-1")).await.status(), 200);
+        }
+        let res = send(&url, Some("token-1")).await;
+        assert_eq!(res.status().as_u16(), 429);
+        let retry_after: u64 = res.headers()["retry-after"]
+            .to_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!((1..=100).contains(&retry_after), "{retry_after}");
+        let res: Value = res.json().await.unwrap();
+        assert_eq!(res["error"]["code"], -32052, "{res}");
+        assert_eq!(res["error"]["data"]["retryAfter"], retry_after, "{res}");
+
+        // other callers have their own bucket and anonymous callers are not counted
+        assert_eq!(send(&url, Some("token-2")).await.status(), 200);
+        for _ in 0..3 {
+            assert_eq!(send(&url, None).await.status(), 200);
+        }
+
+        // buckets are shared across transports
+        for (transport, addr) in handle.local_addrs() {
+            let provider = StaticCredentialProvider::new()
+                .with_credential("bearer", ClientCredential::Bearer("token-1".to_string()));
+            let mut card = card();
+            card.security = vec![Security::empty().with_scheme("bearer", Vec::<String>::new())];
+            let config = A2AClientConfig::new()
+                .with_authentication(ClientAuthentication::new(&card, Arc::new(provider)).unwrap());
+            let client = A2AClient::new_with_config(
+                transport,
+                format!("http://localhost:{}", addr.port()),
+                config,
+            )
+            .await
+            .unwrap();
+            let err = client.send_message(request("chat")).await.unwrap_err();
+            match err {
+                A2AError::Protocol(A2AProtocolError::RateLimitExceeded { .. }) => {}
+                // json-rpc callers over the limit are rejected at the http level
+                A2AError::Transport(A2ATransportError::JsonRpc(_))
+                    if transport == Transport::JsonRpc => {}
+                e => panic!("{transport}: expected rate limit error, got {e:?}"),
+            }
+        }
+
+        handle.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_limit_requests_per_remote_addr() {
+        let handle = start(
+            RateLimiter::new().with_limit(RateLimit::new(LimitKey::RemoteAddr, 1, 0.01).unwrap()),
+        )
+        .await;
+        let url = format!(
+            "http://localhost:{}",
+            handle.local_addr(Transport::JsonRpc).unwrap().port()
+        );
+
+        assert_eq!(send(&url, None).await.status(), 200);
+        // a new connection from the same address shares the bucket
+        assert_eq!(send(&url, Some("token-2")).await.status(), 429);
+
+        handle.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_limit_messages_per_skill() {
+        let handle =
+            start(RateLimiter::new().with_limit(RateLimit::new(LimitKey::Skill, 1, 0.01).unwrap()))
+                .await;
+
+        for (transport, addr) in handle.local_addrs() {
+            let client = A2AClient::new(transport, format!("http://localhost:{}", addr.port()))
+                .await
+                .unwrap();
+            let skill = format!("skill-{transport}");
+            client.send_message(request(&skill)).await.unwrap();
+            let err = client.send_message(request(&skill)).await.unwrap_err();
+            assert!(
+                matches!(
+                    err,
+                    A2AError::Protocol(A2AProtocolError::RateLimitExceeded { retry_after, .. })
+                        if retry_after > 0
+                ),
+                "{transport}: {err:?}"
+            );
+            // other skills and messages without a skill are not limited
+            client
+                .send_message(request(&format!("{skill}-other")))
+                .await
+                .unwrap();
+            client
+                .send_message(SendMessageRequest {
+                    message: Some(Message::new_simple("hello")),
+                    configuration: None,
+                    metadata: None,
+                })
+                .await
+                .unwrap();
+        }
+
+        handle.shutdown().await.unwrap();
+    }
+
+    /// Records the keys of the counters it increments.
+    #[derive(Debug, Default)]
+    struct RecordingQuotaStore {
+        inner: InMemoryQuotaStore,
+        keys: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl QuotaStore for RecordingQuotaStore {
+        async fn increment(&self, key: &str, ttl: Duration) -> Result<u64, QuotaStoreError> {
+            self.keys.lock().unwrap().push(key.to_string());
+            self.inner.increment(key, ttl).await
+        }
+    }
+
+    #[tokio::test]
+    async fn should_enforce_task_quotas() {
+        let store = RecordingQuotaStore::default();
+        let keys = store.keys.clone();
+        let handle = start(
+            RateLimiter::new()
+                .with_quota(Quota::per_day(LimitKey::Principal, 2))
+                .with_quota(Quota::new(
+                    LimitKey::Principal,
+                    10,
+                    Duration::from_secs(24 * 60 * 60),
+                ))
+                .with_quota_store(store),
+        )
+        .await;
+        let url = format!(
+            "http://localhost:{}",
+            handle.local_addr(Transport::JsonRpc).unwrap().port()
+        );
+
+        for _ in 0..2 {
+            let res: Value = send(&url, Some("token-1")).await.json().await.unwrap();
+            assert!(res["result"].is_object(), "{res}");
+        }
+        let res: Value = send(&url, Some("token-1")).await.json().await.unwrap();
+        assert_eq!(res["error"]["code"], -32053, "{res}");
+        assert_eq!(res["error"]["data"]["limit"], 2, "{res}");
+        let retry_after = res["error"]["data"]["retryAfter"].as_u64().unwrap();
+        assert!((1..=86400).contains(&retry_after), "{res}");
+
+        let res: Value = send(&url, Some("token-2")).await.json().await.unwrap();
+        assert!(res["result"].is_object(), "{res}");
+        let keys = keys.lock().unwrap().clone();
+        // each quota counts on its own, the exceeded one stops the count
+        assert_eq!(keys.len(), 7, "{keys:?}");
+        assert_ne!(keys[0], keys[1]);
+        assert!(keys[0].ends_with("/alice"), "{keys:?}");
+        assert!(keys[6].ends_with("/bob"), "{keys:?}");
+
+        handle.shutdown().await.unwrap();
+    }
+
+    #[test]
+    fn should_reject_rate_limits_that_never_allow_requests() {
+        assert_eq!(
+            RateLimit::new(LimitKey::Principal, 0, 1.0),
+            Err(RateLimitError::ZeroBurst)
+        );
+        for refill in [0.0, -1.0, f64::INFINITY] {
+            assert_eq!(
+                RateLimit::new(LimitKey::Principal, 1, refill),
+                Err(RateLimitError::InvalidRefill(refill))
+            );
+        }
+        assert!(matches!(
+            RateLimit::new(LimitKey::Principal, 1, f64::NAN),
+            Err(RateLimitError::InvalidRefill(_))
+        ));
+
+        let limit = RateLimit::new(LimitKey::Skill, 3, 0.5).unwrap();
+        assert_eq!(
+            (limit.key(), limit.burst(), limit.refill_per_second()),
+            (LimitKey::Skill, 3, 0.5)
+        );
+    }
+}
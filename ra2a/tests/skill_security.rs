@@ -0,0 +1,171 @@
+mod common;
+
+#[cfg(test)]
+#[cfg(feature = "agent")]
+mod skill_security {
+    use crate::common;
+    use async_trait::async_trait;
+    use ra2a::agent::{
+        A2AAgentError, AgentBuilder, AgentHandler, RequestContext, SkillAuthFailure, SkillRouter,
+    };
+    use ra2a::auth::{Principal, StaticAuthenticator};
+    use ra2a::core::Transport;
+    use ra2a::core::agent::AgentCard;
+    use ra2a::core::message::{Message, SendMessageResponsePayload};
+    use ra2a::core::part::PartBase;
+    use ra2a::core::task::Task;
+    use ra2a::core::util::Object;
+    use serde_json::{Value, json};
+
+    #[derive(Debug, Default)]
+    struct EchoHandler;
+
+    #[async_trait]
+    impl AgentHandler for EchoHandler {
+        async fn handle_message(
+            &self,
+            _context: &RequestContext,
+            _message: Message,
+            _metadata: Option<Object>,
+            _task: Task,
+        ) -> Result<SendMessageResponsePayload, A2AAgentError> {
+            Ok(SendMessageResponsePayload::Message(Message::new_simple(
+                "done",
+            )))
+        }
+    }
+
+    /// Routes messages starting with "admin" to the admin skill.
+    #[derive(Debug, Default)]
+    struct PrefixRouter;
+
+    #[async_trait]
+    impl SkillRouter for PrefixRouter {
+        async fn route(&self, message: &Message, _metadata: Option<&Object>) -> Option<String> {
+            let text = message.parts.iter().find_map(|part| match &part.part {
+                Some(PartBase::Text(text)) => Some(text.as_str()),
+                _ => None,
+            })?;
+            text.starts_with("admin").then(|| "admin".to_string())
+        }
+    }
+
+    fn card() -> AgentCard {
+        let mut admin = common::skill("admin");
+        admin["security"] = json!([{ "bearer": ["agent:admin"] }]);
+        common::card("skills")
+            .with_scheme("bearer", json!({ "type": "http", "scheme": "bearer" }))
+            .with_skills([common::skill("chat"), admin])
+            .build()
+    }
+
+    fn authenticator() -> StaticAuthenticator {
+        StaticAuthenticator::new()
+            .with_token("token-1", Principal::new("alice"))
+            .with_token(
+                "token-2",
+                Principal::new("root").with_scopes(["agent:admin"]),
+            )
+    }
+
+    fn body(text: &str, skill: Option<&str>) -> Value {
+        let mut body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "message/send",
+            "params": {
+                "message": {
+                    "role": "user",
+                    "parts": [{ "kind": "text", "text": text }],
+                    "messageId": uuid::Uuid::new_v4().to_string()
+                }
+            }
+        });
+        if let Some(skill) = skill {
+            body["params"]["metadata"] = json!({ "skillId": skill });
+        }
+        body
+    }
+
+    async fn send(url: &str, token: Option<&str>, body: Value) -> Value {
+        let mut request = reqwest::Client::new().post(url).json(&body);
+        if let Some(token) = token {
+            request = request.bearer_auth(token);
+        }
+        let res = request.send().await.unwrap();
+        assert_eq!(res.status().as_u16(), 200);
+        res.json().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn should_reject_callers_without_skill_scopes() {
+        let agent = AgentBuilder::new(EchoHandler)
+            .with_name("skills")
+            .with_card(card())
+            .with_authenticator(authenticator())
+            .with_json_rpc_server("[::]:0".parse().unwrap())
+            .build()
+            .expect("failed to build agent");
+        let handle = agent.start_server().await.expect("failed to start server");
+        let url = format!(
+            "http://localhost:{}",
+            handle.local_addr(Transport::JsonRpc).unwrap().port()
+        );
+
+        let cases = [
+            (None, Some("chat"), true),
+            (None, None, true),
+            (None, Some("admin"), false),
+            (Some("token-1"), Some("admin"), false),
+            (Some("token-2"), Some("admin"), true),
+            // skills the card does not declare cannot be used to skip skill security
+            (None, Some("made-up"), false),
+            (Some("token-2"), Some("made-up"), false),
+        ];
+        for (token, skill, allowed) in cases {
+            let res = send(&url, token, body("hello", skill)).await;
+            match allowed {
+                true => assert_eq!(res["result"]["payload"]["parts"][0]["text"], "done"),
+                false => assert!(res["error"].is_object(), "{token:?} {skill:?}: {res}"),
+            }
+        }
+        handle.shutdown().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn should_require_auth_for_routed_skill() {
+        let agent = AgentBuilder::new(EchoHandler)
+            .with_name("skills")
+            .with_card(card())
+            .with_authenticator(authenticator())
+            .with_skill_router(PrefixRouter)
+            .with_skill_auth_failure(SkillAuthFailure::AuthRequired)
+            .with_json_rpc_server("[::]:0".parse().unwrap())
+            .build()
+            .expect("failed to build agent");
+        let handle = agent.start_server().await.expect("failed to start server");
+        let url = format!(
+            "http://localhost:{}",
+            handle.local_addr(Transport::JsonRpc).unwrap().port()
+        );
+
+        let res = send(&url, Some("token-1"), body("admin: reset", None)).await;
+        let task = &res["result"]["payload"];
+        assert_eq!(task["kind"], "task", "{res}");
+        assert_eq!(task["status"]["state"], "auth-required");
+        assert_eq!(task["history"][0]["parts"][0]["text"], "admin: reset");
+
+        let res = send(&url, Some("token-2"), body("admin: reset", None)).await;
+        assert_eq!(res["result"]["payload"]["parts"][0]["text"], "done");
+
+        let res = send(&url, Some("token-1"), body("hello", None)).await;
+        assert_eq!(res["result"]["payload"]["parts"][0]["text"], "done");
+
+        // naming an unprotected skill does not override the router
+        let res = send(&url, Some("token-1"), body("admin: reset", Some("chat"))).await;
+        assert_eq!(res["error"]["code"], -32054, "{res}");
+        let res = send(&url, Some("token-2"), body("admin: reset", Some("admin"))).await;
+        assert_eq!(res["result"]["payload"]["parts"][0]["text"], "done");
+        handle.shutdown().await.unwrap()
+    }
+}
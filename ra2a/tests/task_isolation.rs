@@ -0,0 +1,336 @@
+mod common;
+
+#[cfg(test)]
+#[cfg(feature = "agent")]
+mod task_isolation {
+    use crate::common;
+    use async_trait::async_trait;
+    use ra2a::agent::{A2AAgentError, A2ADelegate, AgentBuilder, AgentHandler, RequestContext};
+    use ra2a::auth::{Authentication, Principal, StaticAuthenticator};
+    use ra2a::broker::{A2AGateway, AgentBroker, AgentReplica};
+    use ra2a::client::{A2AClient, A2AClientConfig, ClientCall, ClientInterceptor, ClientNext};
+    use ra2a::core::agent::AgentCard;
+    use ra2a::core::message::{
+        Message, SendMessageConfiguration, SendMessageRequest, SendMessageResponsePayload,
+    };
+    use ra2a::core::task::{GetTaskRequest, Task, TaskStatus};
+    use ra2a::core::util::Object;
+    use ra2a::core::{A2A, A2AError, A2AProtocolError, A2AResponse, Transport};
+    use ra2a::server::A2AServer;
+    use serde_json::{Value, json};
+    use std::sync::Arc;
+
+    #[derive(Debug, Default)]
+    struct TestHandler;
+
+    #[async_trait]
+    impl AgentHandler for TestHandler {
+        async fn handle_message(
+            &self,
+            _context: &RequestContext,
+            message: Message,
+            _metadata: Option<Object>,
+            mut task: Task,
+        ) -> Result<SendMessageResponsePayload, A2AAgentError> {
+            task.history.push(message);
+            task.status = Some(TaskStatus::default_submitted());
+            Ok(SendMessageResponsePayload::Task(task))
+        }
+    }
+
+    fn card() -> AgentCard {
+        common::card("isolated")
+            .with_scheme("bearer", json!({ "type": "http", "scheme": "bearer" }))
+            .with_security(json!([{ "bearer": [] }]))
+            .build()
+    }
+
+    fn authenticator() -> StaticAuthenticator {
+        StaticAuthenticator::new()
+            .with_token("alice-token", Principal::new("alice"))
+            .with_token("bob-token", Principal::new("bob"))
+    }
+
+    /// Presents a bearer token with every call.
+    #[derive(Debug)]
+    struct Bearer(&'static str);
+
+    #[async_trait]
+    impl ClientInterceptor for Bearer {
+        async fn intercept(
+            &self,
+            mut call: ClientCall,
+            next: ClientNext<'_>,
+        ) -> Result<A2AResponse, A2AError> {
+            let value = format!("Bearer {}", self.0).parse().unwrap();
+            call.headers.insert(http::header::AUTHORIZATION, value);
+            next.run(call).await
+        }
+    }
+
+    async fn client(transport: Transport, url: &str, token: &'static str) -> A2AClient {
+        let config = A2AClientConfig::new().with_interceptor(Bearer(token));
+        A2AClient::new_with_config(transport, url, config)
+            .await
+            .unwrap()
+    }
+
+    async fn send(client: &A2AClient, task_id: Option<&str>) -> Result<Task, A2AError> {
+        let mut message = Message::new_simple("hello there!");
+        message.task_id = task_id.map(str::to_string);
+        let res = client
+            .send_message(SendMessageRequest {
+                message: Some(message),
+                configuration: None,
+                metadata: None,
+            })
+            .await?;
+        match res.payload {
+            Some(SendMessageResponsePayload::Task(task)) => Ok(task),
+            _ => panic!("expected task"),
+        }
+    }
+
+    async fn get(client: &A2AClient, task_id: &str) -> Result<Task, A2AError> {
+        client
+            .get_task(GetTaskRequest {
+                id: task_id.to_string(),
+                history_length: None,
+                metadata: None,
+            })
+            .await
+    }
+
+    fn is_not_found(res: Result<Task, A2AError>) -> bool {
+        matches!(
+            res,
+            Err(A2AError::Protocol(A2AProtocolError::TaskNotFound { .. }))
+        )
+    }
+
+    /// Checks that bob can neither read nor continue the tasks of alice.
+    async fn assert_isolated(alice: &A2AClient, bob: &A2AClient) {
+        let task = send(alice, None).await.unwrap();
+        assert_eq!(get(alice, &task.id).await.unwrap().id, task.id);
+        assert_eq!(send(alice, Some(&task.id)).await.unwrap().id, task.id);
+
+        assert!(is_not_found(get(bob, &task.id).await));
+        assert!(is_not_found(send(bob, Some(&task.id)).await));
+    }
+
+    struct Caller {
+        http: reqwest::Client,
+        url: String,
+        token: &'static str,
+    }
+
+    impl Caller {
+        async fn call(&self, method: &str, params: Value) -> Value {
+            self.http
+                .post(&self.url)
+                .bearer_auth(self.token)
+                .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }))
+                .send()
+                .await
+                .unwrap()
+                .json()
+                .await
+                .unwrap()
+        }
+
+        async fn send(&self, task_id: Option<&str>) -> Value {
+            let mut message = json!({
+                "role": "user",
+                "parts": [{ "kind": "text", "text": "hello there!" }],
+                "messageId": uuid::Uuid::new_v4().to_string()
+            });
+            if let Some(task_id) = task_id {
+                message["taskId"] = json!(task_id);
+            }
+            self.call("message/send", json!({ "message": message }))
+                .await
+        }
+
+        async fn get(&self, task_id: &str) -> Value {
+            self.call("tasks/get", json!({ "id": task_id })).await
+        }
+    }
+
+    #[tokio::test]
+    async fn should_hide_tasks_from_other_principals() {
+        let agent = AgentBuilder::new(TestHandler)
+            .with_name("isolated")
+            .with_card(card())
+            .with_authenticator(authenticator())
+            .with_json_rpc_server("[::]:0".parse().unwrap())
+            .build()
+            .expect("failed to build agent");
+        let handle = agent.start_server().await.expect("failed to start server");
+        let url = format!(
+            "http://localhost:{}",
+            handle.local_addr(Transport::JsonRpc).unwrap().port()
+        );
+        let http = reqwest::Client::new();
+        let alice = Caller {
+            http: http.clone(),
+            url: url.clone(),
+            token: "alice-token",
+        };
+        let bob = Caller {
+            http,
+            url,
+            token: "bob-token",
+        };
+
+        let res = alice.send(None).await;
+        let task_id = res["result"]["payload"]["id"].as_str().unwrap().to_string();
+
+        let own = alice.get(&task_id).await;
+        assert_eq!(own["result"]["id"], task_id, "{own}");
+        let follow_up = alice.send(Some(&task_id)).await;
+        assert_eq!(follow_up["result"]["payload"]["id"], task_id, "{follow_up}");
+
+        // other principals cannot tell the task exists
+        let missing = bob.get("does-not-exist").await;
+        let foreign = bob.get(&task_id).await;
+        assert_eq!(foreign["error"]["code"], -32001, "{foreign}");
+        assert_eq!(foreign["error"]["code"], missing["error"]["code"]);
+        assert_eq!(foreign["error"]["message"], missing["error"]["message"]);
+        let foreign_follow_up = bob.send(Some(&task_id)).await;
+        assert_eq!(
+            foreign_follow_up["error"]["code"], -32001,
+            "{foreign_follow_up}"
+        );
+
+        handle.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_keep_the_owner_of_continued_tasks() {
+        let delegate = A2ADelegate::new(Arc::new(TestHandler));
+        let as_principal = |subject: Option<&str>| RequestContext {
+            principal: subject.map(Principal::new),
+            ..RequestContext::default()
+        };
+        let send_async = |context: RequestContext, task_id: Option<String>| {
+            let delegate = delegate.clone();
+            async move {
+                let mut message = Message::new_simple("hello there!");
+                message.task_id = task_id;
+                let request = SendMessageRequest {
+                    message: Some(message),
+                    configuration: Some(SendMessageConfiguration {
+                        accepted_output_modes: vec![],
+                        push_notification: None,
+                        history_length: 0,
+                        blocking: false,
+                    }),
+                    metadata: None,
+                };
+                match delegate.send_message_with_context(&context, request).await {
+                    Ok(res) => match res.payload {
+                        Some(SendMessageResponsePayload::Task(task)) => Ok(task),
+                        _ => panic!("expected task"),
+                    },
+                    Err(e) => Err(e),
+                }
+            }
+        };
+        let get = |context: RequestContext, task_id: String| {
+            let delegate = delegate.clone();
+            async move {
+                let request = GetTaskRequest {
+                    id: task_id,
+                    history_length: None,
+                    metadata: None,
+                };
+                delegate.get_task_with_context(&context, request).await
+            }
+        };
+
+        // a follow-up to an anonymous task leaves it open to everyone
+        let task = send_async(as_principal(None), None).await.unwrap();
+        send_async(as_principal(Some("bob")), Some(task.id.clone()))
+            .await
+            .unwrap();
+        get(as_principal(Some("alice")), task.id.clone())
+            .await
+            .unwrap();
+        get(as_principal(None), task.id.clone()).await.unwrap();
+
+        // a follow-up by its owner keeps the task private
+        let task = send_async(as_principal(Some("alice")), None).await.unwrap();
+        send_async(as_principal(Some("alice")), Some(task.id.clone()))
+            .await
+            .unwrap();
+        assert!(is_not_found(
+            get(as_principal(Some("bob")), task.id.clone()).await
+        ));
+        get(as_principal(Some("alice")), task.id).await.unwrap();
+    }
+
+    #[cfg(feature = "grpc")]
+    #[tokio::test]
+    async fn should_hide_tasks_over_grpc() {
+        let agent = AgentBuilder::new(TestHandler)
+            .with_name("isolated")
+            .with_card(card())
+            .with_authenticator(authenticator())
+            .with_grpc_server("[::]:0".parse().unwrap())
+            .build()
+            .expect("failed to build agent");
+        let handle = agent.start_server().await.expect("failed to start server");
+        let url = format!(
+            "http://localhost:{}",
+            handle.local_addr(Transport::Grpc).unwrap().port()
+        );
+
+        let alice = client(Transport::Grpc, &url, "alice-token").await;
+        let bob = client(Transport::Grpc, &url, "bob-token").await;
+        assert_isolated(&alice, &bob).await;
+
+        handle.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_hide_tasks_behind_a_gateway() {
+        let backend = AgentBuilder::new(TestHandler)
+            .with_name("backend")
+            .with_json_rpc_server("[::]:0".parse().unwrap())
+            .build()
+            .expect("failed to build agent");
+        let backend = backend
+            .start_server()
+            .await
+            .expect("failed to start server");
+        let replica = AgentReplica::connect(
+            Transport::JsonRpc,
+            format!(
+                "http://localhost:{}",
+                backend.local_addr(Transport::JsonRpc).unwrap().port()
+            ),
+        )
+        .await
+        .unwrap();
+        let broker = AgentBroker::new();
+        broker.register("backend", replica).await;
+        let gateway = A2AGateway::new(broker, card()).with_default_agent("backend");
+
+        let delegate = A2ADelegate::forwarding(Arc::new(gateway))
+            .with_authentication(Authentication::new(&card(), Arc::new(authenticator())));
+        let server = A2AServer::new(delegate).with_jsonrpc("[::]:0".parse().unwrap());
+        #[cfg(feature = "grpc")]
+        let server = server.with_grpc("[::]:0".parse().unwrap());
+        let handle = server.start().await.expect("failed to start gateway");
+
+        for (transport, addr) in handle.local_addrs() {
+            let url = format!("http://localhost:{}", addr.port());
+            let alice = client(transport, &url, "alice-token").await;
+            let bob = client(transport, &url, "bob-token").await;
+            assert_isolated(&alice, &bob).await;
+        }
+
+        handle.shutdown().await.unwrap();
+        backend.shutdown().await.unwrap();
+    }
+}
@@ -2,7 +2,7 @@
 #[cfg(feature = "agent")]
 mod task_polling {
     use async_trait::async_trait;
-    use ra2a::agent::{A2AAgentError, AgentBuilder, AgentHandler};
+    use ra2a::agent::{A2AAgentError, AgentBuilder, AgentHandler, RequestContext};
     use ra2a::client::A2AClient;
     use ra2a::core::message::{Message, SendMessageRequest, SendMessageResponsePayload};
     use ra2a::core::task::{GetTaskRequest, Task, TaskState, TaskStatus};
@@ -16,6 +16,7 @@ mod task_polling {
     impl AgentHandler for TestHandler {
         async fn handle_message(
             &self,
+            _context: &RequestContext,
             message: Message,
             _metadata: Option<Object>,
             mut task: Task,
@@ -0,0 +1,145 @@
+#[cfg(test)]
+#[cfg(feature = "agent")]
+mod task_watcher {
+    use async_trait::async_trait;
+    use ra2a::agent::{
+        AgentBuilder, AgentServerHandle, Middleware, Next, NoopAgentHandler, RequestContext,
+    };
+    use ra2a::client::A2AClient;
+    use ra2a::client::watch::{TaskWatcher, WaitError};
+    use ra2a::core::task::{Task, TaskState, TaskStatus};
+    use ra2a::core::{A2AError, A2ARequest, A2AResponse};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use tokio_util::sync::CancellationToken;
+
+    /// Answers task lookups with the next of `states`, repeating the last one.
+    #[derive(Debug)]
+    struct Progress {
+        states: Vec<TaskState>,
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Middleware for Progress {
+        async fn handle(
+            &self,
+            context: &RequestContext,
+            request: A2ARequest,
+            next: Next<'_>,
+        ) -> Result<A2AResponse, A2AError> {
+            let A2ARequest::GetTask(request) = &request else {
+                return next.run(context, request).await;
+            };
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            let state = self.states[call.min(self.states.len() - 1)];
+            let mut task = Task::new();
+            task.id = request.id.clone();
+            task.status = Some(TaskStatus {
+                state: state.into_i32(),
+                message: None,
+                timestamp: None,
+            });
+            Ok(A2AResponse::GetTask(task))
+        }
+    }
+
+    async fn start(states: Vec<TaskState>) -> (AgentServerHandle, A2AClient, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let agent = AgentBuilder::new(NoopAgentHandler)
+            .with_name("progressing")
+            .with_middleware(Progress {
+                states,
+                calls: calls.clone(),
+            })
+            .with_json_rpc_server("[::]:0".parse().unwrap())
+            .build()
+            .expect("failed to build agent");
+        let handle = agent.start_server().await.expect("failed to start server");
+        let (transport, addr) = handle.local_addrs()[0];
+        let client = A2AClient::new(transport, format!("http://localhost:{}", addr.port()))
+            .await
+            .expect("failed to create client");
+        (handle, client, calls)
+    }
+
+    fn watcher() -> TaskWatcher {
+        TaskWatcher::new().with_poll_interval(Duration::from_millis(10), Duration::from_millis(50))
+    }
+
+    fn state(task: &Task) -> TaskState {
+        TaskState::try_from(task.status.as_ref().unwrap().state).unwrap()
+    }
+
+    #[tokio::test]
+    async fn should_wait_for_terminal_state() {
+        let (handle, client, calls) = start(vec![
+            TaskState::Submitted,
+            TaskState::Working,
+            TaskState::Working,
+            TaskState::Completed,
+        ])
+        .await;
+        let seen = Arc::new(Mutex::new(vec![]));
+        let watcher = watcher().on_status({
+            let seen = seen.clone();
+            move |task| seen.lock().unwrap().push(state(task))
+        });
+
+        let task = client.wait_for_task("t1", &watcher).await.unwrap();
+        assert_eq!(task.id, "t1");
+        assert_eq!(state(&task), TaskState::Completed);
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![TaskState::Submitted, TaskState::Working]
+        );
+
+        handle.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_stop_at_interrupted_state() {
+        let (handle, client, _) = start(vec![TaskState::Working, TaskState::InputRequired]).await;
+
+        let task = client.wait_for_task("t1", &watcher()).await.unwrap();
+        assert_eq!(state(&task), TaskState::InputRequired);
+
+        handle.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_stop_when_authentication_is_required() {
+        let (handle, client, _) = start(vec![TaskState::Working, TaskState::AuthRequired]).await;
+
+        let task = client.wait_for_task("t1", &watcher()).await.unwrap();
+        assert_eq!(state(&task), TaskState::AuthRequired);
+
+        handle.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_give_up_at_deadline_or_cancellation() {
+        let (handle, client, _) = start(vec![TaskState::Working]).await;
+
+        let watcher = watcher().with_deadline(Duration::from_millis(200));
+        let e = client.wait_for_task("t1", &watcher).await.unwrap_err();
+        assert!(
+            matches!(e, WaitError::Deadline { ref id } if id == "t1"),
+            "{e:?}"
+        );
+
+        let cancellation = CancellationToken::new();
+        let watcher = watcher.with_deadline(Duration::from_secs(60));
+        let watcher = watcher.with_cancellation(cancellation.clone());
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            cancellation.cancel();
+        });
+        let e = client.wait_for_task("t1", &watcher).await.unwrap_err();
+        assert!(matches!(e, WaitError::Cancelled { .. }), "{e:?}");
+
+        handle.shutdown().await.unwrap();
+    }
+}
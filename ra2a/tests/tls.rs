@@ -0,0 +1,123 @@
+mod common;
+
+#[cfg(test)]
+#[cfg(feature = "agent")]
+mod tls {
+    use crate::common;
+
+    use ra2a::agent::AgentBuilder;
+    use ra2a::auth::ClientCertificateAuthenticator;
+    use ra2a::client::{A2AClient, A2AClientConfig};
+    use ra2a::core::A2A;
+    use ra2a::core::agent::AgentCard;
+    use ra2a::core::message::{Message, SendMessageRequest, SendMessageResponsePayload};
+    use ra2a::core::part::PartBase;
+
+    use ra2a::tls::{ClientTlsConfig, ServerTlsConfig};
+    use serde_json::json;
+
+    const CA: &str = include_str!("certs/ca.pem");
+    const SERVER_CERT: &str = include_str!("certs/server.pem");
+    const SERVER_KEY: &str = include_str!("certs/server.key");
+    const CLIENT_CERT: &str = include_str!("certs/client.pem");
+    const CLIENT_KEY: &str = include_str!("certs/client.key");
+
+    fn card() -> AgentCard {
+        common::card("secure")
+            .with_scheme("mtls", json!({ "type": "mutualTls" }))
+            .with_security(json!([{ "mtls": [] }]))
+            .build()
+    }
+
+    async fn who_am_i(client: &A2AClient) -> Result<String, ra2a::core::A2AError> {
+        let res = client
+            .send_message(SendMessageRequest {
+                message: Some(Message::new_simple("who am i?")),
+                configuration: None,
+                metadata: None,
+            })
+            .await?;
+        let Some(SendMessageResponsePayload::Message(message)) = res.payload else {
+            panic!("expected a message");
+        };
+        match &message.parts[0].part {
+            Some(PartBase::Text(text)) => Ok(text.clone()),
+            part => panic!("unexpected part {part:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn should_serve_over_tls() {
+        let tls = ServerTlsConfig::from_pem(SERVER_CERT, SERVER_KEY).unwrap();
+        let agent_builder = AgentBuilder::new(common::WhoAmIHandler)
+            .with_name("tls")
+            .with_tls(tls)
+            .with_json_rpc_server("127.0.0.1:0".parse().unwrap());
+        #[cfg(feature = "grpc")]
+        let agent_builder = agent_builder.with_grpc_server("127.0.0.1:0".parse().unwrap());
+        let agent = agent_builder.build().expect("failed to build agent");
+        let handle = agent.start_server().await.expect("failed to start server");
+
+        let config =
+            A2AClientConfig::new().with_tls(ClientTlsConfig::new().with_ca_pem(CA).unwrap());
+        for (transport, addr) in handle.local_addrs() {
+            let client = A2AClient::new_with_config(
+                transport,
+                format!("https://localhost:{}", addr.port()),
+                config.clone(),
+            )
+            .await
+            .unwrap();
+            assert_eq!(who_am_i(&client).await.unwrap(), "anonymous");
+        }
+        handle.shutdown().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn should_authenticate_client_certificates() {
+        let tls = ServerTlsConfig::from_pem(SERVER_CERT, SERVER_KEY)
+            .unwrap()
+            .with_client_ca_pem(CA)
+            .unwrap()
+            .with_client_auth_optional(true);
+        let agent_builder = AgentBuilder::new(common::WhoAmIHandler)
+            .with_name("mtls")
+            .with_card(card())
+            .with_authenticator(ClientCertificateAuthenticator)
+            .with_tls(tls)
+            .with_json_rpc_server("127.0.0.1:0".parse().unwrap());
+        #[cfg(feature = "grpc")]
+        let agent_builder = agent_builder.with_grpc_server("127.0.0.1:0".parse().unwrap());
+        let agent = agent_builder.build().expect("failed to build agent");
+        let handle = agent.start_server().await.expect("failed to start server");
+
+        let with_identity = ClientTlsConfig::new()
+            .with_ca_pem(CA)
+            .unwrap()
+            .with_identity_pem(CLIENT_CERT, CLIENT_KEY)
+            .unwrap();
+        let without_identity = ClientTlsConfig::new().with_ca_pem(CA).unwrap();
+        for (transport, addr) in handle.local_addrs() {
+            let url = format!("https://localhost:{}", addr.port());
+            let client = A2AClient::new_with_config(
+                transport,
+                &url,
+                A2AClientConfig::new().with_tls(with_identity.clone()),
+            )
+            .await
+            .unwrap();
+            assert_eq!(who_am_i(&client).await.unwrap(), "alice");
+
+            // the handshake succeeds without a certificate, the card requirement does not
+            let client = A2AClient::new_with_config(
+                transport,
+                &url,
+                A2AClientConfig::new().with_tls(without_identity.clone()),
+            )
+            .await
+            .unwrap();
+            assert!(who_am_i(&client).await.is_err(), "{transport:?}");
+        }
+        handle.shutdown().await.unwrap()
+    }
+}